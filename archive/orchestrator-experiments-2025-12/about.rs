@@ -0,0 +1,64 @@
+use chrono::{TimeZone, Utc};
+use serde::Serialize;
+
+use super::error::NimbusError;
+
+/// Which build of the app is running, for bug reports
+///
+/// Everything but `version` comes from `build.rs`; a field is None when the
+/// build script didn't set it, e.g. when building outside a git checkout.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BuildInfo {
+    /// `CARGO_PKG_VERSION`, e.g. `0.1.0`
+    pub version: String,
+    /// Full commit hash, with `-dirty` appended when there were local changes
+    pub git_commit: Option<String>,
+    /// When the binary was built, as RFC 3339 in UTC
+    pub build_timestamp: Option<String>,
+    /// reqwest version from `Cargo.lock`
+    pub reqwest_version: Option<String>,
+}
+
+/// Build info of the running binary, fixed at compile time
+pub fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_commit: option_env!("NIMBUS_GIT_COMMIT").map(str::to_string),
+        build_timestamp: option_env!("NIMBUS_BUILD_TIMESTAMP").and_then(format_timestamp),
+        reqwest_version: option_env!("NIMBUS_REQWEST_VERSION").map(str::to_string),
+    }
+}
+
+/// Formats the build script's Unix seconds; None if they don't parse
+fn format_timestamp(seconds: &str) -> Option<String> {
+    let seconds = seconds.trim().parse().ok()?;
+    Utc.timestamp_opt(seconds, 0)
+        .single()
+        .map(|time| time.to_rfc3339())
+}
+
+/// Returns the app version and build details for the About screen and bug reports
+///
+/// # Returns
+/// BuildInfo of the running binary. Never touches the network.
+#[tauri::command]
+pub async fn get_build_info() -> Result<BuildInfo, NimbusError> {
+    Ok(build_info())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_info_reports_the_package_version() {
+        let info = build_info();
+        assert_eq!(info.version, env!("CARGO_PKG_VERSION"));
+
+        assert_eq!(
+            format_timestamp("1767225600").as_deref(),
+            Some("2026-01-01T00:00:00+00:00")
+        );
+        assert_eq!(format_timestamp("yesterday"), None);
+    }
+}
@@ -0,0 +1,200 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::error::NimbusError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOperation {
+    Create,
+    Update,
+    Delete,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditOutcome {
+    Succeeded,
+    Failed,
+    /// Saved to the offline queue instead of being sent
+    Queued,
+}
+
+/// One line of the audit log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub operation: AuditOperation,
+    /// `LocationGroup` or `ScheduleGroup`
+    pub entity: String,
+    /// ID of the affected entity; None for a create that didn't produce one
+    pub target_id: Option<i64>,
+    /// Profile the command ran against
+    pub actor: Option<String>,
+    pub outcome: AuditOutcome,
+    pub error: Option<String>,
+}
+
+/// Append-only JSONL record of every mutation the app performs
+///
+/// Held in Tauri managed state. Appends are serialized through a mutex so
+/// concurrent batch items can't interleave partial lines.
+pub struct AuditLog {
+    path: PathBuf,
+    write_lock: Mutex<()>,
+}
+
+impl AuditLog {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Records the outcome of a mutation; `error` is None when it succeeded
+    ///
+    /// A failure to write is logged rather than returned: the mutation has
+    /// already happened and its result must still reach the caller.
+    pub fn record(
+        &self,
+        operation: AuditOperation,
+        entity: &str,
+        target_id: Option<i64>,
+        actor: Option<String>,
+        error: Option<&NimbusError>,
+    ) {
+        let outcome = match error {
+            None => AuditOutcome::Succeeded,
+            Some(_) => AuditOutcome::Failed,
+        };
+        self.append(AuditEntry {
+            timestamp: Utc::now(),
+            operation,
+            entity: entity.to_string(),
+            target_id,
+            actor,
+            outcome,
+            error: error.map(NimbusError::sanitized_message),
+        });
+    }
+
+    /// Records a create that was queued for later replay
+    pub fn record_queued(&self, entity: &str, actor: Option<String>) {
+        self.append(AuditEntry {
+            timestamp: Utc::now(),
+            operation: AuditOperation::Create,
+            entity: entity.to_string(),
+            target_id: None,
+            actor,
+            outcome: AuditOutcome::Queued,
+            error: None,
+        });
+    }
+
+    fn append(&self, entry: AuditEntry) {
+        let _guard = self.write_lock.lock().expect("audit lock poisoned");
+        let result = serde_json::to_string(&entry)
+            .map_err(std::io::Error::from)
+            .and_then(|line| {
+                if let Some(dir) = self.path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                writeln!(file, "{}", line)
+            });
+        if let Err(e) = result {
+            tracing::error!(path = %self.path.display(), error = %e, ?entry, "Failed to write audit entry");
+        }
+    }
+
+    /// The most recent `limit` entries, newest first
+    ///
+    /// Lines that fail to parse (e.g. a torn final write) are skipped.
+    pub fn read_recent(&self, limit: usize) -> Result<Vec<AuditEntry>, NimbusError> {
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(NimbusError::Config(format!(
+                    "Failed to read {}: {}",
+                    self.path.display(),
+                    e
+                )))
+            }
+        };
+
+        Ok(contents
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .take(limit)
+            .collect())
+    }
+}
+
+/// Returns the most recent audit log entries, newest first
+///
+/// # Arguments
+/// * `audit` - Audit log from managed state
+/// * `limit` - Maximum number of entries to return
+#[tauri::command]
+pub async fn read_audit_log(
+    audit: tauri::State<'_, AuditLog>,
+    limit: usize,
+) -> Result<Vec<AuditEntry>, NimbusError> {
+    audit.read_recent(limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::paths::AUDIT_FILE_NAME;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_concurrent_appends_stay_line_delimited() {
+        let path = std::env::temp_dir()
+            .join(uuid::Uuid::new_v4().to_string())
+            .join(AUDIT_FILE_NAME);
+        let audit = Arc::new(AuditLog::new(&path));
+
+        let handles: Vec<_> = (0..8)
+            .map(|id| {
+                let audit = audit.clone();
+                std::thread::spawn(move || {
+                    audit.record(
+                        AuditOperation::Delete,
+                        "LocationGroup",
+                        Some(id),
+                        Some("dev".to_string()),
+                        None,
+                    );
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        audit.record(
+            AuditOperation::Create,
+            "LocationGroup",
+            None,
+            None,
+            Some(&NimbusError::NotAuthenticated),
+        );
+
+        let entries = audit.read_recent(100).unwrap();
+        assert_eq!(entries.len(), 9);
+        assert_eq!(entries[0].outcome, AuditOutcome::Failed);
+        assert_eq!(audit.read_recent(3).unwrap().len(), 3);
+    }
+}
@@ -0,0 +1,1047 @@
+use std::collections::{HashMap, VecDeque};
+use std::future::Future;
+use std::sync::Mutex;
+
+use futures::stream::{self, StreamExt, TryStreamExt};
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use super::audit::{AuditLog, AuditOperation};
+use super::client::{new_idempotency_key, with_request_timeout, NimbusClient, RetryPolicy};
+use super::entities::{
+    all_pages, fetch_schedule_group_page, patch_schedule_dates, post_location_group,
+    remove_location_group, shift_date_range, LocationGroupRequest, ALL_PAGES_PAGE_SIZE,
+};
+use super::error::NimbusError;
+use super::profiles::ProfileStore;
+use super::session::{Session, Target};
+
+/// Requests kept in flight by the parallel batch commands unless overridden
+pub const DEFAULT_BATCH_CONCURRENCY: usize = 5;
+
+/// Finished batches whose retryable failures `retry_failed` can still re-run
+const RETAINED_BATCHES: usize = 20;
+
+/// Event emitted after each item of a LocationGroup batch
+pub const LOCATION_GROUP_PROGRESS_EVENT: &str = "location-group-progress";
+
+/// Event emitted once a batch has started, carrying the ID `cancel_batch` takes
+pub const BATCH_STARTED_EVENT: &str = "location-group-batch-started";
+
+/// Event emitted when a batch stops early because it was cancelled
+pub const BATCH_CANCELLED_EVENT: &str = "location-group-batch-cancelled";
+
+/// Event emitted last, once every item of a batch has been reported
+pub const BATCH_COMPLETED_EVENT: &str = "location-group-batch-completed";
+
+/// Payload of the started, cancelled and completed batch events
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchEvent {
+    pub batch_id: String,
+    /// Position among the batch's events; see `BatchSummary::final_seq`
+    pub seq: u64,
+    pub total: usize,
+    /// Items that were never sent; 0 in the started event
+    pub cancelled: usize,
+}
+
+/// Payload of `location-group-progress`
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchProgress {
+    pub batch_id: String,
+    /// Position among the batch's events; see `BatchSummary::final_seq`
+    pub seq: u64,
+    /// 0-based index into the submitted requests
+    pub index: usize,
+    pub total: usize,
+    pub location_group_id: Option<i64>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchSuccess {
+    pub index: usize,
+    pub location_group_id: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchFailure {
+    pub index: usize,
+    pub error: NimbusError,
+    /// Whether `retry_failed` will re-run this item; see `NimbusError::is_retryable`
+    pub retryable: bool,
+}
+
+/// One request of a batch and the idempotency key its create is sent with
+///
+/// The key is kept for retries, so a create that did reach Nimbus before
+/// failing is deduplicated rather than made twice.
+#[derive(Debug, Clone)]
+struct BatchItem {
+    /// Index into the originally submitted requests, also in retries
+    index: usize,
+    request: LocationGroupRequest,
+    idempotency_key: String,
+}
+
+impl BatchItem {
+    fn numbered(requests: Vec<LocationGroupRequest>) -> Vec<Self> {
+        requests
+            .into_iter()
+            .enumerate()
+            .map(|(index, request)| Self {
+                index,
+                request,
+                idempotency_key: new_idempotency_key(),
+            })
+            .collect()
+    }
+}
+
+/// Outcome of a whole batch, split into successes, failures, and cancelled items
+#[derive(Debug, Default, Serialize)]
+pub struct BatchSummary {
+    pub batch_id: String,
+    pub succeeded: Vec<BatchSuccess>,
+    pub failed: Vec<BatchFailure>,
+    /// Indices of items skipped because the batch was cancelled
+    pub cancelled: Vec<usize>,
+    /// `seq` of the completed event, the last one the batch emitted
+    ///
+    /// Tauri doesn't order events against the command's result, so the UI
+    /// may see this summary before the final events; it is done once it has
+    /// seen this number.
+    pub final_seq: u64,
+    /// Profile the batch was sent to, which a retry must be sent to as well
+    #[serde(skip)]
+    profile: String,
+    /// Items of the retryable failures, handed to the registry on finish
+    #[serde(skip)]
+    retry: Vec<BatchItem>,
+}
+
+impl BatchSummary {
+    fn new(batch_id: String, profile: &str) -> Self {
+        Self {
+            batch_id,
+            profile: profile.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Files one item's outcome; `None` means it was cancelled before being sent
+    fn record(
+        &mut self,
+        item: BatchItem,
+        outcome: Option<Result<i64, NimbusError>>,
+        policy: &RetryPolicy,
+    ) {
+        let index = item.index;
+        match outcome {
+            Some(Ok(location_group_id)) => self.succeeded.push(BatchSuccess {
+                index,
+                location_group_id,
+            }),
+            Some(Err(error)) => {
+                let retryable = error.is_retryable(policy);
+                if retryable {
+                    self.retry.push(item);
+                }
+                self.failed.push(BatchFailure {
+                    index,
+                    error,
+                    retryable,
+                });
+            }
+            None => self.cancelled.push(index),
+        }
+    }
+}
+
+/// Cancellation tokens of the batches currently running, held in managed state
+///
+/// Also keeps the retryable failures of the last `RETAINED_BATCHES` finished
+/// batches for `retry_failed`.
+#[derive(Debug, Default)]
+pub struct BatchRegistry {
+    batches: Mutex<HashMap<String, CancellationToken>>,
+    finished: Mutex<VecDeque<FinishedBatch>>,
+}
+
+/// Retryable failures of a finished batch and the profile they were sent to
+#[derive(Debug)]
+struct FinishedBatch {
+    batch_id: String,
+    profile: String,
+    items: Vec<BatchItem>,
+}
+
+impl BatchRegistry {
+    /// Registers a new batch, returning its ID and token
+    fn start(&self) -> (String, CancellationToken) {
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let token = CancellationToken::new();
+        self.batches
+            .lock()
+            .expect("batch registry lock poisoned")
+            .insert(batch_id.clone(), token.clone());
+        (batch_id, token)
+    }
+
+    /// Unregisters the batch and keeps its retryable failures for `retry_failed`
+    fn finish(&self, summary: &mut BatchSummary) {
+        self.batches
+            .lock()
+            .expect("batch registry lock poisoned")
+            .remove(&summary.batch_id);
+
+        let retry = std::mem::take(&mut summary.retry);
+        if retry.is_empty() {
+            return;
+        }
+        let mut finished = self.finished.lock().expect("batch registry lock poisoned");
+        if finished.len() >= RETAINED_BATCHES {
+            finished.pop_front();
+        }
+        finished.push_back(FinishedBatch {
+            batch_id: summary.batch_id.clone(),
+            profile: summary.profile.clone(),
+            items: retry,
+        });
+    }
+
+    /// Profile a finished batch with retryable failures was sent to
+    fn retry_profile(&self, batch_id: &str) -> Result<String, NimbusError> {
+        let finished = self.finished.lock().expect("batch registry lock poisoned");
+        finished
+            .iter()
+            .find(|batch| batch.batch_id == batch_id)
+            .map(|batch| batch.profile.clone())
+            .ok_or_else(|| no_retryable_failures(batch_id))
+    }
+
+    /// Removes and returns a finished batch's retryable items, in input order
+    fn take_retryable(&self, batch_id: &str) -> Result<Vec<BatchItem>, NimbusError> {
+        let mut finished = self.finished.lock().expect("batch registry lock poisoned");
+        let position = finished
+            .iter()
+            .position(|batch| batch.batch_id == batch_id)
+            .ok_or_else(|| no_retryable_failures(batch_id))?;
+        let mut items = finished
+            .remove(position)
+            .expect("position is in range")
+            .items;
+        items.sort_by_key(|item| item.index);
+        Ok(items)
+    }
+
+    fn cancel(&self, batch_id: &str) -> Result<(), NimbusError> {
+        let batches = self.batches.lock().expect("batch registry lock poisoned");
+        let token = batches
+            .get(batch_id)
+            .ok_or_else(|| NimbusError::Validation(format!("Unknown batch '{}'", batch_id)))?;
+        token.cancel();
+        Ok(())
+    }
+}
+
+fn no_retryable_failures(batch_id: &str) -> NimbusError {
+    NimbusError::Validation(format!(
+        "Batch '{}' has no retryable failures to retry",
+        batch_id
+    ))
+}
+
+/// Receiver of batch events: the calling window, or a recorder in tests
+trait BatchEventSink: Sync {
+    fn emit_batch(&self, event: &'static str, payload: BatchEvent);
+    fn emit_progress(&self, payload: BatchProgress);
+}
+
+impl BatchEventSink for tauri::Window {
+    // A closed window shouldn't abort the remaining creates
+    fn emit_batch(&self, event: &'static str, payload: BatchEvent) {
+        let _ = self.emit(event, payload);
+    }
+
+    fn emit_progress(&self, payload: BatchProgress) {
+        let _ = self.emit(LOCATION_GROUP_PROGRESS_EVENT, payload);
+    }
+}
+
+/// Emits one batch's events, numbering them from 1 in the order they are sent
+struct BatchEvents<'a> {
+    sink: &'a dyn BatchEventSink,
+    batch_id: String,
+    total: usize,
+    /// Last `seq` sent; held while emitting so numbers never arrive out of order
+    seq: Mutex<u64>,
+}
+
+impl<'a> BatchEvents<'a> {
+    /// Emits the started event, which reports the batch's ID to the caller
+    fn start(sink: &'a dyn BatchEventSink, batch_id: &str, total: usize) -> Self {
+        let events = Self {
+            sink,
+            batch_id: batch_id.to_string(),
+            total,
+            seq: Mutex::new(0),
+        };
+        events.emit_batch(BATCH_STARTED_EVENT, 0);
+        events
+    }
+
+    /// Emits the progress event of one sent item
+    fn progress(&self, index: usize, result: &Result<i64, NimbusError>) {
+        let mut seq = self.seq.lock().expect("batch events lock poisoned");
+        *seq += 1;
+        self.sink.emit_progress(BatchProgress {
+            batch_id: self.batch_id.clone(),
+            seq: *seq,
+            index,
+            total: self.total,
+            location_group_id: result.as_ref().ok().copied(),
+            error: result.as_ref().err().map(NimbusError::sanitized_message),
+        });
+    }
+
+    /// Emits the cancelled event if any items were skipped, then the completed one
+    fn finish(&self, summary: &mut BatchSummary) {
+        let cancelled = summary.cancelled.len();
+        if cancelled > 0 {
+            self.emit_batch(BATCH_CANCELLED_EVENT, cancelled);
+        }
+        summary.final_seq = self.emit_batch(BATCH_COMPLETED_EVENT, cancelled);
+    }
+
+    fn emit_batch(&self, event: &'static str, cancelled: usize) -> u64 {
+        let mut seq = self.seq.lock().expect("batch events lock poisoned");
+        *seq += 1;
+        self.sink.emit_batch(
+            event,
+            BatchEvent {
+                batch_id: self.batch_id.clone(),
+                seq: *seq,
+                total: self.total,
+                cancelled,
+            },
+        );
+        *seq
+    }
+}
+
+/// Creates several LocationGroups, reporting progress as it goes
+///
+/// # Arguments
+/// * `window` - Calling window, which receives the batch events
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `batches` - Registry the batch's cancellation token is stored in
+/// * `audit` - Audit log each create is recorded in
+/// * `requests` - LocationGroupRequests to create, in order
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// BatchSummary of every item. A failed item does not stop the batch; after
+/// `cancel_batch` the remaining items are listed in `cancelled`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_location_groups_batch(
+    window: tauri::Window,
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    batches: tauri::State<'_, BatchRegistry>,
+    audit: tauri::State<'_, AuditLog>,
+    requests: Vec<LocationGroupRequest>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<BatchSummary, NimbusError> {
+    // Resolve the target environment and credentials
+    let target = session.target(&profiles, profile.as_deref())?;
+
+    let items = BatchItem::numbered(requests);
+    let create = create_in_order(&window, &client, &target, &batches, &audit, items);
+    Ok(with_request_timeout(timeout_ms, create).await)
+}
+
+/// Re-runs the retryable failures of a finished batch, as a new batch
+///
+/// Each item is sent again with its original idempotency key, so a create that
+/// reached Nimbus the first time is not duplicated. The retry goes to the
+/// profile the batch was sent to, whichever profile is active now.
+///
+/// # Arguments
+/// * `window` - Calling window, which receives the batch events
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `batches` - Registry the earlier batch's failures are kept in
+/// * `audit` - Audit log each create is recorded in
+/// * `batch_id` - ID of the finished batch, from its BatchSummary
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+///
+/// # Returns
+/// BatchSummary of the retry under a new `batch_id`, with the indices of the
+/// original batch. Its own retryable failures can be retried in turn. A
+/// validation error if the batch is unknown, had no retryable failures, or
+/// was already retried.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn retry_failed(
+    window: tauri::Window,
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    batches: tauri::State<'_, BatchRegistry>,
+    audit: tauri::State<'_, AuditLog>,
+    batch_id: String,
+    timeout_ms: Option<u64>,
+) -> Result<BatchSummary, NimbusError> {
+    // Resolve the environment and credentials the batch was sent with
+    let profile = batches.retry_profile(&batch_id)?;
+    let target = session.target(&profiles, Some(&profile))?;
+
+    let items = batches.take_retryable(&batch_id)?;
+    tracing::info!(%batch_id, %profile, items = items.len(), "Retrying failed batch items");
+    let create = create_in_order(&window, &client, &target, &batches, &audit, items);
+    Ok(with_request_timeout(timeout_ms, create).await)
+}
+
+/// Creates `items` one after another as a new batch, emitting the batch events
+async fn create_in_order(
+    sink: &dyn BatchEventSink,
+    client: &NimbusClient,
+    target: &Target,
+    batches: &BatchRegistry,
+    audit: &AuditLog,
+    items: Vec<BatchItem>,
+) -> BatchSummary {
+    let (batch_id, cancel) = batches.start();
+    let events = BatchEvents::start(sink, &batch_id, items.len());
+    let mut summary = BatchSummary::new(batch_id, &target.profile);
+    let actor = Some(target.profile.clone());
+
+    for item in items {
+        if cancel.is_cancelled() {
+            summary.record(item, None, client.retry_policy());
+            continue;
+        }
+
+        let result = post_location_group(
+            client,
+            &target.base_url,
+            &target.token,
+            &item.request,
+            &item.idempotency_key,
+        )
+        .await;
+        record_create(audit, &actor, &result);
+        events.progress(item.index, &result);
+        summary.record(item, Some(result), client.retry_policy());
+    }
+
+    batches.finish(&mut summary);
+    events.finish(&mut summary);
+    summary
+}
+
+/// Creates several LocationGroups concurrently, with a bound on in-flight requests
+///
+/// # Arguments
+/// * `window` - Calling window, which receives the batch events
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `batches` - Registry the batch's cancellation token is stored in
+/// * `audit` - Audit log each create is recorded in
+/// * `requests` - LocationGroupRequests to create
+/// * `concurrency` - Maximum requests in flight (default 5)
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// BatchSummary with every list sorted by input index. Progress events arrive
+/// in completion order, not input order. A failed item does not cancel the
+/// others; after `cancel_batch`, in-flight creates finish and the rest are
+/// listed in `cancelled`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_location_groups_parallel(
+    window: tauri::Window,
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    batches: tauri::State<'_, BatchRegistry>,
+    audit: tauri::State<'_, AuditLog>,
+    requests: Vec<LocationGroupRequest>,
+    concurrency: Option<usize>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<BatchSummary, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let total = requests.len();
+    let limit = concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+    let client = client.inner();
+    let (audit, actor) = (audit.inner(), Some(profile.clone()));
+    let (batch_id, cancel) = batches.start();
+    let events = BatchEvents::start(&window, &batch_id, total);
+
+    let creates = run_bounded(BatchItem::numbered(requests), limit, |_, item| {
+        let (base_url, token, events) = (&base_url, &token, &events);
+        let (cancel, actor) = (&cancel, &actor);
+        async move {
+            // Items are only started as slots free up, so this skips everything not yet sent
+            if cancel.is_cancelled() {
+                return (item, None);
+            }
+
+            let result = post_location_group(
+                client,
+                base_url,
+                token,
+                &item.request,
+                &item.idempotency_key,
+            )
+            .await;
+            record_create(audit, actor, &result);
+            events.progress(item.index, &result);
+            (item, Some(result))
+        }
+    });
+    let results = with_request_timeout(timeout_ms, creates).await;
+
+    let mut summary = BatchSummary::new(batch_id, &profile);
+    for (item, outcome) in results {
+        summary.record(item, outcome, client.retry_policy());
+    }
+    batches.finish(&mut summary);
+    events.finish(&mut summary);
+    Ok(summary)
+}
+
+/// Deletes several LocationGroups concurrently, with a bound on in-flight requests
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log each deletion is recorded in
+/// * `ids` - LocationGroupIDs to delete
+/// * `concurrency` - Maximum requests in flight (default 5)
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// Each ID paired with its outcome, in input order. A failed delete does not
+/// stop the others.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn delete_location_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    ids: Vec<i64>,
+    concurrency: Option<usize>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<Vec<(i64, Result<(), NimbusError>)>, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let limit = concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+    let deletes = delete_bounded(&client, &base_url, &token, ids, limit);
+    let results = with_request_timeout(timeout_ms, deletes).await;
+
+    let actor = Some(profile);
+    for (id, result) in &results {
+        audit.record(
+            AuditOperation::Delete,
+            "LocationGroup",
+            Some(*id),
+            actor.clone(),
+            result.as_ref().err(),
+        );
+    }
+    Ok(results)
+}
+
+async fn delete_bounded(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    ids: Vec<i64>,
+    limit: usize,
+) -> Vec<(i64, Result<(), NimbusError>)> {
+    run_bounded(ids, limit, |_, id| async move {
+        (id, remove_location_group(client, base_url, token, id).await)
+    })
+    .await
+}
+
+/// One ScheduleGroup's outcome in `shift_schedules_for_location_group`
+#[derive(Debug, Serialize)]
+pub struct ScheduleShift {
+    pub schedule_group_id: i64,
+    /// GroupStartDate/GroupEndDate before the shift
+    pub old_start_date: String,
+    pub old_end_date: String,
+    pub new_start_date: String,
+    pub new_end_date: String,
+    /// Why the update failed; None when the schedule was moved
+    pub error: Option<NimbusError>,
+}
+
+/// Moves the dates of every ScheduleGroup under a LocationGroup by the same number of days
+///
+/// For term rollover: both GroupStartDate and GroupEndDate of each schedule
+/// move by `day_offset`, through the same PATCH as `patch_schedule_group_dates`.
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log each update is recorded in
+/// * `location_group_id` - LocationGroup whose ScheduleGroups are shifted
+/// * `day_offset` - Days to move the dates by; negative moves them earlier
+/// * `concurrency` - Maximum requests in flight (default 5)
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// One ScheduleShift per ScheduleGroup, in list order. Every shifted date is
+/// checked before anything is sent, so an offset that would make any date
+/// invalid fails with a validation error and changes nothing. A failed update
+/// does not stop the others.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn shift_schedules_for_location_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    location_group_id: i64,
+    day_offset: i64,
+    concurrency: Option<usize>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<Vec<ScheduleShift>, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let limit = concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+    let shift = shift_schedules(
+        &client,
+        &base_url,
+        &token,
+        location_group_id,
+        day_offset,
+        limit,
+    );
+    let shifts = with_request_timeout(timeout_ms, shift).await?;
+
+    let actor = Some(profile);
+    for shift in &shifts {
+        audit.record(
+            AuditOperation::Update,
+            "ScheduleGroup",
+            Some(shift.schedule_group_id),
+            actor.clone(),
+            shift.error.as_ref(),
+        );
+    }
+    Ok(shifts)
+}
+
+/// Lists the LocationGroup's ScheduleGroups and PATCHes each with shifted dates
+pub(crate) async fn shift_schedules(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+    day_offset: i64,
+    limit: usize,
+) -> Result<Vec<ScheduleShift>, NimbusError> {
+    if day_offset == 0 {
+        return Err(NimbusError::Validation(
+            "day_offset must not be 0".to_string(),
+        ));
+    }
+
+    let schedules: Vec<_> = all_pages(|page| {
+        fetch_schedule_group_page(
+            client,
+            base_url,
+            token,
+            Some(location_group_id),
+            page,
+            ALL_PAGES_PAGE_SIZE,
+        )
+    })
+    .try_collect()
+    .await?;
+
+    // Shift every range before sending, so one bad date leaves all schedules untouched
+    let mut shifts = Vec::with_capacity(schedules.len());
+    let mut problems = Vec::new();
+    for schedule in schedules {
+        match shift_date_range(&schedule.start_date, &schedule.end_date, day_offset) {
+            Ok((new_start_date, new_end_date)) => shifts.push(ScheduleShift {
+                schedule_group_id: schedule.schedule_group_id,
+                old_start_date: schedule.start_date,
+                old_end_date: schedule.end_date,
+                new_start_date,
+                new_end_date,
+                error: None,
+            }),
+            Err(NimbusError::Validation(reason)) => problems.push(format!(
+                "ScheduleGroup {}: {}",
+                schedule.schedule_group_id, reason
+            )),
+            Err(e) => return Err(e),
+        }
+    }
+    if !problems.is_empty() {
+        return Err(NimbusError::Validation(problems.join("; ")));
+    }
+
+    tracing::info!(
+        location_group_id,
+        day_offset,
+        schedules = shifts.len(),
+        "Shifting schedule dates"
+    );
+    let shifts = run_bounded(shifts, limit, |_, mut shift| async move {
+        let result = patch_schedule_dates(
+            client,
+            base_url,
+            token,
+            shift.schedule_group_id,
+            &shift.new_start_date,
+            &shift.new_end_date,
+        )
+        .await;
+        shift.error = result.err();
+        shift
+    })
+    .await;
+    Ok(shifts)
+}
+
+/// Stops a running batch after its in-flight requests
+///
+/// # Arguments
+/// * `batches` - Registry of running batches
+/// * `batch_id` - ID from the `location-group-batch-started` event
+///
+/// # Returns
+/// A validation error if no batch with that ID is running
+#[tauri::command]
+pub async fn cancel_batch(
+    batches: tauri::State<'_, BatchRegistry>,
+    batch_id: String,
+) -> Result<(), NimbusError> {
+    batches.cancel(&batch_id)
+}
+
+/// Records one batch create in the audit log
+pub(crate) fn record_create(
+    audit: &AuditLog,
+    actor: &Option<String>,
+    result: &Result<i64, NimbusError>,
+) {
+    audit.record(
+        AuditOperation::Create,
+        "LocationGroup",
+        result.as_ref().ok().copied(),
+        actor.clone(),
+        result.as_ref().err(),
+    );
+}
+
+/// Runs `task` over `items` with at most `limit` futures in flight
+///
+/// Results come back in input order regardless of completion order. A `limit`
+/// of 0 is treated as 1.
+pub(crate) async fn run_bounded<I, T, F, Fut>(items: Vec<I>, limit: usize, task: F) -> Vec<T>
+where
+    F: Fn(usize, I) -> Fut,
+    Fut: Future<Output = T>,
+{
+    let mut results: Vec<(usize, T)> = stream::iter(items.into_iter().enumerate())
+        .map(|(index, item)| {
+            let fut = task(index, item);
+            async move { (index, fut.await) }
+        })
+        .buffer_unordered(limit.max(1))
+        .collect()
+        .await;
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_run_bounded_preserves_order_and_limit() {
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        // Later items finish first, so completion order is the reverse of input order
+        let results = run_bounded((0..10u64).collect(), 3, |index, item| {
+            let (in_flight, peak) = (&in_flight, &peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                tokio::time::sleep(Duration::from_millis(20 - item * 2)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                if index == 4 {
+                    Err(index)
+                } else {
+                    Ok(index)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(results.len(), 10);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(*result, if i == 4 { Err(i) } else { Ok(i) });
+        }
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+    }
+
+    #[test]
+    fn test_cancel_unknown_and_finished_batches() {
+        let registry = BatchRegistry::default();
+        let (batch_id, token) = registry.start();
+
+        registry.cancel(&batch_id).unwrap();
+        assert!(token.is_cancelled());
+
+        registry.finish(&mut BatchSummary::new(batch_id.clone(), "tenant-a"));
+        assert!(registry.cancel(&batch_id).is_err());
+        assert!(registry.cancel("no-such-batch").is_err());
+    }
+
+    #[test]
+    fn test_only_retryable_failures_are_kept_for_retry() {
+        let registry = BatchRegistry::default();
+        let (batch_id, _) = registry.start();
+        let request = |description: &str| LocationGroupRequest {
+            description: description.to_string(),
+            location_ids: vec![1],
+            active: true,
+        };
+        let mut items = BatchItem::numbered(vec![
+            request("ok"),
+            request("bad"),
+            request("busy"),
+            request("skipped"),
+        ])
+        .into_iter();
+
+        let policy = RetryPolicy::default();
+        let mut summary = BatchSummary::new(batch_id.clone(), "tenant-a");
+        summary.record(items.next().unwrap(), Some(Ok(7)), &policy);
+        summary.record(
+            items.next().unwrap(),
+            Some(Err(NimbusError::from_body(400, "Bad request"))),
+            &policy,
+        );
+        let busy = items.next().unwrap();
+        let busy_key = busy.idempotency_key.clone();
+        summary.record(
+            busy,
+            Some(Err(NimbusError::from_body(503, "busy"))),
+            &policy,
+        );
+        summary.record(items.next().unwrap(), None, &policy);
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["failed"][0]["retryable"], false);
+        assert_eq!(json["failed"][1]["retryable"], true);
+        assert!(json.get("retry").is_none());
+
+        registry.finish(&mut summary);
+        // The retry goes where the batch went, not to the active profile
+        assert_eq!(registry.retry_profile(&batch_id).unwrap(), "tenant-a");
+        let retry = registry.take_retryable(&batch_id).unwrap();
+        assert_eq!(retry.len(), 1);
+        assert_eq!(retry[0].index, 2);
+        assert_eq!(retry[0].request.description, "busy");
+        assert_eq!(retry[0].idempotency_key, busy_key);
+
+        // Each batch can be retried once
+        assert!(registry.take_retryable(&batch_id).is_err());
+        assert!(registry.retry_profile(&batch_id).is_err());
+    }
+
+    /// Keeps the name and `seq` of every event sent
+    #[derive(Default)]
+    struct RecordedEvents(Mutex<Vec<(&'static str, u64)>>);
+
+    impl BatchEventSink for RecordedEvents {
+        fn emit_batch(&self, event: &'static str, payload: BatchEvent) {
+            self.0.lock().unwrap().push((event, payload.seq));
+        }
+
+        fn emit_progress(&self, payload: BatchProgress) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((LOCATION_GROUP_PROGRESS_EVENT, payload.seq));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_batch_events_are_numbered_and_completed_last() {
+        let mock = MockExecutor::new();
+        mock.respond(201, r#"{"LocationGroupID": 7}"#)
+            .respond(400, r#"{"Message": "Bad location"}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+        let audit_path = std::env::temp_dir()
+            .join(uuid::Uuid::new_v4().to_string())
+            .join("audit.jsonl");
+        let audit = AuditLog::new(&audit_path);
+        let requests = ["West", "East"]
+            .map(|description| LocationGroupRequest {
+                description: description.to_string(),
+                location_ids: vec![1],
+                active: true,
+            })
+            .to_vec();
+
+        let events = RecordedEvents::default();
+        let target = Target {
+            profile: "tenant-a".to_string(),
+            base_url: "https://nimbus.test".to_string(),
+            token: "tok".to_string(),
+        };
+        let summary = create_in_order(
+            &events,
+            &client,
+            &target,
+            &BatchRegistry::default(),
+            &audit,
+            BatchItem::numbered(requests),
+        )
+        .await;
+
+        assert_eq!(
+            *events.0.lock().unwrap(),
+            vec![
+                (BATCH_STARTED_EVENT, 1),
+                (LOCATION_GROUP_PROGRESS_EVENT, 2),
+                (LOCATION_GROUP_PROGRESS_EVENT, 3),
+                (BATCH_COMPLETED_EVENT, 4),
+            ]
+        );
+        assert_eq!(summary.final_seq, 4);
+        assert_eq!((summary.succeeded.len(), summary.failed.len()), (1, 1));
+    }
+
+    #[tokio::test]
+    async fn test_delete_bounded_reports_each_id() {
+        let mock = MockExecutor::new();
+        mock.respond(204, "")
+            .respond(409, r#"{"Message": "In use"}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        // Limit 1 so the stub responses are consumed in input order
+        let results = delete_bounded(&client, "https://nimbus.test", "tok", vec![1, 2], 1).await;
+        assert_eq!(results[0].0, 1);
+        assert!(results[0].1.is_ok());
+        assert_eq!(results[1].0, 2);
+        assert_eq!(results[1].1.as_ref().unwrap_err().status(), Some(409));
+
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json[1][1]["Err"]["kind"], "http");
+    }
+
+    fn schedule(id: i64, start_date: &str, end_date: &str) -> serde_json::Value {
+        serde_json::json!({
+            "ScheduleGroupID": id,
+            "Description": "Term",
+            "LocationGroupID": 5,
+            "GroupStartDate": start_date,
+            "GroupEndDate": end_date,
+            "Active": true,
+            "AdhocFields": []
+        })
+    }
+
+    #[tokio::test]
+    async fn test_shift_schedules_patches_each_schedule() {
+        let mock = MockExecutor::new();
+        let listed = serde_json::json!([
+            schedule(1, "2025-01-27", "2025-06-30"),
+            schedule(2, "2025-07-14", "2025-12-12"),
+        ]);
+        mock.respond(200, &listed.to_string())
+            .respond(200, &schedule(1, "2026-01-26", "2026-06-29").to_string())
+            .respond(404, "");
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let shifts = shift_schedules(&client, "https://nimbus.test", "tok", 5, 364, 1)
+            .await
+            .unwrap();
+        assert_eq!(shifts.len(), 2);
+        assert_eq!(shifts[0].new_start_date, "2026-01-26");
+        assert_eq!(shifts[0].new_end_date, "2026-06-29");
+        assert!(shifts[0].error.is_none());
+        assert_eq!(shifts[1].old_start_date, "2025-07-14");
+        assert!(matches!(
+            shifts[1].error,
+            Some(NimbusError::NotFound { id: 2, .. })
+        ));
+
+        let requests = mock.requests();
+        assert!(requests[0].url.contains("LocationGroupID=5"));
+        assert_eq!(
+            requests[1].body,
+            Some(serde_json::json!({"GroupStartDate": "2026-01-26", "GroupEndDate": "2026-06-29"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_shift_schedules_sends_nothing_if_a_date_would_be_invalid() {
+        let mock = MockExecutor::new();
+        let listed = serde_json::json!([
+            schedule(1, "2025-01-27", "2025-06-30"),
+            schedule(2, "9999-06-01", "9999-12-01"),
+        ]);
+        mock.respond(200, &listed.to_string());
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let err = shift_schedules(&client, "https://nimbus.test", "tok", 5, 365, 1)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("ScheduleGroup 2"), "{}", err);
+        assert_eq!(mock.requests().len(), 1);
+    }
+}
@@ -0,0 +1,192 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// When the shared client stops sending to a failing server
+///
+/// `failure_threshold` consecutive failed calls, all within `window` of the
+/// first, open the circuit for `cooldown`. A threshold of 0 disables it.
+#[derive(Debug, Clone)]
+pub struct CircuitBreakerConfig {
+    pub failure_threshold: u32,
+    pub window: Duration,
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            window: Duration::from_secs(30),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    /// Calls go through; `failures` consecutive ones have failed since `first_failure`
+    Closed {
+        failures: u32,
+        first_failure: Option<Instant>,
+    },
+    /// Calls fail fast until `until`
+    Open { until: Instant },
+    /// One trial call, started at `trial_started`, decides whether to close again
+    HalfOpen { trial_started: Instant },
+}
+
+const CLOSED: State = State::Closed {
+    failures: 0,
+    first_failure: None,
+};
+
+/// Consecutive-failure circuit breaker used by `NimbusClient::send`
+///
+/// Every method takes `now` so tests can drive the transitions without sleeping.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    state: Mutex<State>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config,
+            state: Mutex::new(CLOSED),
+        }
+    }
+
+    /// Asks to send a call; `Err` holds how long until the next trial is allowed
+    ///
+    /// Once the cooldown has passed, exactly one caller gets through as the
+    /// trial. Should that trial never report back (e.g. its command was
+    /// dropped), another is let through after a further cooldown.
+    pub fn try_acquire(&self, now: Instant) -> Result<(), Duration> {
+        let mut state = self.state.lock().expect("breaker lock poisoned");
+        match *state {
+            State::Closed { .. } => Ok(()),
+            State::Open { until } if now < until => Err(until - now),
+            State::HalfOpen { trial_started } if now < trial_started + self.config.cooldown => {
+                Err(trial_started + self.config.cooldown - now)
+            }
+            State::Open { .. } | State::HalfOpen { .. } => {
+                *state = State::HalfOpen { trial_started: now };
+                Ok(())
+            }
+        }
+    }
+
+    pub fn record_success(&self) {
+        *self.state.lock().expect("breaker lock poisoned") = CLOSED;
+    }
+
+    pub fn record_failure(&self, now: Instant) {
+        if self.config.failure_threshold == 0 {
+            return;
+        }
+
+        let mut state = self.state.lock().expect("breaker lock poisoned");
+        let (failures, first_failure) = match *state {
+            State::Closed {
+                failures,
+                first_failure: Some(first),
+            } if now.duration_since(first) <= self.config.window => (failures + 1, first),
+            State::Closed { .. } => (1, now),
+            // The trial failed, so the server is still down
+            State::HalfOpen { .. } => (self.config.failure_threshold, now),
+            State::Open { .. } => return,
+        };
+
+        *state = if failures >= self.config.failure_threshold {
+            tracing::warn!(
+                failures,
+                cooldown = ?self.config.cooldown,
+                "Nimbus keeps failing, pausing requests"
+            );
+            State::Open {
+                until: now + self.config.cooldown,
+            }
+        } else {
+            State::Closed {
+                failures,
+                first_failure: Some(first_failure),
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: 3,
+            window: Duration::from_secs(10),
+            cooldown: Duration::from_secs(5),
+        })
+    }
+
+    #[test]
+    fn test_opens_after_threshold_and_half_opens_after_cooldown() {
+        let breaker = breaker();
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            assert!(breaker.try_acquire(start).is_ok());
+            breaker.record_failure(start);
+        }
+        assert_eq!(breaker.try_acquire(start), Err(Duration::from_secs(5)));
+
+        // Cooldown over: one trial goes through, the rest keep failing fast
+        let later = start + Duration::from_secs(5);
+        assert!(breaker.try_acquire(later).is_ok());
+        assert!(breaker.try_acquire(later).is_err());
+
+        // A failed trial reopens straight away
+        breaker.record_failure(later);
+        assert!(breaker.try_acquire(later + Duration::from_secs(1)).is_err());
+
+        let trial = later + Duration::from_secs(5);
+        assert!(breaker.try_acquire(trial).is_ok());
+        breaker.record_success();
+        assert!(breaker.try_acquire(trial).is_ok());
+        assert!(breaker.try_acquire(trial).is_ok());
+    }
+
+    #[test]
+    fn test_failures_must_be_consecutive_and_within_window() {
+        let breaker = breaker();
+        let start = Instant::now();
+
+        breaker.record_failure(start);
+        breaker.record_failure(start);
+        breaker.record_success();
+        breaker.record_failure(start);
+        assert!(breaker.try_acquire(start).is_ok());
+
+        // Spread out beyond the window, so the count starts over
+        breaker.record_failure(start + Duration::from_secs(11));
+        breaker.record_failure(start + Duration::from_secs(12));
+        assert!(breaker.try_acquire(start + Duration::from_secs(12)).is_ok());
+        breaker.record_failure(start + Duration::from_secs(13));
+        assert!(breaker
+            .try_acquire(start + Duration::from_secs(13))
+            .is_err());
+    }
+
+    #[test]
+    fn test_abandoned_trial_lets_another_through() {
+        let breaker = breaker();
+        let start = Instant::now();
+        for _ in 0..3 {
+            breaker.record_failure(start);
+        }
+
+        let trial = start + Duration::from_secs(5);
+        assert!(breaker.try_acquire(trial).is_ok());
+        assert!(breaker.try_acquire(trial + Duration::from_secs(4)).is_err());
+        assert!(breaker.try_acquire(trial + Duration::from_secs(5)).is_ok());
+    }
+}
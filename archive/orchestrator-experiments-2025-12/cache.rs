@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use super::client::NimbusClient;
+use super::error::NimbusError;
+
+/// Response bodies Nimbus is asked to revalidate instead of resending
+///
+/// `NimbusRequest::cached` GETs send the stored ETag as `If-None-Match`, and a
+/// 304 answer is served with the body read alongside that ETag. Holds at most `capacity` URLs, evicting the
+/// least recently used; a capacity of 0 disables caching.
+#[derive(Debug)]
+pub struct EtagCache {
+    capacity: usize,
+    inner: Mutex<CacheState>,
+}
+
+#[derive(Debug, Default)]
+struct CacheState {
+    entries: HashMap<String, CachedResponse>,
+    /// Bumped on every access; the lowest `last_used` is evicted first
+    clock: u64,
+}
+
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    etag: String,
+    body: String,
+    last_used: u64,
+}
+
+impl EtagCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            inner: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// The ETag and body stored for `url`, marking it recently used
+    ///
+    /// Both come from the same entry under one lock, so a 304 for that ETag
+    /// can be answered with this body even if the entry is evicted meanwhile.
+    pub fn get(&self, url: &str) -> Option<(String, String)> {
+        let mut state = self.inner.lock().expect("cache lock poisoned");
+        state.clock += 1;
+        let clock = state.clock;
+        let entry = state.entries.get_mut(url)?;
+        entry.last_used = clock;
+        Some((entry.etag.clone(), entry.body.clone()))
+    }
+
+    /// Stores a fresh response, evicting the least recently used entry if full
+    pub fn store(&self, url: &str, etag: &str, body: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut state = self.inner.lock().expect("cache lock poisoned");
+        state.clock += 1;
+        let last_used = state.clock;
+        if !state.entries.contains_key(url) && state.entries.len() >= self.capacity {
+            let oldest = state
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(url, _)| url.clone());
+            if let Some(oldest) = oldest {
+                state.entries.remove(&oldest);
+            }
+        }
+        state.entries.insert(
+            url.to_string(),
+            CachedResponse {
+                etag: etag.to_string(),
+                body: body.to_string(),
+                last_used,
+            },
+        );
+    }
+
+    pub fn remove(&self, url: &str) {
+        let mut state = self.inner.lock().expect("cache lock poisoned");
+        state.entries.remove(url);
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .expect("cache lock poisoned")
+            .entries
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.inner.lock().expect("cache lock poisoned");
+        state.entries.clear();
+    }
+}
+
+/// Drops every cached response, so the next reads download in full
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+#[tauri::command]
+pub async fn clear_cache(client: tauri::State<'_, NimbusClient>) -> Result<(), NimbusError> {
+    client.etag_cache().clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = EtagCache::new(2);
+        cache.store("/a", "\"1\"", "A");
+        cache.store("/b", "\"2\"", "B");
+
+        // Reading /a makes /b the oldest
+        assert_eq!(cache.get("/a").unwrap().1, "A");
+        cache.store("/c", "\"3\"", "C");
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get("/b").is_none());
+        assert_eq!(
+            cache.get("/a"),
+            Some(("\"1\"".to_string(), "A".to_string()))
+        );
+
+        cache.clear();
+        assert!(cache.is_empty());
+
+        let disabled = EtagCache::new(0);
+        disabled.store("/a", "\"1\"", "A");
+        assert!(disabled.is_empty());
+    }
+}
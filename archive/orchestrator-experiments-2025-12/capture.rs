@@ -0,0 +1,122 @@
+use std::path::{Path, PathBuf};
+
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::RequestBuilder;
+use serde_json::{json, Map, Value};
+
+/// Request and response headers that carry credentials and are never written out
+const REDACTED_HEADERS: [&str; 5] = [
+    "authorization",
+    "authenticationtoken",
+    "proxy-authorization",
+    "cookie",
+    "set-cookie",
+];
+
+/// One request/response pair being written to the debug capture directory
+///
+/// Both files share a timestamp prefix, e.g. `20250101T120000.123456Z-request.json`
+/// and `20250101T120000.123456Z-response.json`. Write failures are logged and
+/// otherwise ignored; capture must never break the request it observes.
+pub(crate) struct Capture {
+    dir: PathBuf,
+    stamp: String,
+}
+
+impl Capture {
+    pub(crate) fn start(dir: &Path) -> Self {
+        Self {
+            dir: dir.to_path_buf(),
+            stamp: Utc::now().format("%Y%m%dT%H%M%S%.6fZ").to_string(),
+        }
+    }
+
+    /// Writes the outgoing request, with token headers redacted
+    pub(crate) fn request(&self, request: &RequestBuilder) {
+        let Some(request) = request.try_clone().and_then(|r| r.build().ok()) else {
+            return;
+        };
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .and_then(|bytes| serde_json::from_slice::<Value>(bytes).ok());
+
+        let captured = json!({
+            "method": request.method().as_str(),
+            "url": request.url().as_str(),
+            "headers": redact_headers(request.headers()),
+            "body": body,
+        });
+        self.write("request", &captured);
+    }
+
+    /// Writes the raw response body, as JSON when it parses and as a string otherwise
+    pub(crate) fn response(&self, status: u16, body: &str) {
+        let body = serde_json::from_str(body).unwrap_or_else(|_| Value::String(body.to_string()));
+        self.write("response", &json!({ "status": status, "body": body }));
+    }
+
+    fn write(&self, kind: &str, contents: &Value) {
+        let path = self.dir.join(format!("{}-{}.json", self.stamp, kind));
+        let result = std::fs::create_dir_all(&self.dir).and_then(|_| {
+            let text = serde_json::to_string_pretty(contents).unwrap_or_default();
+            std::fs::write(&path, text)
+        });
+        if let Err(e) = result {
+            tracing::warn!(path = %path.display(), error = %e, "Failed to write debug capture");
+        }
+    }
+}
+
+/// Headers as a JSON object, with credential-bearing values replaced
+pub(crate) fn redact_headers(headers: &HeaderMap) -> Map<String, Value> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                "<redacted>".to_string()
+            } else {
+                value.to_str().unwrap_or("<binary>").to_string()
+            };
+            (name.to_string(), Value::String(value))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::build_nimbus_headers;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_capture_writes_redacted_pair() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let capture = Capture::start(&dir);
+        let headers = build_nimbus_headers("secret-token", &HashMap::new(), true, None).unwrap();
+
+        let request = reqwest::Client::new()
+            .post("https://nimbus.test/RESTApi/LocationGroup")
+            .headers(headers)
+            .json(&json!({"Description": "West Coast"}));
+        capture.request(&request);
+        capture.response(201, r#"{"LocationGroupID": 7}"#);
+
+        let mut files: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        assert_eq!(files.len(), 2);
+
+        let request = std::fs::read_to_string(&files[0]).unwrap();
+        assert!(files[0].to_string_lossy().ends_with("-request.json"));
+        assert!(!request.contains("secret-token"));
+        assert!(request.contains("West Coast"));
+
+        let response: Value =
+            serde_json::from_str(&std::fs::read_to_string(&files[1]).unwrap()).unwrap();
+        assert_eq!(response["body"]["LocationGroupID"], 7);
+    }
+}
@@ -0,0 +1,1346 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, ACCEPT_LANGUAGE, RETRY_AFTER, USER_AGENT,
+};
+use reqwest::{
+    Certificate, ClientBuilder, NoProxy, Proxy, Request, RequestBuilder, Response, StatusCode,
+};
+
+use super::breaker::{CircuitBreaker, CircuitBreakerConfig};
+use super::cache::EtagCache;
+use super::capture::Capture;
+use super::demo::DemoExecutor;
+use super::error::NimbusError;
+use super::executor::{HttpExecutor, ReqwestExecutor};
+use super::inflight::{ActiveRequests, InFlight};
+use super::metrics::{endpoint_key, Metrics};
+use super::ratelimit::{RateLimitConfig, TokenBucket};
+use super::request::PayloadEncoding;
+use super::session::TokenRefresher;
+
+/// Header Nimbus uses to deduplicate replayed creates
+pub const IDEMPOTENCY_KEY_HEADER: &str = "Idempotency-Key";
+
+/// Header carrying the client-generated ID that ties one request to its logs and errors
+pub const CORRELATION_ID_HEADER: &str = "X-Correlation-Id";
+
+/// Generates a fresh idempotency key for a create request
+pub fn new_idempotency_key() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// The caller's idempotency key, or a fresh one when None
+///
+/// A key that can't be sent as a header value (e.g. one with a newline) is a
+/// `Validation` error up front. Otherwise it would only fail once the request
+/// is built, as a network error, and a queued create could never be replayed.
+pub fn resolve_idempotency_key(key: Option<String>) -> Result<String, NimbusError> {
+    let Some(key) = key else {
+        return Ok(new_idempotency_key());
+    };
+    if key.is_empty() || HeaderValue::from_str(&key).is_err() {
+        return Err(NimbusError::Validation(format!(
+            "idempotency_key must be a non-empty header value without control characters, got {:?}",
+            key
+        )));
+    }
+    Ok(key)
+}
+
+/// User-Agent sent unless `NimbusConfig::user_agent` overrides it, e.g. `claude-family/0.1.0`
+pub fn default_user_agent() -> String {
+    format!("claude-family/{}", env!("CARGO_PKG_VERSION"))
+}
+
+/// Default for `NimbusConfig::pool_idle_timeout`
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+
+/// Default for `NimbusConfig::pool_max_idle_per_host`
+const POOL_MAX_IDLE_PER_HOST: usize = 8;
+
+/// Default for `NimbusConfig::max_description_length`
+pub const DEFAULT_MAX_DESCRIPTION_LENGTH: usize = 255;
+
+tokio::task_local! {
+    /// Per-command override of `NimbusConfig::request_timeout`, set by `with_request_timeout`
+    static REQUEST_TIMEOUT: Duration;
+}
+
+/// Runs `fut` with every request it sends given `timeout_ms` instead of the configured timeout
+///
+/// Applied through `RequestBuilder::timeout`, so it replaces the overall
+/// per-attempt request timeout only; `connect_timeout` stays as configured.
+/// With `None` the configured default is used.
+pub async fn with_request_timeout<F: Future>(timeout_ms: Option<u64>, fut: F) -> F::Output {
+    match timeout_ms {
+        Some(ms) => REQUEST_TIMEOUT.scope(Duration::from_millis(ms), fut).await,
+        None => fut.await,
+    }
+}
+
+/// Applies the `with_request_timeout` override in scope, if any
+fn apply_request_timeout(request: RequestBuilder) -> RequestBuilder {
+    match REQUEST_TIMEOUT.try_with(|timeout| *timeout) {
+        Ok(timeout) => request.timeout(timeout),
+        Err(_) => request,
+    }
+}
+
+/// Retry settings for transient Nimbus failures
+///
+/// Attempt `n` (1-based) that fails is followed by a random delay between zero
+/// and `base_delay * 2^(n-1)` (100ms, 200ms, 400ms with the defaults). The
+/// "full jitter" keeps several clients retrying against a recovering server
+/// from colliding on every attempt.
+///
+/// `budget` caps the wall-clock time of one `send`, whatever `max_attempts`
+/// allows: a retry whose wait would end past it is not made, and the last
+/// response or error is returned instead.
+///
+/// `retryable_statuses` lists the response codes worth another attempt. 502
+/// and 504 can be returned by a gateway after the upstream already processed
+/// the request, so they are only retried for idempotent calls; any other
+/// listed code, e.g. 408 or 425 from a gateway that refused the request, is
+/// retried for every call. A listed 429 waits for `Retry-After`.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; 1 disables retries
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    /// Longest time from the first attempt to starting a retry; unlimited when None
+    pub budget: Option<Duration>,
+    /// Seeds the jitter RNG for reproducible delays; random when None
+    pub jitter_seed: Option<u64>,
+    /// Status codes that are retried; 429, 502, 503 and 504 by default
+    ///
+    /// Leaving out 429 also turns off the `Retry-After` handling: a 429 is
+    /// then returned as-is and ends up as `NimbusError::Http` with status 429,
+    /// never `NimbusError::RateLimited`.
+    pub retryable_statuses: Vec<u16>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(100),
+            budget: Some(Duration::from_secs(60)),
+            jitter_seed: None,
+            retryable_statuses: vec![429, 502, 503, 504],
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Upper bound of the delay after the given failed attempt (1-based)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay * 2u32.saturating_pow(attempt.saturating_sub(1))
+    }
+
+    /// Delay to wait after the given failed attempt: uniform in `0..=backoff(attempt)`
+    pub fn jittered_backoff(&self, attempt: u32, rng: &mut impl Rng) -> Duration {
+        let cap = u64::try_from(self.backoff(attempt).as_nanos()).unwrap_or(u64::MAX);
+        Duration::from_nanos(rng.gen_range(0..=cap))
+    }
+
+    /// Whether a retry starting `elapsed` after the first attempt fits the budget
+    pub fn within_budget(&self, elapsed: Duration) -> bool {
+        match self.budget {
+            Some(budget) => elapsed <= budget,
+            None => true,
+        }
+    }
+
+    /// Whether a response with `status` is worth retrying
+    pub fn retries_status(&self, status: StatusCode, idempotent: bool) -> bool {
+        if !self.retryable_statuses.contains(&status.as_u16()) {
+            return false;
+        }
+        match status {
+            StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT => idempotent,
+            _ => true,
+        }
+    }
+
+    fn jitter_rng(&self) -> StdRng {
+        match self.jitter_seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        }
+    }
+}
+
+/// Source of the current time for retry budgets
+///
+/// `SystemClock` outside tests; a fake lets tests spend the budget without sleeping.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+}
+
+/// Reads `Instant::now`
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Longest `Retry-After` we will sleep through before giving up with `RateLimited`
+///
+/// Beyond this the command returns immediately so the UI can show a countdown
+/// instead of appearing hung.
+const MAX_RETRY_AFTER_WAIT: Duration = Duration::from_secs(30);
+
+/// Parses a `Retry-After` value: delay-seconds or an HTTP-date
+///
+/// A date in the past yields zero.
+fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some(
+        (date.with_timezone(&Utc) - now)
+            .to_std()
+            .unwrap_or(Duration::ZERO),
+    )
+}
+
+/// Whether a transport error is worth retrying
+///
+/// A connect failure means nothing reached the server. Any other error (e.g. a
+/// timeout mid-response) may have been processed, so only idempotent calls retry.
+fn should_retry_error(error: &reqwest::Error, idempotent: bool) -> bool {
+    error.is_connect() || (idempotent && error.is_timeout())
+}
+
+/// How the shared client reaches Nimbus through a proxy
+#[derive(Clone, Default)]
+pub enum ProxyConfig {
+    /// Honour `HTTP_PROXY` / `HTTPS_PROXY` / `NO_PROXY` from the environment
+    #[default]
+    Environment,
+    /// Send every request through `url`, optionally with basic auth
+    Custom {
+        url: String,
+        username: Option<String>,
+        password: Option<String>,
+        /// Comma-separated hosts to reach directly, e.g. `localhost,127.0.0.1`
+        no_proxy: Option<String>,
+    },
+    /// Always connect directly, ignoring any environment proxy
+    Disabled,
+}
+
+impl fmt::Debug for ProxyConfig {
+    // Hand-written so the proxy password never ends up in logs
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ProxyConfig::Environment => f.write_str("Environment"),
+            ProxyConfig::Custom {
+                url,
+                username,
+                password,
+                no_proxy,
+            } => f
+                .debug_struct("Custom")
+                .field("url", url)
+                .field("username", username)
+                .field("password", &password.as_ref().map(|_| "<redacted>"))
+                .field("no_proxy", no_proxy)
+                .finish(),
+            ProxyConfig::Disabled => f.write_str("Disabled"),
+        }
+    }
+}
+
+/// Applies the proxy settings to the client builder
+fn apply_proxy(builder: ClientBuilder, proxy: &ProxyConfig) -> Result<ClientBuilder, String> {
+    match proxy {
+        // reqwest reads the environment variables itself
+        ProxyConfig::Environment => Ok(builder),
+        ProxyConfig::Custom {
+            url,
+            username,
+            password,
+            no_proxy,
+        } => {
+            let mut proxy =
+                Proxy::all(url).map_err(|e| format!("Invalid proxy URL '{}': {}", url, e))?;
+            if let Some(username) = username {
+                proxy = proxy.basic_auth(username, password.as_deref().unwrap_or(""));
+            }
+            proxy = proxy.no_proxy(no_proxy.as_deref().and_then(NoProxy::from_string));
+            Ok(builder.proxy(proxy))
+        }
+        ProxyConfig::Disabled => Ok(builder.no_proxy()),
+    }
+}
+
+/// Applies the CA bundle and certificate-validation settings to the client builder
+fn apply_tls(builder: ClientBuilder, config: &NimbusConfig) -> Result<ClientBuilder, String> {
+    let mut builder = builder;
+
+    if let Some(path) = &config.ca_bundle_path {
+        let pem = std::fs::read(path)
+            .map_err(|e| format!("Failed to read CA bundle {}: {}", path.display(), e))?;
+        let certificates = Certificate::from_pem_bundle(&pem)
+            .map_err(|e| format!("Invalid CA bundle {}: {}", path.display(), e))?;
+        if certificates.is_empty() {
+            return Err(format!("CA bundle {} has no certificates", path.display()));
+        }
+        for certificate in certificates {
+            builder = builder.add_root_certificate(certificate);
+        }
+    }
+
+    if config.danger_accept_invalid_certs {
+        tracing::warn!(
+            "TLS certificate validation is DISABLED for Nimbus requests. \
+             Any server can impersonate Nimbus; never enable this outside development."
+        );
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+
+    Ok(builder)
+}
+
+/// Startup configuration for the shared Nimbus client
+#[derive(Debug, Clone)]
+pub struct NimbusConfig {
+    /// Overall deadline for a single request, from send to full body
+    pub request_timeout: Duration,
+    /// Deadline for establishing the TCP/TLS connection
+    pub connect_timeout: Duration,
+    /// Idle connections kept open per Nimbus host for reuse (default 8).
+    /// Raise it for parallel batches so each worker keeps its socket
+    pub pool_max_idle_per_host: usize,
+    /// How long an idle pooled connection is kept before being closed (default 90s)
+    pub pool_idle_timeout: Duration,
+    pub retry: RetryPolicy,
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// Send `Authorization: Bearer <token>` alongside `AuthenticationToken`
+    pub send_bearer_auth: bool,
+    /// Sent on every request after the standard headers, e.g. `X-Tenant-Id`
+    pub extra_headers: HashMap<String, String>,
+    pub proxy: ProxyConfig,
+    /// PEM file of extra root certificates, e.g. an internal CA for staging
+    pub ca_bundle_path: Option<PathBuf>,
+    /// Skip certificate validation entirely. Development only; logs a warning
+    pub danger_accept_invalid_certs: bool,
+    /// Write every create's payload and raw response to `debug_capture_dir`
+    pub debug_capture: bool,
+    pub debug_capture_dir: PathBuf,
+    /// Identifies the app in Nimbus server logs
+    pub user_agent: String,
+    /// Sent as `Accept-Language`, e.g. `de-DE`, for localized error messages
+    pub accept_language: Option<String>,
+    /// Most GET responses kept for `If-None-Match` revalidation; 0 disables the cache
+    pub etag_cache_capacity: usize,
+    /// Largest response body read before failing with `ResponseTooLarge`; None for no limit
+    pub max_response_bytes: Option<u64>,
+    /// Longest description, in characters, the create and update commands accept; 0 for no limit
+    pub max_description_length: usize,
+    /// Allow the `call_rest` command to reach arbitrary endpoints; turn off in locked-down deployments
+    pub allow_call_rest: bool,
+    /// Answer every request from an in-memory `DemoExecutor` instead of Nimbus,
+    /// for demos and UI work without credentials
+    pub demo_mode: bool,
+    /// Caps the rate requests are sent at, retries included; unlimited when None
+    pub rate_limit: Option<RateLimitConfig>,
+    /// How the create commands encode their body; JSON unless a Nimbus variant wants forms
+    pub payload_encoding: PayloadEncoding,
+}
+
+impl Default for NimbusConfig {
+    fn default() -> Self {
+        Self {
+            request_timeout: Duration::from_secs(30),
+            connect_timeout: Duration::from_secs(10),
+            pool_max_idle_per_host: POOL_MAX_IDLE_PER_HOST,
+            pool_idle_timeout: POOL_IDLE_TIMEOUT,
+            retry: RetryPolicy::default(),
+            circuit_breaker: CircuitBreakerConfig::default(),
+            send_bearer_auth: true,
+            extra_headers: HashMap::new(),
+            proxy: ProxyConfig::default(),
+            ca_bundle_path: None,
+            danger_accept_invalid_certs: false,
+            debug_capture: false,
+            debug_capture_dir: std::env::temp_dir().join("nimbus-capture"),
+            user_agent: default_user_agent(),
+            accept_language: None,
+            etag_cache_capacity: 256,
+            max_response_bytes: Some(50 * 1024 * 1024),
+            max_description_length: DEFAULT_MAX_DESCRIPTION_LENGTH,
+            allow_call_rest: true,
+            demo_mode: false,
+            rate_limit: None,
+            payload_encoding: PayloadEncoding::Json,
+        }
+    }
+}
+
+/// Shared HTTP client for Nimbus REST calls
+///
+/// Built once at startup and registered with `tauri::Builder::manage`, so every
+/// command reuses the same connection pool instead of paying for a fresh TLS
+/// handshake per call.
+pub struct NimbusClient {
+    /// Used to build requests; sending goes through `executor`
+    http: reqwest::Client,
+    executor: Arc<dyn HttpExecutor>,
+    retry: RetryPolicy,
+    clock: Arc<dyn Clock>,
+    jitter: Mutex<StdRng>,
+    breaker: CircuitBreaker,
+    rate_limit: Option<TokenBucket>,
+    extra_headers: HashMap<String, String>,
+    send_bearer_auth: bool,
+    metrics: Metrics,
+    etag_cache: EtagCache,
+    in_flight: InFlight,
+    active_requests: ActiveRequests,
+    max_response_bytes: Option<u64>,
+    max_description_length: usize,
+    allow_call_rest: bool,
+    payload_encoding: PayloadEncoding,
+    /// Asked for a new token when a request gets a 401
+    token_refresher: Option<Arc<dyn TokenRefresher>>,
+    /// Set when debug capture is enabled
+    capture_dir: Option<PathBuf>,
+    user_agent: String,
+    accept_language: Option<String>,
+}
+
+impl NimbusClient {
+    /// Builds the shared client with pooling and response decompression enabled
+    ///
+    /// With `demo_mode` on, requests go to a seeded `DemoExecutor` and never
+    /// reach the network.
+    pub fn new(config: NimbusConfig) -> Result<Self, String> {
+        let builder = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .connect_timeout(config.connect_timeout)
+            .pool_idle_timeout(config.pool_idle_timeout)
+            .pool_max_idle_per_host(config.pool_max_idle_per_host)
+            // Sends `Accept-Encoding: gzip, br, deflate` and decodes the body transparently
+            .gzip(true)
+            .brotli(true)
+            .deflate(true);
+        let builder = apply_proxy(builder, &config.proxy)?;
+        let http = apply_tls(builder, &config)?
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client: {}", e))?;
+
+        let executor: Arc<dyn HttpExecutor> = if config.demo_mode {
+            tracing::info!("Demo mode: Nimbus requests are answered in memory");
+            Arc::new(DemoExecutor::seeded())
+        } else {
+            Arc::new(ReqwestExecutor::new(http.clone()))
+        };
+        Ok(Self::from_parts(http, executor, config))
+    }
+
+    /// Builds a client whose requests are sent by `executor`, e.g. a mock in tests
+    ///
+    /// The config's timeouts only apply if the executor honours them.
+    pub fn with_executor(config: NimbusConfig, executor: Arc<dyn HttpExecutor>) -> Self {
+        Self::from_parts(reqwest::Client::new(), executor, config)
+    }
+
+    /// Replaces the time source the retry budget and rate limit are measured with
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        // The bucket was seeded from the old clock; its instants mean nothing to the new one
+        if let Some(bucket) = &self.rate_limit {
+            bucket.reset(clock.now());
+        }
+        self.clock = clock;
+        self
+    }
+
+    fn from_parts(
+        http: reqwest::Client,
+        executor: Arc<dyn HttpExecutor>,
+        config: NimbusConfig,
+    ) -> Self {
+        if !config.retry.retryable_statuses.contains(&429) {
+            tracing::warn!(
+                "429 is not in retryable_statuses; rate-limited requests fail with an HTTP error instead of RateLimited"
+            );
+        }
+        let clock: Arc<dyn Clock> = Arc::new(SystemClock);
+        Self {
+            http,
+            executor,
+            jitter: Mutex::new(config.retry.jitter_rng()),
+            breaker: CircuitBreaker::new(config.circuit_breaker),
+            rate_limit: config
+                .rate_limit
+                .map(|limit| TokenBucket::new(&limit, clock.now())),
+            clock,
+            retry: config.retry,
+            extra_headers: config.extra_headers,
+            send_bearer_auth: config.send_bearer_auth,
+            metrics: Metrics::default(),
+            etag_cache: EtagCache::new(config.etag_cache_capacity),
+            in_flight: InFlight::default(),
+            active_requests: ActiveRequests::default(),
+            max_response_bytes: config.max_response_bytes,
+            max_description_length: config.max_description_length,
+            allow_call_rest: config.allow_call_rest,
+            payload_encoding: config.payload_encoding,
+            token_refresher: None,
+            capture_dir: config.debug_capture.then_some(config.debug_capture_dir),
+            user_agent: config.user_agent,
+            accept_language: config.accept_language,
+        }
+    }
+
+    /// Retries requests that get a 401 once, with a token from `refresher`
+    ///
+    /// Built into the client in `setup`, e.g. with a `SessionRefresher`.
+    pub fn with_token_refresher(mut self, refresher: Arc<dyn TokenRefresher>) -> Self {
+        self.token_refresher = Some(refresher);
+        self
+    }
+
+    pub(crate) fn token_refresher(&self) -> Option<&dyn TokenRefresher> {
+        self.token_refresher.as_deref()
+    }
+
+    /// `reqwest::Client` to build requests with; pass them to `send` to execute
+    pub fn http(&self) -> &reqwest::Client {
+        &self.http
+    }
+
+    /// Per-endpoint call stats recorded by `send`
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// ETag-validated GET responses, used by `NimbusRequest::cached`
+    pub fn etag_cache(&self) -> &EtagCache {
+        &self.etag_cache
+    }
+
+    /// Retry policy the client sends with, which also decides `NimbusError::is_retryable`
+    pub fn retry_policy(&self) -> &RetryPolicy {
+        &self.retry
+    }
+
+    /// Description limit the request validators are given
+    pub fn max_description_length(&self) -> usize {
+        self.max_description_length
+    }
+
+    /// Whether `call_rest` may be used
+    pub fn allow_call_rest(&self) -> bool {
+        self.allow_call_rest
+    }
+
+    /// Body encoding for the create commands
+    pub fn payload_encoding(&self) -> PayloadEncoding {
+        self.payload_encoding
+    }
+
+    /// Requests still being sent or read, waited on at shutdown
+    pub fn active_requests(&self) -> &ActiveRequests {
+        &self.active_requests
+    }
+
+    /// Body size limit `NimbusRequest` enforces while reading responses
+    pub fn max_response_bytes(&self) -> Option<u64> {
+        self.max_response_bytes
+    }
+
+    /// GETs being sent right now, which identical concurrent GETs wait on
+    pub fn in_flight(&self) -> &InFlight {
+        &self.in_flight
+    }
+
+    /// A new capture for one request/response pair, if debug capture is enabled
+    pub(crate) fn capture(&self) -> Option<Capture> {
+        self.capture_dir.as_deref().map(Capture::start)
+    }
+
+    /// Standard Nimbus headers for `token`, the User-Agent, and the configured extra headers
+    ///
+    /// A `User-Agent` in `extra_headers` wins over `NimbusConfig::user_agent`.
+    pub fn headers(&self, token: &str) -> Result<HeaderMap, NimbusError> {
+        let mut headers = build_nimbus_headers(
+            token,
+            &self.extra_headers,
+            self.send_bearer_auth,
+            self.accept_language.as_deref(),
+        )?;
+        if !headers.contains_key(USER_AGENT) {
+            let user_agent = HeaderValue::from_str(&self.user_agent)
+                .map_err(|e| NimbusError::InvalidHeader(format!("user agent: {}", e)))?;
+            headers.insert(USER_AGENT, user_agent);
+        }
+        Ok(headers)
+    }
+
+    /// Sends a request, retrying transient failures with jittered exponential backoff
+    ///
+    /// `idempotent` must be false for requests that could create duplicates if
+    /// replayed (plain POSTs); those are only retried when the server provably
+    /// did not process them. The final response is returned as-is, so callers
+    /// still handle non-success statuses themselves.
+    ///
+    /// A 429 waits for the server's `Retry-After` (or the backoff when absent)
+    /// and ends in `NimbusError::RateLimited` once attempts run out.
+    ///
+    /// No retry starts past `RetryPolicy::budget`; the last response or error
+    /// is returned then, even with attempts left.
+    ///
+    /// Every call is recorded in `metrics()`, with retries counted as one call.
+    /// Each attempt first waits for `NimbusConfig::rate_limit`, if set, so
+    /// batches slow down to it on their own.
+    ///
+    /// Calls that end in a network error, timeout or 5xx feed the circuit
+    /// breaker; while it is open, `send` fails fast with
+    /// `NimbusError::CircuitOpen` without touching the network.
+    pub async fn send(
+        &self,
+        request: RequestBuilder,
+        idempotent: bool,
+    ) -> Result<Response, NimbusError> {
+        let request = apply_request_timeout(request).build()?;
+        let endpoint = endpoint_key(request.method(), request.url().path());
+        self.breaker
+            .try_acquire(Instant::now())
+            .map_err(|retry_after| NimbusError::CircuitOpen { retry_after })?;
+
+        let started = Instant::now();
+        let result = self.send_with_retry(request, idempotent).await;
+        self.record(&endpoint, &result, started);
+        self.record_breaker(&result);
+
+        result
+    }
+
+    /// Sends a request exactly once, bypassing the retry policy and the circuit breaker
+    ///
+    /// The rate limit still applies.
+    pub async fn send_once(&self, request: RequestBuilder) -> Result<Response, NimbusError> {
+        let request = apply_request_timeout(request).build()?;
+        let endpoint = endpoint_key(request.method(), request.url().path());
+
+        let started = Instant::now();
+        let result = self.execute(request).await.map_err(NimbusError::from);
+        self.record(&endpoint, &result, started);
+
+        result
+    }
+
+    async fn send_with_retry(
+        &self,
+        request: Request,
+        idempotent: bool,
+    ) -> Result<Response, NimbusError> {
+        let started = self.clock.now();
+        let mut attempt = 1;
+        loop {
+            // Buffered bodies (all `NimbusRequest` ones) are cloneable; streams get one shot
+            let Some(this_attempt) = request.try_clone() else {
+                return Ok(self.execute(request).await?);
+            };
+            let last_attempt = attempt >= self.retry.max_attempts;
+
+            let wait = match self.execute(this_attempt).await {
+                // 429 means the request was refused, so it is safe to replay either way
+                Ok(response)
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS
+                        && self.retry.retries_status(response.status(), idempotent) =>
+                {
+                    let retry_after = response
+                        .headers()
+                        .get(RETRY_AFTER)
+                        .and_then(|v| v.to_str().ok())
+                        .and_then(|v| parse_retry_after(v, Utc::now()))
+                        .unwrap_or_else(|| self.backoff(attempt));
+                    if last_attempt
+                        || retry_after > MAX_RETRY_AFTER_WAIT
+                        || !self.retry_fits(started, retry_after)
+                    {
+                        return Err(NimbusError::RateLimited { retry_after });
+                    }
+                    retry_after
+                }
+                Ok(response)
+                    if !last_attempt
+                        && self.retry.retries_status(response.status(), idempotent) =>
+                {
+                    let wait = self.backoff(attempt);
+                    if !self.retry_fits(started, wait) {
+                        return Ok(response);
+                    }
+                    wait
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if !last_attempt && should_retry_error(&e, idempotent) => {
+                    let wait = self.backoff(attempt);
+                    if !self.retry_fits(started, wait) {
+                        return Err(e.into());
+                    }
+                    wait
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
+    /// Sends one attempt, first waiting for the rate limit if one is configured
+    async fn execute(&self, request: Request) -> Result<Response, reqwest::Error> {
+        if let Some(bucket) = &self.rate_limit {
+            let wait = bucket.reserve(self.clock.now());
+            if !wait.is_zero() {
+                tracing::debug!(?wait, "Waiting for the client-side rate limit");
+                tokio::time::sleep(wait).await;
+            }
+        }
+        self.executor.execute(request).await
+    }
+
+    /// Whether a retry after waiting `wait` would still start within the budget
+    fn retry_fits(&self, started: Instant, wait: Duration) -> bool {
+        let elapsed = self.clock.now().saturating_duration_since(started);
+        self.retry.within_budget(elapsed + wait)
+    }
+
+    fn backoff(&self, attempt: u32) -> Duration {
+        let mut rng = self.jitter.lock().expect("jitter lock poisoned");
+        self.retry.jittered_backoff(attempt, &mut *rng)
+    }
+
+    fn record_breaker(&self, result: &Result<Response, NimbusError>) {
+        match result {
+            Ok(response) if response.status().is_server_error() => {
+                self.breaker.record_failure(Instant::now())
+            }
+            Err(NimbusError::Network(_) | NimbusError::Timeout(_)) => {
+                self.breaker.record_failure(Instant::now())
+            }
+            // Any answer, even a 429, means the server is up
+            Ok(_) | Err(NimbusError::RateLimited { .. }) => self.breaker.record_success(),
+            Err(_) => {}
+        }
+    }
+
+    fn record(&self, endpoint: &str, result: &Result<Response, NimbusError>, started: Instant) {
+        let success = matches!(
+            result,
+            Ok(response) if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED
+        );
+        self.metrics.record(endpoint, success, started.elapsed());
+    }
+}
+
+/// Builds the standard Nimbus request headers
+///
+/// Nimbus accepts the token either as `AuthenticationToken` or as a Bearer
+/// `Authorization` header depending on the deployment, so both are sent
+/// unless `send_bearer_auth` is false, for gateways that reject the latter.
+/// `Accept-Language` is only sent when `accept_language` is set, so Nimbus
+/// localizes its error messages; otherwise the server default applies.
+/// `extra_headers` are applied last and so override a standard header of the
+/// same name; an invalid name or value is an error rather than being dropped.
+pub fn build_nimbus_headers(
+    token: &str,
+    extra_headers: &HashMap<String, String>,
+    send_bearer_auth: bool,
+    accept_language: Option<&str>,
+) -> Result<HeaderMap, NimbusError> {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        "AuthenticationToken",
+        HeaderValue::from_str(token)
+            .map_err(|e| NimbusError::InvalidHeader(format!("token: {}", e)))?,
+    );
+    if send_bearer_auth {
+        headers.insert(
+            "Authorization",
+            HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|e| NimbusError::InvalidHeader(format!("authorization: {}", e)))?,
+        );
+    }
+    headers.insert("Accept", HeaderValue::from_static("application/json"));
+    headers.insert("Content-Type", HeaderValue::from_static("application/json"));
+    if let Some(language) = accept_language {
+        headers.insert(
+            ACCEPT_LANGUAGE,
+            HeaderValue::from_str(language)
+                .map_err(|e| NimbusError::InvalidHeader(format!("accept language: {}", e)))?,
+        );
+    }
+
+    for (name, value) in extra_headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| NimbusError::InvalidHeader(format!("{}: {}", name, e)))?;
+        let header_value = HeaderValue::from_str(value)
+            .map_err(|e| NimbusError::InvalidHeader(format!("{}: {}", name, e)))?;
+        headers.insert(header_name, header_value);
+    }
+
+    Ok(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::executor::MockExecutor;
+
+    #[test]
+    fn test_retry_backoff_doubles() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_jittered_backoff_is_bounded_and_seedable() {
+        let policy = RetryPolicy {
+            jitter_seed: Some(42),
+            ..RetryPolicy::default()
+        };
+        let delays = |policy: &RetryPolicy| {
+            let mut rng = policy.jitter_rng();
+            (1..=5)
+                .map(|attempt| policy.jittered_backoff(attempt, &mut rng))
+                .collect::<Vec<_>>()
+        };
+
+        let first = delays(&policy);
+        assert_eq!(first, delays(&policy));
+        for (attempt, delay) in (1..=5).zip(&first) {
+            assert!(*delay <= policy.backoff(attempt));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_carry_user_agent() {
+        let mock = MockExecutor::new();
+        mock.respond(200, "{}").respond(200, "{}");
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+        let custom = NimbusClient::with_executor(
+            NimbusConfig {
+                user_agent: "tenant-scheduler/2".to_string(),
+                ..NimbusConfig::default()
+            },
+            mock.clone(),
+        );
+
+        for client in [&client, &custom] {
+            let request = client
+                .http()
+                .get("https://nimbus.test/RESTApi/LocationGroup")
+                .headers(client.headers("tok").unwrap());
+            client.send(request, true).await.unwrap();
+        }
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].headers[USER_AGENT], default_user_agent());
+        assert!(default_user_agent().starts_with("claude-family/"));
+        assert_eq!(requests[1].headers[USER_AGENT], "tenant-scheduler/2");
+    }
+
+    #[tokio::test]
+    async fn test_gzipped_response_is_decoded() {
+        use flate2::write::GzEncoder;
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder
+            .write_all(br#"{"Items": [], "TotalCount": 0}"#)
+            .unwrap();
+        let body = encoder.finish().unwrap();
+
+        // A one-shot HTTP server, since only the real client decompresses
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4096];
+            let read = stream.read(&mut request).unwrap();
+            let head = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                 Content-Encoding: gzip\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+            String::from_utf8_lossy(&request[..read]).to_lowercase()
+        });
+
+        let client = NimbusClient::new(NimbusConfig {
+            proxy: ProxyConfig::Disabled,
+            ..NimbusConfig::default()
+        })
+        .unwrap();
+        let request = client
+            .http()
+            .get(format!("http://{}/RESTApi/LocationGroup", addr));
+        let response = client.send_once(request).await.unwrap();
+        let decoded: serde_json::Value = response.json().await.unwrap();
+        assert_eq!(decoded["TotalCount"], 0);
+
+        let request = server.join().unwrap();
+        assert!(request.contains("accept-encoding: gzip"), "{}", request);
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_override() {
+        use std::io::Read;
+        use std::net::TcpListener;
+
+        // Accepts the connection and reads the request, but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = [0u8; 4096];
+            let _ = stream.read(&mut request);
+            std::thread::sleep(Duration::from_millis(500));
+        });
+
+        let client = NimbusClient::new(NimbusConfig {
+            proxy: ProxyConfig::Disabled,
+            ..NimbusConfig::default()
+        })
+        .unwrap();
+        let request = client
+            .http()
+            .get(format!("http://{}/RESTApi/LocationGroup", addr));
+        let started = Instant::now();
+        let result = with_request_timeout(Some(50), client.send_once(request)).await;
+        assert!(
+            matches!(result, Err(NimbusError::Timeout(_))),
+            "{:?}",
+            result
+        );
+        assert!(started.elapsed() < Duration::from_millis(500));
+
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_client_builds_with_default_config() {
+        let config = NimbusConfig::default();
+        assert_eq!(config.request_timeout, Duration::from_secs(30));
+        assert_eq!(config.connect_timeout, Duration::from_secs(10));
+        assert!(NimbusClient::new(config).is_ok());
+    }
+
+    #[test]
+    fn test_client_builds_with_custom_pool() {
+        let config = NimbusConfig {
+            pool_max_idle_per_host: 32,
+            pool_idle_timeout: Duration::from_secs(15),
+            ..NimbusConfig::default()
+        };
+        assert!(NimbusClient::new(config).is_ok());
+
+        let no_pooling = NimbusConfig {
+            pool_max_idle_per_host: 0,
+            ..NimbusConfig::default()
+        };
+        assert!(NimbusClient::new(no_pooling).is_ok());
+    }
+
+    #[test]
+    fn test_client_builds_with_proxy_configs() {
+        for proxy in [
+            ProxyConfig::Disabled,
+            ProxyConfig::Custom {
+                url: "http://proxy.corp:3128".to_string(),
+                username: Some("user".to_string()),
+                password: Some("secret".to_string()),
+                no_proxy: Some("localhost,127.0.0.1".to_string()),
+            },
+        ] {
+            let config = NimbusConfig {
+                proxy,
+                ..NimbusConfig::default()
+            };
+            assert!(!format!("{:?}", config).contains("secret"));
+            assert!(NimbusClient::new(config).is_ok());
+        }
+
+        let config = NimbusConfig {
+            proxy: ProxyConfig::Custom {
+                url: "not a url".to_string(),
+                username: None,
+                password: None,
+                no_proxy: None,
+            },
+            ..NimbusConfig::default()
+        };
+        assert!(NimbusClient::new(config).is_err());
+    }
+
+    #[test]
+    fn test_client_rejects_bad_ca_bundle() {
+        let missing = NimbusConfig {
+            ca_bundle_path: Some(PathBuf::from("/nonexistent/ca.pem")),
+            ..NimbusConfig::default()
+        };
+        let err = NimbusClient::new(missing).err().unwrap();
+        assert!(err.contains("ca.pem"));
+
+        let path = std::env::temp_dir().join(format!("{}.pem", new_idempotency_key()));
+        std::fs::write(&path, "not a certificate").unwrap();
+        let empty = NimbusConfig {
+            ca_bundle_path: Some(path),
+            ..NimbusConfig::default()
+        };
+        assert!(NimbusClient::new(empty).is_err());
+
+        let insecure = NimbusConfig {
+            danger_accept_invalid_certs: true,
+            ..NimbusConfig::default()
+        };
+        assert!(NimbusClient::new(insecure).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_send_retries_503_then_succeeds() {
+        let mock = MockExecutor::new();
+        mock.respond(503, "").respond(200, "{}");
+        let config = NimbusConfig {
+            retry: RetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            },
+            ..NimbusConfig::default()
+        };
+        let client = NimbusClient::with_executor(config, mock.clone());
+
+        let request = client
+            .http()
+            .get("https://nimbus.test/RESTApi/LocationGroup/1");
+        let response = client.send(request, true).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(mock.requests().len(), 2);
+
+        let stats = &client.metrics().snapshot().endpoints["GET /RESTApi/LocationGroup/{id}"];
+        assert_eq!((stats.calls, stats.successes), (1, 1));
+    }
+
+    /// Moves forward by `tick` every time it is read
+    struct FakeClock {
+        now: Mutex<Instant>,
+        tick: Duration,
+    }
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            let mut now = self.now.lock().unwrap();
+            *now += self.tick;
+            *now
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_stop_when_budget_is_spent() {
+        let mock = MockExecutor::new();
+        for _ in 0..10 {
+            mock.respond(503, "busy");
+        }
+        let config = NimbusConfig {
+            retry: RetryPolicy {
+                max_attempts: 10,
+                base_delay: Duration::from_millis(1),
+                budget: Some(Duration::from_millis(2500)),
+                ..RetryPolicy::default()
+            },
+            ..NimbusConfig::default()
+        };
+        // Each failed attempt appears to take a second
+        let clock = FakeClock {
+            now: Mutex::new(Instant::now()),
+            tick: Duration::from_secs(1),
+        };
+        let client = NimbusClient::with_executor(config, mock.clone()).with_clock(Arc::new(clock));
+
+        let request = client
+            .http()
+            .get("https://nimbus.test/RESTApi/LocationGroup/1");
+        let response = client.send(request, true).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(mock.requests().len(), 3);
+
+        let policy = RetryPolicy {
+            budget: None,
+            ..RetryPolicy::default()
+        };
+        assert!(policy.within_budget(Duration::from_secs(86_400)));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_spaces_out_sends() {
+        let mock = MockExecutor::new();
+        mock.respond(200, "{}").respond(200, "{}");
+        let config = NimbusConfig {
+            rate_limit: Some(RateLimitConfig {
+                requests_per_second: 1.0,
+                burst: 1,
+            }),
+            ..NimbusConfig::default()
+        };
+        // Stands still unless moved by hand, and lags the real clock, so a
+        // bucket seeded from `Instant::now` would never see it refill
+        let start = Instant::now()
+            .checked_sub(Duration::from_secs(60))
+            .expect("monotonic clock has run for a minute");
+        let clock = Arc::new(FakeClock {
+            now: Mutex::new(start),
+            tick: Duration::ZERO,
+        });
+        let client = NimbusClient::with_executor(config, mock.clone()).with_clock(clock.clone());
+        let url = "https://nimbus.test/RESTApi/LocationGroup/1";
+
+        let started = Instant::now();
+        client.send(client.http().get(url), true).await.unwrap();
+        // A second later by the fake clock the token has refilled, so neither send waits
+        *clock.now.lock().unwrap() += Duration::from_secs(1);
+        client.send(client.http().get(url), true).await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert_eq!(mock.requests().len(), 2);
+
+        // The next send at the same moment would have to wait a full second
+        let bucket = client.rate_limit.as_ref().unwrap();
+        assert_eq!(bucket.reserve(clock.now()), Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_fails_fast() {
+        let mock = MockExecutor::new();
+        mock.respond(500, "down").respond(500, "down");
+        let config = NimbusConfig {
+            circuit_breaker: CircuitBreakerConfig {
+                failure_threshold: 2,
+                ..CircuitBreakerConfig::default()
+            },
+            ..NimbusConfig::default()
+        };
+        let client = NimbusClient::with_executor(config, mock.clone());
+
+        let url = "https://nimbus.test/RESTApi/LocationGroup";
+        for _ in 0..2 {
+            let response = client.send(client.http().get(url), true).await.unwrap();
+            assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        }
+        let err = client.send(client.http().get(url), true).await.unwrap_err();
+        assert!(matches!(err, NimbusError::CircuitOpen { .. }));
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_retry_after() {
+        let now = DateTime::parse_from_rfc2822("Wed, 01 Jan 2025 12:00:00 GMT")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(parse_retry_after("5", now), Some(Duration::from_secs(5)));
+        assert_eq!(
+            parse_retry_after("Wed, 01 Jan 2025 12:00:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("Wed, 01 Jan 2025 11:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon", now), None);
+    }
+
+    #[tokio::test]
+    async fn test_send_gives_up_with_rate_limited() {
+        let mock = MockExecutor::new();
+        mock.respond_with_headers(429, &[("Retry-After", "0")], "")
+            .respond_with_headers(429, &[("Retry-After", "2")], "");
+        let config = NimbusConfig {
+            retry: RetryPolicy {
+                max_attempts: 2,
+                base_delay: Duration::from_millis(1),
+                ..RetryPolicy::default()
+            },
+            ..NimbusConfig::default()
+        };
+        let client = NimbusClient::with_executor(config, mock.clone());
+
+        let request = client
+            .http()
+            .post("https://nimbus.test/RESTApi/LocationGroup");
+        let err = client.send(request, false).await.unwrap_err();
+        assert!(matches!(
+            err,
+            NimbusError::RateLimited { retry_after } if retry_after == Duration::from_secs(2)
+        ));
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_caller_idempotency_keys_must_be_header_values() {
+        assert_eq!(
+            resolve_idempotency_key(Some("order-42".to_string())).unwrap(),
+            "order-42"
+        );
+        assert_eq!(resolve_idempotency_key(None).unwrap().len(), 36);
+        for bad in ["", "line\nbreak", "bell\u{7}"] {
+            assert!(matches!(
+                resolve_idempotency_key(Some(bad.to_string())),
+                Err(NimbusError::Validation(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_retries_status() {
+        let policy = RetryPolicy::default();
+        assert!(policy.retries_status(StatusCode::SERVICE_UNAVAILABLE, false));
+        assert!(policy.retries_status(StatusCode::BAD_GATEWAY, true));
+        assert!(!policy.retries_status(StatusCode::BAD_GATEWAY, false));
+        assert!(!policy.retries_status(StatusCode::BAD_REQUEST, true));
+        assert!(!policy.retries_status(StatusCode::NOT_FOUND, true));
+        assert!(!policy.retries_status(StatusCode::REQUEST_TIMEOUT, true));
+    }
+
+    #[tokio::test]
+    async fn test_custom_retryable_statuses_are_retried() {
+        let mock = MockExecutor::new();
+        mock.respond(408, "")
+            .respond(200, "{}")
+            .respond(503, "")
+            .respond(429, "");
+        let config = NimbusConfig {
+            retry: RetryPolicy {
+                max_attempts: 3,
+                base_delay: Duration::from_millis(1),
+                retryable_statuses: vec![408, 425],
+                ..RetryPolicy::default()
+            },
+            ..NimbusConfig::default()
+        };
+        let client = NimbusClient::with_executor(config, mock.clone());
+        let url = "https://nimbus.test/RESTApi/LocationGroup";
+
+        // Listed, so retried even for a plain POST
+        let response = client.send(client.http().post(url), false).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(mock.requests().len(), 2);
+
+        // 503 is no longer in the list
+        let response = client.send(client.http().get(url), true).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(mock.requests().len(), 3);
+
+        // Without 429 in the list it is handed back rather than turned into RateLimited
+        let response = client.send(client.http().get(url), true).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[test]
+    fn test_build_nimbus_headers() {
+        let headers = build_nimbus_headers("abc123", &HashMap::new(), true, None).unwrap();
+        assert_eq!(headers["Authorization"], "Bearer abc123");
+        assert_eq!(headers["AuthenticationToken"], "abc123");
+        assert_eq!(headers["Accept"], "application/json");
+    }
+
+    #[test]
+    fn test_build_nimbus_headers_without_bearer() {
+        let headers = build_nimbus_headers("abc123", &HashMap::new(), false, None).unwrap();
+        assert!(headers.get("Authorization").is_none());
+        assert_eq!(headers["AuthenticationToken"], "abc123");
+
+        // The client passes the config flag through
+        let client = NimbusClient::with_executor(
+            NimbusConfig {
+                send_bearer_auth: false,
+                ..NimbusConfig::default()
+            },
+            MockExecutor::new(),
+        );
+        assert!(client
+            .headers("abc123")
+            .unwrap()
+            .get("Authorization")
+            .is_none());
+        let client = NimbusClient::with_executor(NimbusConfig::default(), MockExecutor::new());
+        assert_eq!(
+            client.headers("abc123").unwrap()["Authorization"],
+            "Bearer abc123"
+        );
+    }
+
+    #[test]
+    fn test_accept_language_is_sent_only_when_configured() {
+        let headers = build_nimbus_headers("abc123", &HashMap::new(), true, Some("de-DE")).unwrap();
+        assert_eq!(headers[ACCEPT_LANGUAGE], "de-DE");
+        let headers = build_nimbus_headers("abc123", &HashMap::new(), true, None).unwrap();
+        assert!(headers.get(ACCEPT_LANGUAGE).is_none());
+
+        let client = NimbusClient::with_executor(
+            NimbusConfig {
+                accept_language: Some("fr-CA, fr;q=0.8".to_string()),
+                ..NimbusConfig::default()
+            },
+            MockExecutor::new(),
+        );
+        assert_eq!(
+            client.headers("abc123").unwrap()[ACCEPT_LANGUAGE],
+            "fr-CA, fr;q=0.8"
+        );
+        let client = NimbusClient::with_executor(NimbusConfig::default(), MockExecutor::new());
+        assert!(client
+            .headers("abc123")
+            .unwrap()
+            .get(ACCEPT_LANGUAGE)
+            .is_none());
+    }
+
+    #[test]
+    fn test_build_nimbus_headers_rejects_invalid_token() {
+        let result = build_nimbus_headers("bad\ntoken", &HashMap::new(), true, None);
+        assert!(matches!(result, Err(NimbusError::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn test_build_nimbus_headers_merges_extra_headers() {
+        let extra = HashMap::from([("X-Tenant-Id".to_string(), "tenant-7".to_string())]);
+        let headers = build_nimbus_headers("abc123", &extra, true, None).unwrap();
+        assert_eq!(headers["X-Tenant-Id"], "tenant-7");
+        assert_eq!(headers["Authorization"], "Bearer abc123");
+        assert_eq!(headers.len(), 5);
+    }
+
+    #[test]
+    fn test_build_nimbus_headers_rejects_invalid_extra_header() {
+        let extra = HashMap::from([("X Tenant".to_string(), "tenant-7".to_string())]);
+        let err = build_nimbus_headers("abc123", &extra, true, None).unwrap_err();
+        assert!(err.to_string().contains("X Tenant"));
+
+        let extra = HashMap::from([("X-Tenant-Id".to_string(), "bad\nvalue".to_string())]);
+        assert!(build_nimbus_headers("abc123", &extra, true, None).is_err());
+    }
+}
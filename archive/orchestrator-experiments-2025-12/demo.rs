@@ -0,0 +1,437 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use futures::future::BoxFuture;
+use reqwest::header::CONTENT_TYPE;
+use reqwest::{Method, Request, Response, StatusCode};
+use serde_json::{json, Value};
+
+use super::executor::HttpExecutor;
+
+/// Name of the only profile in demo mode
+pub const DEMO_PROFILE: &str = "demo";
+
+/// Base URL of the demo profile; never resolved, `DemoExecutor` answers instead
+pub const DEMO_BASE_URL: &str = "https://demo.nimbus.invalid";
+
+/// Token the demo session starts signed in with
+pub const DEMO_TOKEN: &str = "demo-token";
+
+/// Answers requests from an in-memory fake Nimbus, for `NimbusConfig::demo_mode`
+///
+/// Any `/RESTApi/{Entity}` path is a collection whose records are keyed by
+/// `{Entity}ID`: POST assigns the next ID, GET lists (paged, with
+/// `Search` matching the description and any other query param filtering
+/// on the field of that name), and `/{id}` supports GET, PUT, PATCH and
+/// DELETE. `/LocationGroup/{id}/Locations` adds and removes locations, and
+/// `CurrentUser` and `RefreshToken` always succeed. Anything else is a 404,
+/// so commands take the same fallbacks as on an older server. Every token
+/// is accepted, and nothing is persisted.
+pub struct DemoExecutor {
+    collections: Mutex<HashMap<String, Collection>>,
+}
+
+#[derive(Default)]
+struct Collection {
+    next_id: i64,
+    records: BTreeMap<i64, Value>,
+}
+
+impl DemoExecutor {
+    /// An empty store
+    pub fn new() -> Self {
+        Self {
+            collections: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A store with a few LocationGroups and ScheduleGroups, the same every run
+    pub fn seeded() -> Self {
+        let demo = Self::new();
+        for record in [
+            json!({"Description": "North Campus", "Active": true,
+                   "Locations": [{"LocationID": 101}, {"LocationID": 102}]}),
+            json!({"Description": "South Campus", "Active": true,
+                   "Locations": [{"LocationID": 201}]}),
+            json!({"Description": "Closed Annex", "Active": false, "Locations": []}),
+        ] {
+            demo.insert("LocationGroup", record);
+        }
+        for (description, start, end, period) in [
+            ("Semester 1", "2025-02-03", "2025-06-27", "Term"),
+            ("Semester 2", "2025-07-21", "2025-11-28", "90"),
+        ] {
+            demo.insert(
+                "ScheduleGroup",
+                json!({
+                    "Description": description,
+                    "Active": true,
+                    "LocationGroupID": 1,
+                    "GroupStartDate": start,
+                    "GroupEndDate": end,
+                    "AdhocFields": [{"FieldName": "adhoc_LearningPeriod", "Value": period}]
+                }),
+            );
+        }
+        demo
+    }
+
+    /// Stores `record` under the next ID of `entity`, returning it with the ID set
+    fn insert(&self, entity: &str, mut record: Value) -> Value {
+        let mut collections = self.lock();
+        let collection = collections.entry(entity.to_string()).or_default();
+        collection.next_id += 1;
+        let id = collection.next_id;
+        if let Some(object) = record.as_object_mut() {
+            object.insert(id_field(entity), json!(id));
+        }
+        collection.records.insert(id, record.clone());
+        record
+    }
+
+    fn answer(&self, method: &Method, url: &reqwest::Url, body: Option<Value>) -> DemoResponse {
+        let Some(path) = url.path().strip_prefix("/RESTApi/") else {
+            return not_found(url.path());
+        };
+        let segments: Vec<&str> = path.trim_end_matches('/').split('/').collect();
+        let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+
+        match (method, segments.as_slice()) {
+            (&Method::GET, ["CurrentUser"]) => DemoResponse::ok(json!({
+                "UserName": "demo",
+                "Roles": ["Administrator"]
+            })),
+            (&Method::POST, ["RefreshToken"]) => {
+                DemoResponse::ok(json!({ "AuthenticationToken": DEMO_TOKEN }))
+            }
+            (_, ["CurrentUser" | "RefreshToken"]) => method_not_allowed(),
+            (&Method::GET, [entity]) => self.list(entity, &query),
+            (&Method::POST, [entity]) => match body {
+                Some(Value::Object(record)) => DemoResponse {
+                    status: StatusCode::CREATED,
+                    body: Some(self.insert(entity, Value::Object(record))),
+                },
+                _ => bad_request("expected a JSON object"),
+            },
+            (_, [_]) => method_not_allowed(),
+            (_, [entity, id]) => match id.parse() {
+                Ok(id) => self.entity(method, entity, id, body),
+                Err(_) => not_found(url.path()),
+            },
+            (_, ["LocationGroup", id, "Locations"]) => match id.parse() {
+                Ok(id) => self.locations(method, id, body),
+                Err(_) => not_found(url.path()),
+            },
+            _ => not_found(url.path()),
+        }
+    }
+
+    fn list(&self, entity: &str, query: &[(String, String)]) -> DemoResponse {
+        let mut page_number = 1usize;
+        let mut page_size = usize::MAX;
+        let mut search = None;
+        let mut filters = Vec::new();
+        for (name, value) in query {
+            match name.as_str() {
+                "PageNumber" => page_number = value.parse().unwrap_or(1).max(1),
+                "PageSize" => page_size = value.parse().unwrap_or(usize::MAX).max(1),
+                "Search" => search = Some(value.to_lowercase()),
+                _ => filters.push((name.as_str(), value.as_str())),
+            }
+        }
+
+        let collections = self.lock();
+        let matching: Vec<&Value> = collections
+            .get(entity)
+            .into_iter()
+            .flat_map(|collection| collection.records.values())
+            .filter(|record| {
+                filters
+                    .iter()
+                    .all(|(name, value)| record.get(*name).is_some_and(|v| field_eq(v, value)))
+            })
+            .filter(|record| match &search {
+                Some(search) => record
+                    .get("Description")
+                    .and_then(|v| v.as_str())
+                    .is_some_and(|d| d.to_lowercase().contains(search)),
+                None => true,
+            })
+            .collect();
+
+        let items: Vec<Value> = matching
+            .iter()
+            .skip((page_number - 1).saturating_mul(page_size))
+            .take(page_size)
+            .map(|record| (*record).clone())
+            .collect();
+        DemoResponse::ok(json!({ "Items": items, "TotalCount": matching.len() }))
+    }
+
+    fn entity(&self, method: &Method, entity: &str, id: i64, body: Option<Value>) -> DemoResponse {
+        let mut collections = self.lock();
+        let Some(record) = collections
+            .get_mut(entity)
+            .and_then(|collection| collection.records.get_mut(&id))
+        else {
+            return missing(entity, id);
+        };
+
+        match (method, body) {
+            (&Method::GET, _) => DemoResponse::ok(record.clone()),
+            (&Method::PUT, Some(Value::Object(mut replacement))) => {
+                replacement.insert(id_field(entity), json!(id));
+                *record = Value::Object(replacement);
+                DemoResponse::ok(record.clone())
+            }
+            (&Method::PATCH, Some(Value::Object(changes))) => {
+                if let Some(object) = record.as_object_mut() {
+                    object.extend(changes);
+                    object.insert(id_field(entity), json!(id));
+                }
+                DemoResponse::ok(record.clone())
+            }
+            (&Method::PUT | &Method::PATCH, _) => bad_request("expected a JSON object"),
+            (&Method::DELETE, _) => {
+                if let Some(collection) = collections.get_mut(entity) {
+                    collection.records.remove(&id);
+                }
+                DemoResponse {
+                    status: StatusCode::NO_CONTENT,
+                    body: None,
+                }
+            }
+            _ => method_not_allowed(),
+        }
+    }
+
+    fn locations(&self, method: &Method, id: i64, body: Option<Value>) -> DemoResponse {
+        let ids: Vec<i64> = body
+            .as_ref()
+            .and_then(|body| body.get("Locations"))
+            .and_then(|v| v.as_array())
+            .map(|locations| {
+                locations
+                    .iter()
+                    .filter_map(|l| l.as_i64().or_else(|| l.get("LocationID")?.as_i64()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut collections = self.lock();
+        let Some(record) = collections
+            .get_mut("LocationGroup")
+            .and_then(|collection| collection.records.get_mut(&id))
+        else {
+            return missing("LocationGroup", id);
+        };
+
+        let mut current: Vec<i64> = record
+            .get("Locations")
+            .and_then(|v| v.as_array())
+            .map(|locations| {
+                locations
+                    .iter()
+                    .filter_map(|l| l.get("LocationID")?.as_i64())
+                    .collect()
+            })
+            .unwrap_or_default();
+        match *method {
+            Method::POST => {
+                for location_id in ids {
+                    if !current.contains(&location_id) {
+                        current.push(location_id);
+                    }
+                }
+            }
+            Method::DELETE => current.retain(|location_id| !ids.contains(location_id)),
+            _ => return method_not_allowed(),
+        }
+
+        let locations: Vec<Value> = current
+            .iter()
+            .map(|location_id| json!({"LocationID": location_id}))
+            .collect();
+        if let Some(object) = record.as_object_mut() {
+            object.insert("Locations".to_string(), Value::Array(locations));
+        }
+        DemoResponse::ok(record.clone())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Collection>> {
+        self.collections.lock().expect("demo store lock poisoned")
+    }
+}
+
+impl Default for DemoExecutor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl HttpExecutor for DemoExecutor {
+    fn execute(&self, request: Request) -> BoxFuture<'_, Result<Response, reqwest::Error>> {
+        let body = request
+            .body()
+            .and_then(|b| b.as_bytes())
+            .and_then(|bytes| serde_json::from_slice(bytes).ok());
+        let answer = self.answer(request.method(), request.url(), body);
+
+        let mut builder = http::Response::builder().status(answer.status);
+        let text = match answer.body {
+            Some(body) => {
+                builder = builder.header(CONTENT_TYPE, "application/json");
+                body.to_string()
+            }
+            None => String::new(),
+        };
+        let response = builder.body(text).expect("invalid demo response");
+        Box::pin(async move { Ok(Response::from(response)) })
+    }
+}
+
+/// Status and JSON body of a demo answer
+struct DemoResponse {
+    status: StatusCode,
+    body: Option<Value>,
+}
+
+impl DemoResponse {
+    fn ok(body: Value) -> Self {
+        Self {
+            status: StatusCode::OK,
+            body: Some(body),
+        }
+    }
+
+    fn error(status: StatusCode, message: String) -> Self {
+        Self {
+            status,
+            body: Some(json!({ "Message": message })),
+        }
+    }
+}
+
+/// `LocationGroup` -> `LocationGroupID`
+fn id_field(entity: &str) -> String {
+    format!("{}ID", entity)
+}
+
+/// Whether a record field equals a query param value
+fn field_eq(field: &Value, value: &str) -> bool {
+    match field {
+        Value::String(field) => field == value,
+        Value::Number(field) => field.to_string() == value,
+        Value::Bool(field) => value.parse() == Ok(*field),
+        _ => false,
+    }
+}
+
+fn missing(entity: &str, id: i64) -> DemoResponse {
+    DemoResponse::error(
+        StatusCode::NOT_FOUND,
+        format!("{} {} does not exist", entity, id),
+    )
+}
+
+fn not_found(path: &str) -> DemoResponse {
+    DemoResponse::error(StatusCode::NOT_FOUND, format!("No route for {}", path))
+}
+
+fn method_not_allowed() -> DemoResponse {
+    DemoResponse::error(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "Method not allowed".to_string(),
+    )
+}
+
+fn bad_request(message: &str) -> DemoResponse {
+    DemoResponse::error(StatusCode::BAD_REQUEST, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::commands::client::{NimbusClient, NimbusConfig};
+    use crate::commands::entities::{
+        apply_location_delta, copy_schedule_group, fetch_location_group, fetch_location_group_page,
+        fetch_schedule_group, post_location_group, remove_location_group, LearningPeriod,
+        LocationDelta, LocationGroupRequest,
+    };
+    use crate::commands::error::NimbusError;
+
+    #[tokio::test]
+    async fn test_demo_store_creates_lists_and_deletes() {
+        let client =
+            NimbusClient::with_executor(NimbusConfig::default(), Arc::new(DemoExecutor::seeded()));
+        let (base_url, token) = (DEMO_BASE_URL, DEMO_TOKEN);
+
+        let seeded = fetch_location_group_page(&client, base_url, token, 1, 50)
+            .await
+            .unwrap();
+        assert_eq!(seeded.total, Some(3));
+
+        let request = LocationGroupRequest {
+            description: "Depot".to_string(),
+            location_ids: vec![7],
+            active: true,
+        };
+        let id = post_location_group(&client, base_url, token, &request, "key-1")
+            .await
+            .unwrap();
+        assert_eq!(id, 4);
+        let ids = apply_location_delta(&client, base_url, token, id, LocationDelta::Add, &[8])
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![7, 8]);
+
+        let page = fetch_location_group_page(&client, base_url, token, 2, 2)
+            .await
+            .unwrap();
+        let descriptions: Vec<&str> = page.items.iter().map(|g| g.description.as_str()).collect();
+        assert_eq!(descriptions, ["Closed Annex", "Depot"]);
+
+        remove_location_group(&client, base_url, token, id)
+            .await
+            .unwrap();
+        let err = fetch_location_group(&client, base_url, token, id)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, NimbusError::NotFound { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_seeded_schedules_can_be_cloned() {
+        let client =
+            NimbusClient::with_executor(NimbusConfig::default(), Arc::new(DemoExecutor::seeded()));
+        let (base_url, token) = (DEMO_BASE_URL, DEMO_TOKEN);
+
+        for (source_id, period) in [(1, LearningPeriod::Term), (2, LearningPeriod::Days90)] {
+            let id = copy_schedule_group(
+                &client,
+                base_url,
+                token,
+                source_id,
+                "2026-02-02".to_string(),
+                "2026-06-26".to_string(),
+                true,
+            )
+            .await
+            .unwrap();
+
+            let copy = fetch_schedule_group(&client, base_url, token, id)
+                .await
+                .unwrap();
+            assert_eq!(copy.start_date, "2026-02-02");
+            let learning_period = copy
+                .adhoc_fields
+                .iter()
+                .find(|field| field.field_name == "adhoc_LearningPeriod")
+                .map(|field| field.value.as_str());
+            assert_eq!(learning_period, Some(period.as_str()));
+            assert!(copy.description.ends_with("(copy)"));
+        }
+    }
+}
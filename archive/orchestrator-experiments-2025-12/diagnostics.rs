@@ -0,0 +1,255 @@
+use std::time::Instant;
+
+use reqwest::header::{HeaderValue, CONTENT_TYPE, LOCATION};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use super::about::{build_info, BuildInfo};
+use super::audit::{AuditLog, AuditOperation};
+use super::capture::redact_headers;
+use super::client::{
+    new_idempotency_key, NimbusClient, CORRELATION_ID_HEADER, IDEMPOTENCY_KEY_HEADER,
+};
+use super::entities::{created_id, prepare_location_group_payload, LocationGroupRequest};
+use super::error::{redact, redact_value, NimbusError};
+use super::profiles::ProfileStore;
+use super::request::{encode_body, header_value, read_body, SuccessBody};
+use super::session::{Session, Target};
+
+/// Everything about one create, for attaching to a support ticket
+///
+/// Token headers are replaced and secrets in the response body go through
+/// `redact`, so the report can be shared as-is.
+#[derive(Debug, Serialize)]
+pub struct Diagnostics {
+    /// The app build that sent the request
+    pub build: BuildInfo,
+    pub method: String,
+    /// Fully resolved URL the request was sent to
+    pub url: String,
+    pub request_headers: Map<String, Value>,
+    /// The body as sent, shown as JSON even when `payload_encoding` is `Form`
+    pub payload: Value,
+    /// None when no response arrived; see `error`
+    pub status: Option<u16>,
+    pub response_headers: Map<String, Value>,
+    /// Parsed JSON, or a string when the body isn't JSON; None without a response
+    pub response_body: Option<Value>,
+    /// From sending the request until the body was read (or the send failed)
+    pub elapsed_ms: u64,
+    /// ID of the created group, when the create succeeded and it could be read
+    pub location_group_id: Option<i64>,
+    /// Why the exchange failed: the network error, or the mapped HTTP error
+    pub error: Option<String>,
+    /// The error behind `error`, for the audit log
+    #[serde(skip)]
+    failure: Option<NimbusError>,
+}
+
+impl Diagnostics {
+    fn fail(&mut self, error: NimbusError) {
+        self.error = Some(error.sanitized_message());
+        self.failure = Some(error);
+    }
+}
+
+/// Creates a LocationGroup once and reports the whole exchange
+///
+/// For support only: the create is real, sent exactly once without retries,
+/// the circuit breaker or token refresh, so the report shows what Nimbus
+/// answered to this one request. Normal flows use `create_location_group`.
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the create is recorded in
+/// * `request` - LocationGroupRequest to create
+///
+/// # Returns
+/// Diagnostics for the exchange, also when Nimbus rejected the create or
+/// could not be reached. Only a request that fails local validation is an
+/// error, since nothing is sent then.
+#[tauri::command]
+pub async fn diagnose_create_location_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    request: LocationGroupRequest,
+) -> Result<Diagnostics, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, None)?;
+
+    let diagnostics = diagnose_location_group_create(&client, &base_url, &token, &request).await?;
+    audit.record(
+        AuditOperation::Create,
+        "LocationGroup",
+        diagnostics.location_group_id,
+        Some(profile),
+        diagnostics.failure.as_ref(),
+    );
+    Ok(diagnostics)
+}
+
+/// Sends one LocationGroup create and records every detail of it
+pub(crate) async fn diagnose_location_group_create(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    request: &LocationGroupRequest,
+) -> Result<Diagnostics, NimbusError> {
+    let payload = prepare_location_group_payload(request, client.max_description_length())?;
+    let url = format!("{}/RESTApi/LocationGroup", base_url);
+    let correlation_id = uuid::Uuid::new_v4().to_string();
+
+    let mut headers = client.headers(token)?;
+    for (name, value) in [
+        (IDEMPOTENCY_KEY_HEADER, new_idempotency_key()),
+        (CORRELATION_ID_HEADER, correlation_id.clone()),
+    ] {
+        let value = value
+            .parse()
+            .map_err(|e| NimbusError::InvalidHeader(format!("{}: {}", name, e)))?;
+        headers.insert(name, value);
+    }
+    let (content_type, bytes) = encode_body(&payload, client.payload_encoding())?;
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    let http_request = client.http().post(&url).headers(headers).body(bytes);
+    let request_headers = http_request
+        .try_clone()
+        .and_then(|r| r.build().ok())
+        .map(|r| redact_headers(r.headers()))
+        .unwrap_or_default();
+
+    let mut diagnostics = Diagnostics {
+        build: build_info(),
+        method: "POST".to_string(),
+        url,
+        request_headers,
+        payload,
+        status: None,
+        response_headers: Map::new(),
+        response_body: None,
+        elapsed_ms: 0,
+        location_group_id: None,
+        error: None,
+        failure: None,
+    };
+
+    tracing::info!(url = %diagnostics.url, %correlation_id, "Sending diagnostic create");
+    let started = Instant::now();
+    let response = match client.send_once(http_request).await {
+        Ok(response) => response,
+        Err(e) => {
+            diagnostics.elapsed_ms = started.elapsed().as_millis() as u64;
+            diagnostics.fail(e);
+            return Ok(diagnostics);
+        }
+    };
+    let status = response.status().as_u16();
+    let headers = response.headers().clone();
+    diagnostics.status = Some(status);
+    diagnostics.response_headers = redact_headers(&headers);
+    let text = read_body(response, client.max_response_bytes()).await;
+    diagnostics.elapsed_ms = started.elapsed().as_millis() as u64;
+
+    let text = match text {
+        Ok(text) => text,
+        Err(e) => {
+            diagnostics.fail(e);
+            return Ok(diagnostics);
+        }
+    };
+    diagnostics.response_body = Some(if text.trim().is_empty() {
+        Value::Null
+    } else {
+        match serde_json::from_str(&text) {
+            Ok(mut body) => {
+                redact_value(&mut body);
+                body
+            }
+            Err(_) => Value::String(redact(&text)),
+        }
+    });
+
+    if !(200..300).contains(&status) {
+        diagnostics
+            .fail(NimbusError::from_body(status, &text).with_correlation_id(&correlation_id));
+        return Ok(diagnostics);
+    }
+    let body = SuccessBody {
+        status,
+        content_type: header_value(&headers, CONTENT_TYPE),
+        location: header_value(&headers, LOCATION),
+        headers,
+        text,
+    };
+    match created_id(&body, "LocationGroupID") {
+        Ok((id, _)) => diagnostics.location_group_id = Some(id),
+        Err(e) => diagnostics.fail(e),
+    }
+    Ok(diagnostics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+    use serde_json::json;
+
+    fn request() -> LocationGroupRequest {
+        LocationGroupRequest {
+            description: "Depot".to_string(),
+            location_ids: vec![3],
+            active: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diagnostics_report_the_whole_exchange() {
+        let mock = MockExecutor::new();
+        mock.respond_with_headers(
+            201,
+            &[
+                ("Content-Type", "application/json"),
+                ("X-Server", "n1"),
+                ("Set-Cookie", "session=abc123; HttpOnly"),
+            ],
+            r#"{"LocationGroupID": 77, "Password": "hunter2"}"#,
+        )
+        .respond(400, r#"{"Message": "Bad location"}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let report =
+            diagnose_location_group_create(&client, "https://nimbus.test", "tok", &request())
+                .await
+                .unwrap();
+        assert_eq!(report.build, build_info());
+        assert_eq!(report.url, "https://nimbus.test/RESTApi/LocationGroup");
+        assert_eq!(report.payload["Locations"], json!([{"LocationID": 3}]));
+        assert_eq!(report.request_headers["authenticationtoken"], "<redacted>");
+        assert!(report.request_headers.contains_key("idempotency-key"));
+        assert_eq!(report.status, Some(201));
+        assert_eq!(report.response_headers["x-server"], "n1");
+        assert_eq!(report.response_headers["set-cookie"], "<redacted>");
+        assert_eq!(report.response_body.as_ref().unwrap()["Password"], "***");
+        assert_eq!(report.location_group_id, Some(77));
+        assert!(report.error.is_none());
+
+        // A rejection is still a report, not an error
+        let report =
+            diagnose_location_group_create(&client, "https://nimbus.test", "tok", &request())
+                .await
+                .unwrap();
+        assert_eq!(report.status, Some(400));
+        assert_eq!(report.location_group_id, None);
+        assert!(report.error.unwrap().contains("Bad location"));
+        assert_eq!(mock.requests().len(), 2);
+    }
+}
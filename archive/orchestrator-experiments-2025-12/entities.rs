@@ -1,241 +1,4325 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+use chrono::{Datelike, NaiveDate};
+use futures::stream::{self, Stream, TryStreamExt};
+use reqwest::header::{HeaderMap, HeaderName, LINK};
+use reqwest::Method;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::{json, Value};
-use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use super::audit::{AuditLog, AuditOperation};
+use super::client::{
+    new_idempotency_key, resolve_idempotency_key, with_request_timeout, NimbusClient,
+    DEFAULT_MAX_DESCRIPTION_LENGTH, IDEMPOTENCY_KEY_HEADER,
+};
+use super::error::{FieldError, NimbusError};
+use super::profiles::ProfileStore;
+use super::queue::{OfflineQueue, QueuedRequest};
+use super::request::{header_value, SuccessBody};
+use super::schema::{warn_on_drift, CREATED_LOCATION_GROUP, CREATED_SCHEDULE_GROUP};
+use super::session::{Session, Target};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LocationGroupRequest {
     pub description: String,
     pub location_ids: Vec<i64>,
+    /// Set false to stage the group as inactive until reviewed
+    #[serde(default = "default_active")]
+    pub active: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// An empty, active request, matching what serde fills in for omitted fields
+impl Default for LocationGroupRequest {
+    fn default() -> Self {
+        Self {
+            description: String::new(),
+            location_ids: Vec::new(),
+            active: default_active(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct LocationGroupResponse {
+    /// None for a dry run or a queued create, where nothing is created yet
+    pub location_group_id: Option<i64>,
+    /// Idempotency-Key the create was (or would be) sent with
+    pub idempotency_key: String,
+    /// Nimbus was unreachable, so the create was saved for `flush_queue`
+    pub queued: bool,
+    /// The created group as Nimbus stored it; None for a dry run or queued create
+    pub detail: Option<LocationGroupDetail>,
+    /// The JSON that would be POSTed, set only for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocationGroupDetail {
     pub location_group_id: i64,
+    pub description: String,
+    pub active: bool,
+    pub location_ids: Vec<i64>,
+}
+
+/// A field's stored value and the value an update would give it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange<T> {
+    pub from: T,
+    pub to: T,
+}
+
+/// What `update_location_group` would change, for a confirmation dialog
+///
+/// Location IDs are compared as sets, so reordering alone is not a change.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LocationGroupDiff {
+    pub description: Option<FieldChange<String>>,
+    pub active: Option<FieldChange<bool>>,
+    /// IDs the update adds, in request order
+    pub added_location_ids: Vec<i64>,
+    /// IDs the update removes, in the group's current order
+    pub removed_location_ids: Vec<i64>,
+}
+
+impl LocationGroupDiff {
+    /// True when applying the update would leave the group as it is
+    pub fn is_empty(&self) -> bool {
+        self.description.is_none()
+            && self.active.is_none()
+            && self.added_location_ids.is_empty()
+            && self.removed_location_ids.is_empty()
+    }
+
+    /// Compares the stored group with the request that would replace it
+    pub fn between(current: &LocationGroupDetail, request: &LocationGroupRequest) -> Self {
+        let current_ids: HashSet<i64> = current.location_ids.iter().copied().collect();
+        let mut new_ids = HashSet::new();
+        let added_location_ids = request
+            .location_ids
+            .iter()
+            .copied()
+            .filter(|id| new_ids.insert(*id) && !current_ids.contains(id))
+            .collect();
+        let removed_location_ids = current
+            .location_ids
+            .iter()
+            .copied()
+            .filter(|id| !new_ids.contains(id))
+            .collect();
+
+        let description = normalize_description(&request.description);
+        Self {
+            description: (current.description != description).then(|| FieldChange {
+                from: current.description.clone(),
+                to: description,
+            }),
+            active: (current.active != request.active).then_some(FieldChange {
+                from: current.active,
+                to: request.active,
+            }),
+            added_location_ids,
+            removed_location_ids,
+        }
+    }
 }
 
+/// Where `search_location_groups` did the matching
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchMode {
+    /// Nimbus applied the `Search` filter itself
+    Server,
+    /// Every group was downloaded and matched locally
+    Client,
+}
+
+/// LocationGroups matching a search, and how they were found
+#[derive(Debug, Serialize)]
+pub struct LocationGroupSearch {
+    pub matches: Vec<LocationGroupDetail>,
+    pub mode: SearchMode,
+}
+
+/// One page of results from a Nimbus list endpoint
 #[derive(Debug, Serialize, Deserialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 1-based page number this page was requested as
+    pub page: u32,
+    /// Total matching records, when the server reports it
+    pub total: Option<u64>,
+    pub has_next: bool,
+    /// URL of the next page, when the server sends a `Link` header
+    pub next_url: Option<String>,
+}
+
+/// A Nimbus adhoc (tenant-defined) field on a ScheduleGroup
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdhocField {
+    pub field_name: String,
+    pub value: String,
+}
+
+/// Learning period values Nimbus accepts for `adhoc_LearningPeriod`
+///
+/// Serialized as the bare string Nimbus expects (`"30"`, `"60"`, `"90"`,
+/// `"Term"`). `Custom` covers tenant-specific values outside that set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LearningPeriod {
+    Days30,
+    Days60,
+    Days90,
+    Term,
+    Custom(String),
+}
+
+impl LearningPeriod {
+    /// The value sent to Nimbus
+    pub fn as_str(&self) -> &str {
+        match self {
+            LearningPeriod::Days30 => "30",
+            LearningPeriod::Days60 => "60",
+            LearningPeriod::Days90 => "90",
+            LearningPeriod::Term => "Term",
+            LearningPeriod::Custom(value) => value,
+        }
+    }
+}
+
+impl LearningPeriod {
+    /// The values every Nimbus tenant accepts, in dropdown order
+    pub fn standard() -> [LearningPeriod; 4] {
+        [
+            LearningPeriod::Days30,
+            LearningPeriod::Days60,
+            LearningPeriod::Days90,
+            LearningPeriod::Term,
+        ]
+    }
+
+    /// One of the standard values, or any non-blank custom value such as `"120"`
+    ///
+    /// Tenants define their own values (e.g. `"Semester"`), which
+    /// `list_learning_periods` can return, so a custom value is not checked
+    /// against a fixed set.
+    pub fn is_known(&self) -> bool {
+        match self {
+            LearningPeriod::Custom(value) => !value.trim().is_empty(),
+            _ => true,
+        }
+    }
+}
+
+impl fmt::Display for LearningPeriod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LearningPeriod {
+    type Err = NimbusError;
+
+    /// Parses a stored or user-entered value; unrecognised values become `Custom`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        Ok(match value {
+            "" => {
+                return Err(NimbusError::Validation(
+                    "learning_period must not be empty".to_string(),
+                ))
+            }
+            "30" => LearningPeriod::Days30,
+            "60" => LearningPeriod::Days60,
+            "90" => LearningPeriod::Days90,
+            _ if value.eq_ignore_ascii_case("Term") => LearningPeriod::Term,
+            _ => LearningPeriod::Custom(value.to_string()),
+        })
+    }
+}
+
+impl Serialize for LearningPeriod {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for LearningPeriod {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ScheduleGroupRequest {
     pub description: String,
     pub location_group_id: i64,
     pub start_date: String, // YYYY-MM-DD format
     pub end_date: String,   // YYYY-MM-DD format
-    pub learning_period: String,
+    pub learning_period: LearningPeriod,
+    /// Additional adhoc fields sent alongside `adhoc_LearningPeriod`
+    #[serde(default)]
+    pub adhoc_fields: Vec<AdhocField>,
+    /// Set false to stage the group as inactive until reviewed
+    #[serde(default = "default_active")]
+    pub active: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// A ScheduleGroupRequest whose LocationGroup doesn't exist yet
+///
+/// Used by `create_location_and_schedule`, which fills in the ID once the
+/// LocationGroup has been created.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleGroupDraft {
+    pub description: String,
+    pub start_date: String, // YYYY-MM-DD format
+    pub end_date: String,   // YYYY-MM-DD format
+    pub learning_period: LearningPeriod,
+    #[serde(default)]
+    pub adhoc_fields: Vec<AdhocField>,
+    #[serde(default = "default_active")]
+    pub active: bool,
+}
+
+/// An empty, active 30-day request; fill in at least the IDs, dates and description
+impl Default for ScheduleGroupRequest {
+    fn default() -> Self {
+        ScheduleGroupDraft::default().with_location_group(0)
+    }
+}
+
+impl Default for ScheduleGroupDraft {
+    fn default() -> Self {
+        Self {
+            description: String::new(),
+            start_date: String::new(),
+            end_date: String::new(),
+            learning_period: LearningPeriod::Days30,
+            adhoc_fields: Vec::new(),
+            active: default_active(),
+        }
+    }
+}
+
+impl ScheduleGroupDraft {
+    /// Completes the draft with the LocationGroup it belongs to
+    pub fn with_location_group(self, location_group_id: i64) -> ScheduleGroupRequest {
+        ScheduleGroupRequest {
+            description: self.description,
+            location_group_id,
+            start_date: self.start_date,
+            end_date: self.end_date,
+            learning_period: self.learning_period,
+            adhoc_fields: self.adhoc_fields,
+            active: self.active,
+        }
+    }
+}
+
+impl ScheduleGroupRequest {
+    /// Starts a request from Rust code; see `ScheduleGroupRequestBuilder`
+    pub fn builder() -> ScheduleGroupRequestBuilder {
+        ScheduleGroupRequestBuilder::default()
+    }
+
+    /// A builder holding this request's fields, to change some and re-validate
+    pub fn into_builder(self) -> ScheduleGroupRequestBuilder {
+        ScheduleGroupRequestBuilder {
+            request: self,
+            max_description_length: DEFAULT_MAX_DESCRIPTION_LENGTH,
+        }
+    }
+}
+
+/// Assembles a ScheduleGroupRequest field by field, validating it in `build`
+///
+/// Starts from `ScheduleGroupRequest::default()`: active, 30-day learning
+/// period, no adhoc fields.
+///
+/// ```ignore
+/// let request = ScheduleGroupRequest::builder()
+///     .description("Term 1")
+///     .location_group_id(42)
+///     .dates("2025-01-01", "2025-03-31")
+///     .add_adhoc_field("adhoc_Campus", "North")
+///     .build()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScheduleGroupRequestBuilder {
+    request: ScheduleGroupRequest,
+    max_description_length: usize,
+}
+
+impl Default for ScheduleGroupRequestBuilder {
+    fn default() -> Self {
+        ScheduleGroupRequest::default().into_builder()
+    }
+}
+
+impl ScheduleGroupRequestBuilder {
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.request.description = description.into();
+        self
+    }
+
+    pub fn location_group_id(mut self, location_group_id: i64) -> Self {
+        self.request.location_group_id = location_group_id;
+        self
+    }
+
+    /// GroupStartDate and GroupEndDate, both YYYY-MM-DD
+    pub fn dates(mut self, start_date: impl Into<String>, end_date: impl Into<String>) -> Self {
+        self.request.start_date = start_date.into();
+        self.request.end_date = end_date.into();
+        self
+    }
+
+    pub fn learning_period(mut self, learning_period: LearningPeriod) -> Self {
+        self.request.learning_period = learning_period;
+        self
+    }
+
+    /// Appends an adhoc field, sent after `adhoc_LearningPeriod`
+    pub fn add_adhoc_field(
+        mut self,
+        field_name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.request.adhoc_fields.push(AdhocField {
+            field_name: field_name.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    pub fn active(mut self, active: bool) -> Self {
+        self.request.active = active;
+        self
+    }
+
+    /// Description limit `build` checks against; `DEFAULT_MAX_DESCRIPTION_LENGTH` unless set
+    pub fn max_description_length(mut self, max_description_length: usize) -> Self {
+        self.max_description_length = max_description_length;
+        self
+    }
+
+    /// The request, if it passes `validate_schedule_group_request`
+    ///
+    /// Every failing field is reported in one `NimbusError::Validation`.
+    pub fn build(self) -> Result<ScheduleGroupRequest, NimbusError> {
+        validate_schedule_group_request(&self.request, self.max_description_length)?;
+        Ok(self.request)
+    }
+}
+
+/// Groups are created active unless the caller says otherwise
+fn default_active() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ScheduleGroupResponse {
+    /// None for a dry run or a queued create, where nothing is created yet
+    pub schedule_group_id: Option<i64>,
+    /// Idempotency-Key the create was (or would be) sent with
+    pub idempotency_key: String,
+    /// Nimbus was unreachable, so the create was saved for `flush_queue`
+    pub queued: bool,
+    /// The JSON that would be POSTed, set only for a dry run
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payload: Option<Value>,
+}
+
+/// IDs created by `create_location_and_schedule`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LocationAndScheduleResponse {
+    pub location_group_id: i64,
     pub schedule_group_id: i64,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleGroupDetail {
+    pub schedule_group_id: i64,
+    pub description: String,
+    pub location_group_id: i64,
+    pub start_date: String, // YYYY-MM-DD format
+    pub end_date: String,   // YYYY-MM-DD format
+    pub active: bool,
+    pub adhoc_fields: Vec<AdhocField>,
+}
+
 /// Creates a LocationGroup via REST API
 /// 
 /// # Arguments
-/// * `base_url` - The base URL for the Nimbus API
-/// * `token` - Authentication token
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `queue` - Offline queue network failures are saved to
+/// * `audit` - Audit log the create is recorded in
 /// * `request` - LocationGroupRequest with description and location_ids
+/// * `idempotency_key` - Optional caller-supplied key; a UUID is generated if omitted.
+///   A key that isn't a valid header value fails with `NimbusError::Validation`
+/// * `dry_run` - When true, validate and return the payload without sending it
+/// * `profile` - Profile to send to; the active one when omitted
 ///
 /// # Returns
-/// LocationGroupID of the created group
+/// LocationGroupID and detail of the created group and the idempotency key
+/// used, or `queued: true` if Nimbus was unreachable
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_location_group(
-    base_url: String,
-    token: String,
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    queue: tauri::State<'_, OfflineQueue>,
+    audit: tauri::State<'_, AuditLog>,
     request: LocationGroupRequest,
-) -> Result<LocationGroupResponse, String> {
+    idempotency_key: Option<String>,
+    dry_run: Option<bool>,
+    profile: Option<String>,
+) -> Result<LocationGroupResponse, NimbusError> {
+    let idempotency_key = resolve_idempotency_key(idempotency_key)?;
+    if dry_run.unwrap_or(false) {
+        return dry_run_location_group(&request, idempotency_key, client.max_description_length());
+    }
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let result =
+        post_location_group_with_body(&client, &base_url, &token, &request, &idempotency_key).await;
+    let actor = Some(profile.clone());
+    let (location_group_id, body) = match result {
+        Ok(created) => created,
+        // Unreachable server: keep the create for replay under the same key
+        Err(NimbusError::Network(_) | NimbusError::CircuitOpen { .. }) => {
+            queue.push(
+                &profile,
+                &base_url,
+                &idempotency_key,
+                QueuedRequest::LocationGroup(request),
+            )?;
+            audit.record_queued("LocationGroup", actor);
+            return Ok(LocationGroupResponse {
+                location_group_id: None,
+                idempotency_key,
+                queued: true,
+                detail: None,
+                payload: None,
+            });
+        }
+        Err(e) => {
+            audit.record(
+                AuditOperation::Create,
+                "LocationGroup",
+                None,
+                actor,
+                Some(&e),
+            );
+            return Err(e);
+        }
+    };
+    audit.record(
+        AuditOperation::Create,
+        "LocationGroup",
+        Some(location_group_id),
+        actor,
+        None,
+    );
+
+    let detail =
+        created_location_group_detail(&client, &base_url, &token, location_group_id, &body).await;
+
+    Ok(LocationGroupResponse {
+        location_group_id: Some(location_group_id),
+        idempotency_key,
+        queued: false,
+        detail,
+        payload: None,
+    })
+}
+
+/// Builds the dry-run response: the validated payload, with no HTTP call
+fn dry_run_location_group(
+    request: &LocationGroupRequest,
+    idempotency_key: String,
+    max_description_length: usize,
+) -> Result<LocationGroupResponse, NimbusError> {
+    Ok(LocationGroupResponse {
+        location_group_id: None,
+        idempotency_key,
+        queued: false,
+        detail: None,
+        payload: Some(prepare_location_group_payload(
+            request,
+            max_description_length,
+        )?),
+    })
+}
+
+/// Validates a LocationGroupRequest and builds its create payload
+pub(crate) fn prepare_location_group_payload(
+    request: &LocationGroupRequest,
+    max_description_length: usize,
+) -> Result<Value, NimbusError> {
+    // Reject bad input and drop duplicate IDs locally; the API's errors are opaque
+    validate_location_group_request(request, max_description_length)?;
+    let location_ids = validate_location_ids(&request.location_ids)?;
+
+    Ok(build_location_group_payload(&LocationGroupRequest {
+        location_ids,
+        ..request.clone()
+    }))
+}
+
+/// Trims a description and collapses each inner run of whitespace to one space
+///
+/// Nimbus stores descriptions verbatim, so a pasted "West Coast \n" would sit
+/// next to "West Coast" as a separate, identical-looking group.
+pub fn normalize_description(description: &str) -> String {
+    description.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// The create payload for `req`, with its description normalized but otherwise
+/// exactly as given (no validation)
+pub fn build_location_group_payload(req: &LocationGroupRequest) -> Value {
     // Build locations array
-    let locations: Vec<Value> = request
+    let locations: Vec<Value> = req
         .location_ids
         .iter()
         .map(|id| json!({"LocationID": id}))
         .collect();
 
     // Build request payload
-    let payload = json!({
-        "Description": request.description,
-        "Active": true,
+    json!({
+        "Description": normalize_description(&req.description),
+        "Active": req.active,
         "Locations": locations
-    });
+    })
+}
 
-    // Create HTTP client
-    let client = reqwest::Client::new();
+/// POSTs a new LocationGroup, shared by the single and batch create commands
+///
+/// Returns the new LocationGroupID.
+pub(crate) async fn post_location_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    request: &LocationGroupRequest,
+    idempotency_key: &str,
+) -> Result<i64, NimbusError> {
+    post_location_group_with_body(client, base_url, token, request, idempotency_key)
+        .await
+        .map(|(location_group_id, _)| location_group_id)
+}
 
-    // Prepare headers
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert(
-        "AuthenticationToken",
-        reqwest::header::HeaderValue::from_str(&token)
-            .map_err(|e| format!("Invalid token header: {}", e))?,
-    );
-    headers.insert(
-        "Authorization",
-        reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
-            .map_err(|e| format!("Invalid authorization header: {}", e))?,
-    );
-    headers.insert(
-        "Accept",
-        reqwest::header::HeaderValue::from_static("application/json"),
-    );
-    headers.insert(
-        "Content-Type",
-        reqwest::header::HeaderValue::from_static("application/json"),
-    );
+/// Like `post_location_group`, also returning the parsed response body
+#[tracing::instrument(skip(client, token), fields(status, correlation_id))]
+async fn post_location_group_with_body(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    request: &LocationGroupRequest,
+    idempotency_key: &str,
+) -> Result<(i64, Value), NimbusError> {
+    let payload = prepare_location_group_payload(request, client.max_description_length())?;
 
-    // Make POST request
-    let url = format!("{}/RESTApi/LocationGroup", base_url);
+    tracing::info!(%base_url, "Creating location group");
+    // The idempotency key lets Nimbus dedupe replays, so the POST is safe to retry
     let response = client
-        .post(&url)
-        .headers(headers)
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to create location group: {}", e))?;
+        .request(Method::POST, base_url, token, "/RESTApi/LocationGroup")
+        .header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
+        .json_body(payload)
+        .payload_encoding(client.payload_encoding())
+        .idempotent(true)
+        .captured()
+        .send_raw()
+        .await?;
 
-    // Check response status
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!(
-            "API error ({}): {}",
-            response.status(),
-            error_text
-        ));
+    let (location_group_id, body) = created_id(&response, "LocationGroupID")?;
+    // Bare-ID and Location-header answers have no body to check
+    if !body.is_null() {
+        warn_on_drift(&CREATED_LOCATION_GROUP, &body);
+    }
+    Ok((location_group_id, body))
+}
+
+/// Detail of a just-created LocationGroup
+///
+/// Parsed from the create response when Nimbus echoes the entity, otherwise
+/// fetched once. The group already exists at this point, so a failed fetch is
+/// logged and reported as None rather than failing the create.
+async fn created_location_group_detail(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+    body: &Value,
+) -> Option<LocationGroupDetail> {
+    if body.get("Description").is_some() && body.get("Locations").is_some() {
+        if let Ok(detail) = parse_location_group_detail(body, location_group_id) {
+            return Some(detail);
+        }
+    }
+
+    match fetch_location_group(client, base_url, token, location_group_id).await {
+        Ok(detail) => Some(detail),
+        Err(e) => {
+            tracing::warn!(location_group_id, error = %e.sanitized_message(), "Failed to fetch created location group");
+            None
+        }
     }
+}
+
+/// Fetches an existing LocationGroup via REST API
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `location_group_id` - ID of the LocationGroup to fetch
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// LocationGroupDetail with description, active flag, and location IDs.
+/// A 404 is reported as `NimbusError::NotFound`.
+#[tauri::command]
+pub async fn get_location_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    location_group_id: i64,
+    profile: Option<String>,
+) -> Result<LocationGroupDetail, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    fetch_location_group(&client, &base_url, &token, location_group_id).await
+}
+
+/// GETs a LocationGroup by ID
+pub(crate) async fn fetch_location_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+) -> Result<LocationGroupDetail, NimbusError> {
+    let response_body =
+        fetch_location_group_body(client, base_url, token, location_group_id).await?;
+    parse_location_group_detail(&response_body, location_group_id)
+}
 
-    // Parse response - expecting LocationGroupID in response
-    let response_body: Value = response
-        .json()
+/// GETs a LocationGroup by ID, keeping the fields LocationGroupDetail drops
+async fn fetch_location_group_body(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+) -> Result<Value, NimbusError> {
+    let path = format!("/RESTApi/LocationGroup/{}", location_group_id);
+    client
+        .request(Method::GET, base_url, token, &path)
+        .not_found("LocationGroup", location_group_id)
+        .cached()
+        .send_parsed()
         .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+}
+
+/// Finds the ID of a just-created entity, and the JSON body if there was one
+///
+/// Nimbus usually echoes the entity with its `field`, but some endpoints
+/// answer with the bare ID as text, or an empty body and a `Location` header
+/// ending in the ID. The body is `Value::Null` unless it was a JSON object.
+pub(crate) fn created_id(response: &SuccessBody, field: &str) -> Result<(i64, Value), NimbusError> {
+    let text = response.text.trim();
+    if let Ok(body) = serde_json::from_str::<Value>(text) {
+        if let Some(id) = id_field(&body, field).or_else(|| body.as_i64()) {
+            let body = if body.is_object() { body } else { Value::Null };
+            return Ok((id, body));
+        }
+    }
+
+    // Location: /RESTApi/LocationGroup/123
+    let location_id = response
+        .location
+        .as_deref()
+        .and_then(|location| location.trim_end_matches('/').rsplit('/').next())
+        .and_then(|segment| segment.parse().ok());
+    match location_id {
+        Some(id) => Ok((id, Value::Null)),
+        None if text.is_empty() => Err(NimbusError::UnexpectedEmptyBody {
+            status: response.status,
+        }),
+        None if !response.is_json() => Err(NimbusError::Parse(format!(
+            "{} not found in {} response",
+            field,
+            response.content_type.as_deref().unwrap_or_default()
+        ))),
+        None => Err(NimbusError::Parse(format!(
+            "{} not found in response",
+            field
+        ))),
+    }
+}
 
-    let location_group_id = response_body
-        .get("LocationGroupID")
+/// Reads an integer ID field, matching the key case-insensitively
+///
+/// Nimbus versions disagree on casing (`LocationGroupID` vs `LocationGroupId`),
+/// so an exact match is tried first and then any key equal ignoring case.
+fn id_field(body: &Value, name: &str) -> Option<i64> {
+    let object = body.as_object()?;
+    object
+        .get(name)
+        .or_else(|| {
+            object
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value)
+        })
         .and_then(|v| v.as_i64())
-        .ok_or_else(|| "LocationGroupID not found in response".to_string())?;
+}
+
+/// Parses a Nimbus LocationGroup entity into a LocationGroupDetail
+///
+/// `fallback_id` is used when the body does not echo `LocationGroupID`.
+fn parse_location_group_detail(
+    body: &Value,
+    fallback_id: i64,
+) -> Result<LocationGroupDetail, NimbusError> {
+    let location_group_id = id_field(body, "LocationGroupID").unwrap_or(fallback_id);
+
+    let description = body
+        .get("Description")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| NimbusError::Parse("Description not found in response".to_string()))?
+        .to_string();
+
+    let active = body.get("Active").and_then(|v| v.as_bool()).unwrap_or(true);
+
+    let location_ids = match body.get("Locations") {
+        Some(locations) => parse_location_ids(locations)?,
+        None => Vec::new(),
+    };
 
-    Ok(LocationGroupResponse { location_group_id })
+    Ok(LocationGroupDetail {
+        location_group_id,
+        description,
+        active,
+        location_ids,
+    })
 }
 
-/// Creates a ScheduleGroup via REST API
+/// Reads the IDs out of a LocationGroup's `Locations` array
+///
+/// Entries are usually `{"LocationID": n}` objects, but some Nimbus versions
+/// send bare integers; both are accepted. `null` is an empty list. Anything
+/// else fails with a `Parse` error naming the offending entry's index.
+pub fn parse_location_ids(value: &Value) -> Result<Vec<i64>, NimbusError> {
+    if value.is_null() {
+        return Ok(Vec::new());
+    }
+    let locations = value
+        .as_array()
+        .ok_or_else(|| NimbusError::Parse(format!("Locations is not an array: {}", value)))?;
+
+    locations
+        .iter()
+        .enumerate()
+        .map(|(index, location)| {
+            location
+                .as_i64()
+                .or_else(|| id_field(location, "LocationID"))
+                .ok_or_else(|| {
+                    NimbusError::Parse(format!(
+                        "Locations[{}] has no integer LocationID: {}",
+                        index, location
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// Updates an existing LocationGroup via REST API
 ///
 /// # Arguments
-/// * `base_url` - The base URL for the Nimbus API
-/// * `token` - Authentication token
-/// * `request` - ScheduleGroupRequest with description, location_group_id, dates, and learning_period
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the update is recorded in
+/// * `location_group_id` - ID of the LocationGroup to update
+/// * `request` - LocationGroupRequest with the new description and location_ids
+/// * `profile` - Profile to send to; the active one when omitted
 ///
 /// # Returns
-/// ScheduleGroupID of the created group
+/// The updated LocationGroupDetail. When the server replies with an empty body
+/// the detail is built from the request that was sent.
 #[tauri::command]
-pub async fn create_schedule_group(
-    base_url: String,
-    token: String,
-    request: ScheduleGroupRequest,
-) -> Result<ScheduleGroupResponse, String> {
-    // Build adhoc fields array
-    let adhoc_fields = json!([
-        {
-            "FieldName": "adhoc_LearningPeriod",
-            "Value": request.learning_period
-        }
-    ]);
-
-    // Build request payload
-    let payload = json!({
-        "Description": request.description,
-        "Active": true,
-        "LocationGroupID": request.location_group_id,
-        "GroupStartDate": request.start_date,
-        "GroupEndDate": request.end_date,
-        "AdhocFields": adhoc_fields
-    });
+pub async fn update_location_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    location_group_id: i64,
+    mut request: LocationGroupRequest,
+    profile: Option<String>,
+) -> Result<LocationGroupDetail, NimbusError> {
+    validate_location_group_request(&request, client.max_description_length())?;
+    request.location_ids = validate_location_ids(&request.location_ids)?;
 
-    // Create HTTP client
-    let client = reqwest::Client::new();
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
 
-    // Prepare headers
-    let mut headers = reqwest::header::HeaderMap::new();
-    headers.insert(
-        "AuthenticationToken",
-        reqwest::header::HeaderValue::from_str(&token)
-            .map_err(|e| format!("Invalid token header: {}", e))?,
-    );
-    headers.insert(
-        "Authorization",
-        reqwest::header::HeaderValue::from_str(&format!("Bearer {}", token))
-            .map_err(|e| format!("Invalid authorization header: {}", e))?,
-    );
-    headers.insert(
-        "Accept",
-        reqwest::header::HeaderValue::from_static("application/json"),
-    );
-    headers.insert(
-        "Content-Type",
-        reqwest::header::HeaderValue::from_static("application/json"),
+    let result = put_location_group(&client, &base_url, &token, location_group_id, request).await;
+    audit.record(
+        AuditOperation::Update,
+        "LocationGroup",
+        Some(location_group_id),
+        Some(profile),
+        result.as_ref().err(),
     );
+    result
+}
 
-    // Make POST request
-    let url = format!("{}/RESTApi/ScheduleGroup", base_url);
-    let response = client
-        .post(&url)
-        .headers(headers)
-        .json(&payload)
-        .send()
-        .await
-        .map_err(|e| format!("Failed to create schedule group: {}", e))?;
+/// Previews what `update_location_group` would change, without sending it
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `location_group_id` - ID of the LocationGroup to compare against
+/// * `request` - LocationGroupRequest the update would send
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// LocationGroupDiff of the description, active flag, and added/removed
+/// location IDs; empty when the update would change nothing. The request is
+/// validated as `update_location_group` would.
+#[tauri::command]
+pub async fn diff_location_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    location_group_id: i64,
+    request: LocationGroupRequest,
+    profile: Option<String>,
+) -> Result<LocationGroupDiff, NimbusError> {
+    validate_location_group_request(&request, client.max_description_length())?;
 
-    // Check response status
-    if !response.status().is_success() {
-        let error_text = response
-            .text()
-            .await
-            .unwrap_or_else(|_| "Unknown error".to_string());
-        return Err(format!(
-            "API error ({}): {}",
-            response.status(),
-            error_text
-        ));
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    preview_location_group_update(&client, &base_url, &token, location_group_id, &request).await
+}
+
+/// GETs the current group and diffs it against `request`
+pub(crate) async fn preview_location_group_update(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+    request: &LocationGroupRequest,
+) -> Result<LocationGroupDiff, NimbusError> {
+    let current = fetch_location_group(client, base_url, token, location_group_id).await?;
+    Ok(LocationGroupDiff::between(&current, request))
+}
+
+/// PUTs a full LocationGroup whose `location_ids` have already been validated
+pub(crate) async fn put_location_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+    request: LocationGroupRequest,
+) -> Result<LocationGroupDetail, NimbusError> {
+    let payload = build_location_group_update_payload(location_group_id, &request);
+
+    // Make PUT request
+    let path = format!("/RESTApi/LocationGroup/{}", location_group_id);
+    let body_text = client
+        .request(Method::PUT, base_url, token, &path)
+        .json_body(payload)
+        .send_text()
+        .await?;
+
+    // Some Nimbus versions reply 200 with no body, others echo the entity
+    if body_text.trim().is_empty() {
+        return Ok(LocationGroupDetail {
+            location_group_id,
+            description: normalize_description(&request.description),
+            active: request.active,
+            location_ids: request.location_ids,
+        });
     }
 
-    // Parse response - expecting ScheduleGroupID in response
-    let response_body: Value = response
-        .json()
-        .await
-        .map_err(|e| format!("Failed to parse response: {}", e))?;
+    let response_body: Value =
+        serde_json::from_str(&body_text).map_err(|e| NimbusError::Parse(e.to_string()))?;
 
-    let schedule_group_id = response_body
-        .get("ScheduleGroupID")
-        .and_then(|v| v.as_i64())
-        .ok_or_else(|| "ScheduleGroupID not found in response".to_string())?;
+    parse_location_group_detail(&response_body, location_group_id)
+}
+
+/// Deletes a LocationGroup via REST API
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the deletion is recorded in
+/// * `location_group_id` - ID of the LocationGroup to delete
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// Nothing on success (200 or 204)
+#[tauri::command]
+pub async fn delete_location_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    location_group_id: i64,
+    profile: Option<String>,
+) -> Result<(), NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
 
-    Ok(ScheduleGroupResponse { schedule_group_id })
+    let result = remove_location_group(&client, &base_url, &token, location_group_id).await;
+    audit.record(
+        AuditOperation::Delete,
+        "LocationGroup",
+        Some(location_group_id),
+        Some(profile),
+        result.as_ref().err(),
+    );
+    result
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// DELETEs a LocationGroup by ID
+pub(crate) async fn remove_location_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+) -> Result<(), NimbusError> {
+    // Make DELETE request - 200 and 204 both mean the group is gone
+    let path = format!("/RESTApi/LocationGroup/{}", location_group_id);
+    client
+        .request(Method::DELETE, base_url, token, &path)
+        .send_text()
+        .await?;
 
-    #[test]
-    fn test_location_group_request_serialization() {
-        let request = LocationGroupRequest {
-            description: "Test Group".to_string(),
-            location_ids: vec![1, 2, 3],
-        };
-        let json = serde_json::to_value(&request).unwrap();
-        assert_eq!(json["description"], "Test Group");
-        assert_eq!(json["location_ids"].as_array().unwrap().len(), 3);
-    }
+    Ok(())
+}
 
-    #[test]
-    fn test_schedule_group_request_serialization() {
-        let request = ScheduleGroupRequest {
-            description: "Test Schedule".to_string(),
-            location_group_id: 42,
-            start_date: "2025-01-01".to_string(),
-            end_date: "2025-12-31".to_string(),
-            learning_period: "30".to_string(),
-        };
-        let json = serde_json::to_value(&request).unwrap();
+/// Activates or deactivates a LocationGroup, leaving every other field as is
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the update is recorded in
+/// * `location_group_id` - ID of the LocationGroup to update
+/// * `active` - New Active flag
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// The group's Active flag as reported by the server after the change
+#[tauri::command]
+pub async fn set_location_group_active(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    location_group_id: i64,
+    active: bool,
+    profile: Option<String>,
+) -> Result<bool, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let result =
+        patch_location_group_active(&client, &base_url, &token, location_group_id, active).await;
+    audit.record(
+        AuditOperation::Update,
+        "LocationGroup",
+        Some(location_group_id),
+        Some(profile),
+        result.as_ref().err(),
+    );
+    result
+}
+
+/// PATCHes only the Active flag, falling back to GET-merge-PUT when the
+/// server answers 405 or 501
+pub(crate) async fn patch_location_group_active(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+    active: bool,
+) -> Result<bool, NimbusError> {
+    // Make PATCH request
+    let path = format!("/RESTApi/LocationGroup/{}", location_group_id);
+    let result = client
+        .request(Method::PATCH, base_url, token, &path)
+        .json_body(json!({ "Active": active }))
+        .not_found("LocationGroup", location_group_id)
+        .send_text()
+        .await;
+
+    // Check response status
+    let body_text = match result {
+        Err(NimbusError::Http {
+            status: 405 | 501, ..
+        }) => {
+            tracing::info!(%path, "PATCH unsupported, falling back to PUT");
+            let detail = fetch_location_group(client, base_url, token, location_group_id).await?;
+            let request = LocationGroupRequest {
+                description: detail.description,
+                location_ids: detail.location_ids,
+                active,
+            };
+            let updated =
+                put_location_group(client, base_url, token, location_group_id, request).await?;
+            return Ok(updated.active);
+        }
+        result => result?,
+    };
+
+    // Prefer the echoed flag; an empty or partial body needs a GET to confirm
+    let echoed = serde_json::from_str::<Value>(&body_text)
+        .ok()
+        .and_then(|body| body.get("Active").and_then(Value::as_bool));
+    match echoed {
+        Some(active) => Ok(active),
+        None => Ok(
+            fetch_location_group(client, base_url, token, location_group_id)
+                .await?
+                .active,
+        ),
+    }
+}
+
+/// Adds locations to a LocationGroup without resending the ones it already has
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the update is recorded in
+/// * `location_group_id` - ID of the LocationGroup to add to
+/// * `ids` - LocationIDs to add
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// The group's location IDs after the change
+#[tauri::command]
+pub async fn add_locations(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    location_group_id: i64,
+    ids: Vec<i64>,
+    profile: Option<String>,
+) -> Result<Vec<i64>, NimbusError> {
+    change_locations(
+        &client,
+        &session,
+        &profiles,
+        profile.as_deref(),
+        &audit,
+        location_group_id,
+        LocationDelta::Add,
+        &ids,
+    )
+    .await
+}
+
+/// Removes locations from a LocationGroup, leaving the rest untouched
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the update is recorded in
+/// * `location_group_id` - ID of the LocationGroup to remove from
+/// * `ids` - LocationIDs to remove
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// The group's location IDs after the change
+#[tauri::command]
+pub async fn remove_locations(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    location_group_id: i64,
+    ids: Vec<i64>,
+    profile: Option<String>,
+) -> Result<Vec<i64>, NimbusError> {
+    change_locations(
+        &client,
+        &session,
+        &profiles,
+        profile.as_deref(),
+        &audit,
+        location_group_id,
+        LocationDelta::Remove,
+        &ids,
+    )
+    .await
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn change_locations(
+    client: &NimbusClient,
+    session: &Session,
+    profiles: &ProfileStore,
+    profile: Option<&str>,
+    audit: &AuditLog,
+    location_group_id: i64,
+    delta: LocationDelta,
+    ids: &[i64],
+) -> Result<Vec<i64>, NimbusError> {
+    let ids = validate_location_ids(ids)?;
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(profiles, profile)?;
+
+    let result =
+        apply_location_delta(client, &base_url, &token, location_group_id, delta, &ids).await;
+    audit.record(
+        AuditOperation::Update,
+        "LocationGroup",
+        Some(location_group_id),
+        Some(profile),
+        result.as_ref().err(),
+    );
+    result
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum LocationDelta {
+    Add,
+    Remove,
+}
+
+impl LocationDelta {
+    /// `current` with `ids` added or removed, keeping the existing order
+    fn apply(self, mut current: Vec<i64>, ids: &[i64]) -> Vec<i64> {
+        match self {
+            LocationDelta::Add => {
+                for id in ids {
+                    if !current.contains(id) {
+                        current.push(*id);
+                    }
+                }
+            }
+            LocationDelta::Remove => current.retain(|id| !ids.contains(id)),
+        }
+        current
+    }
+}
+
+/// POSTs (add) or DELETEs (remove) `ids` on the group's Locations sub-resource
+///
+/// Servers without that sub-resource get a GET, a local merge, and a full
+/// PUT instead, which can overwrite a concurrent edit, so it is logged as a
+/// warning. Returns the location IDs the group ends up with.
+pub(crate) async fn apply_location_delta(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+    delta: LocationDelta,
+    ids: &[i64],
+) -> Result<Vec<i64>, NimbusError> {
+    let method = match delta {
+        LocationDelta::Add => Method::POST,
+        LocationDelta::Remove => Method::DELETE,
+    };
+    let locations: Vec<Value> = ids.iter().map(|id| json!({"LocationID": id})).collect();
+
+    // Make POST/DELETE request - repeating either leaves the same locations
+    let path = format!("/RESTApi/LocationGroup/{}/Locations", location_group_id);
+    let result = client
+        .request(method, base_url, token, &path)
+        .json_body(json!({ "Locations": locations }))
+        .idempotent(true)
+        .send_text()
+        .await;
+
+    match result {
+        Ok(_) => {}
+        // A missing group is reported by the GET below
+        Err(NimbusError::Http {
+            status: 404 | 405 | 501,
+            ..
+        }) => {
+            tracing::warn!(
+                location_group_id,
+                ?delta,
+                "Location endpoints unavailable, falling back to a full update"
+            );
+            let detail = fetch_location_group(client, base_url, token, location_group_id).await?;
+            let location_ids = delta.apply(detail.location_ids, ids);
+            if location_ids.is_empty() {
+                return Err(NimbusError::Validation(format!(
+                    "Removing these would leave LocationGroup {} with no locations",
+                    location_group_id
+                )));
+            }
+            let request = LocationGroupRequest {
+                description: detail.description,
+                location_ids,
+                active: detail.active,
+            };
+            let updated =
+                put_location_group(client, base_url, token, location_group_id, request).await?;
+            return Ok(updated.location_ids);
+        }
+        Err(e) => return Err(e),
+    }
+
+    let detail = fetch_location_group(client, base_url, token, location_group_id).await?;
+    Ok(detail.location_ids)
+}
+
+/// Lists LocationGroups via REST API, one page at a time
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `page` - 1-based page number
+/// * `page_size` - Maximum number of groups per page
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// A Page of LocationGroupDetail with the total count when the server reports it
+#[tauri::command]
+pub async fn list_location_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    page: u32,
+    page_size: u32,
+    profile: Option<String>,
+) -> Result<Page<LocationGroupDetail>, NimbusError> {
+    if page == 0 || page_size == 0 {
+        return Err(NimbusError::Validation(
+            "page and page_size must be at least 1".to_string(),
+        ));
+    }
+
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    fetch_location_group_page(&client, &base_url, &token, page, page_size).await
+}
+
+/// Lists every LocationGroup, fetching as many pages as it takes
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// All LocationGroupDetail records, in server order
+#[tauri::command]
+pub async fn list_all_location_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<Vec<LocationGroupDetail>, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let groups = all_pages(|page| {
+        fetch_location_group_page(&client, &base_url, &token, page, ALL_PAGES_PAGE_SIZE)
+    });
+    with_request_timeout(timeout_ms, groups.try_collect()).await
+}
+
+/// Finds LocationGroups whose description contains `query`, ignoring case
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `query` - Text to look for in the description
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// The matching LocationGroupDetail records in server order, and whether
+/// Nimbus filtered them (`server`) or every group was fetched and matched
+/// locally (`client`)
+#[tauri::command]
+pub async fn search_location_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    query: String,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<LocationGroupSearch, NimbusError> {
+    if query.trim().is_empty() {
+        return Err(NimbusError::Validation(
+            "query must not be empty".to_string(),
+        ));
+    }
+
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    with_request_timeout(
+        timeout_ms,
+        find_location_groups(&client, &base_url, &token, query.trim()),
+    )
+    .await
+}
+
+/// Query param Nimbus versions with server-side search filter descriptions by
+const SEARCH_QUERY_PARAM: &str = "Search";
+
+/// Pages through LocationGroups with the `Search` filter, matching locally too
+///
+/// Servers that don't know the filter either reject it (400/422/501), in
+/// which case the listing is repeated without it, or silently ignore it and
+/// return every group. Matching each record locally covers both; the mode is
+/// `Server` only when nothing the server returned had to be dropped.
+pub(crate) async fn find_location_groups(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    query: &str,
+) -> Result<LocationGroupSearch, NimbusError> {
+    let (matches, fetched) = match matching_pages(client, base_url, token, query, true).await {
+        Err(NimbusError::Http {
+            status: status @ (400 | 422 | 501),
+            ..
+        }) => {
+            tracing::info!(status, "Search filter rejected, matching locally");
+            let (matches, _) = matching_pages(client, base_url, token, query, false).await?;
+            return Ok(LocationGroupSearch {
+                matches,
+                mode: SearchMode::Client,
+            });
+        }
+        result => result?,
+    };
+
+    let mode = if matches.len() == fetched {
+        SearchMode::Server
+    } else {
+        SearchMode::Client
+    };
+    Ok(LocationGroupSearch { matches, mode })
+}
+
+/// Every LocationGroup containing `query`, and how many were fetched to find them
+async fn matching_pages(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    query: &str,
+    send_filter: bool,
+) -> Result<(Vec<LocationGroupDetail>, usize), NimbusError> {
+    let filter = send_filter.then_some(query);
+    let groups: Vec<LocationGroupDetail> = all_pages(|page| {
+        fetch_location_groups(client, base_url, token, page, ALL_PAGES_PAGE_SIZE, filter)
+    })
+    .try_collect()
+    .await?;
+
+    let fetched = groups.len();
+    let needle = query.to_lowercase();
+    let matches = groups
+        .into_iter()
+        .filter(|group| group.description.to_lowercase().contains(&needle))
+        .collect();
+    Ok((matches, fetched))
+}
+
+/// GETs one page of LocationGroups
+pub(crate) async fn fetch_location_group_page(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    page: u32,
+    page_size: u32,
+) -> Result<Page<LocationGroupDetail>, NimbusError> {
+    fetch_location_groups(client, base_url, token, page, page_size, None).await
+}
+
+/// GETs one page of LocationGroups, filtered by `search` when given
+async fn fetch_location_groups(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    page: u32,
+    page_size: u32,
+    search: Option<&str>,
+) -> Result<Page<LocationGroupDetail>, NimbusError> {
+    // Make GET request
+    let mut request = client
+        .request(Method::GET, base_url, token, "/RESTApi/LocationGroup")
+        .query("PageNumber", page)
+        .query("PageSize", page_size);
+    if let Some(search) = search {
+        request = request.query(SEARCH_QUERY_PARAM, search);
+    }
+    let response = request.send_raw().await?;
+    let response_body: Value = response.json()?;
+
+    let (records, total) = split_page_body(&response_body)?;
+    let items = records
+        .iter()
+        .map(|record| parse_location_group_detail(record, 0))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(PageHeaders::parse(&response.headers, base_url).page(items, page, page_size, total))
+}
+
+/// Splits a list response into its records and optional total count
+///
+/// Nimbus returns either a bare array or an envelope of the form
+/// `{"Items": [...], "TotalCount": n}`.
+fn split_page_body(body: &Value) -> Result<(&Vec<Value>, Option<u64>), NimbusError> {
+    if let Some(records) = body.as_array() {
+        return Ok((records, None));
+    }
+
+    let records = body
+        .get("Items")
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| NimbusError::Parse("Items not found in response".to_string()))?;
+    let total = body.get("TotalCount").and_then(|v| v.as_u64());
+
+    Ok((records, total))
+}
+
+/// Assembles a Page, deciding whether another page follows
+///
+/// With a total count that is exact; without one, a short page is taken to be
+/// the last.
+fn build_page<T>(items: Vec<T>, page: u32, page_size: u32, total: Option<u64>) -> Page<T> {
+    let has_next = match total {
+        Some(total) => u64::from(page) * u64::from(page_size) < total,
+        None => items.len() >= page_size as usize,
+    };
+
+    Page {
+        items,
+        page,
+        total,
+        has_next,
+        next_url: None,
+    }
+}
+
+/// Paging info some deployments send as headers instead of in the body
+#[derive(Debug, Default, PartialEq)]
+struct PageHeaders {
+    /// `X-Total-Count`
+    total: Option<u64>,
+    /// Whether a `Link` header was sent at all, which settles `has_next`
+    has_link: bool,
+    /// The `Link` header's `rel="next"` target, resolved against the base URL
+    next_url: Option<String>,
+}
+
+impl PageHeaders {
+    fn parse(headers: &HeaderMap, base_url: &str) -> Self {
+        let total = header_value(headers, HeaderName::from_static("x-total-count"))
+            .and_then(|v| v.trim().parse().ok());
+        let link = header_value(headers, LINK);
+        let next_url = link.as_deref().and_then(link_next).map(|next| {
+            url::Url::parse(base_url)
+                .and_then(|base| base.join(&next))
+                .map_or(next, String::from)
+        });
+
+        Self {
+            total,
+            has_link: link.is_some(),
+            next_url,
+        }
+    }
+
+    /// Builds the Page, preferring the headers' total and next link over the body's
+    fn page<T>(self, items: Vec<T>, page: u32, page_size: u32, body_total: Option<u64>) -> Page<T> {
+        let mut page = build_page(items, page, page_size, self.total.or(body_total));
+        if self.has_link {
+            page.has_next = self.next_url.is_some();
+        }
+        page.next_url = self.next_url;
+        page
+    }
+}
+
+/// The `rel="next"` target of an RFC 5988 `Link` header
+///
+/// e.g. `</RESTApi/LocationGroup?PageNumber=3>; rel="next", </RESTApi/LocationGroup?PageNumber=9>; rel="last"`.
+/// `rel` may be unquoted or list several relations (`rel="next last"`).
+fn link_next(header: &str) -> Option<String> {
+    let mut rest = header;
+    while let Some(start) = rest.find('<') {
+        let end = start + rest[start..].find('>')?;
+        let params_end = rest[end..].find('<').map_or(rest.len(), |i| end + i);
+        let is_next = rest[end + 1..params_end].split(';').any(|param| {
+            param.split_once('=').is_some_and(|(name, value)| {
+                name.trim().eq_ignore_ascii_case("rel")
+                    && value
+                        .trim()
+                        .trim_end_matches(',')
+                        .trim_matches('"')
+                        .split_whitespace()
+                        .any(|rel| rel.eq_ignore_ascii_case("next"))
+            })
+        });
+        if is_next {
+            return Some(rest[start + 1..end].to_string());
+        }
+        rest = &rest[params_end..];
+    }
+    None
+}
+
+/// Page size `all_pages` callers use unless they have a reason not to
+pub(crate) const ALL_PAGES_PAGE_SIZE: u32 = 100;
+
+/// Most pages `all_pages` fetches before concluding the server's `has_next` is wrong
+const MAX_PAGES: u32 = 1_000;
+
+/// Streams every item of a paged list, fetching page after page on demand
+///
+/// `fetch` is called with 1-based page numbers. Paging stops at the first
+/// page that has no successor, is empty, or brings the item count up to the
+/// reported total; a server that keeps claiming more pages past `MAX_PAGES`
+/// ends the stream with a `Parse` error instead of looping forever.
+pub(crate) fn all_pages<T, F, Fut>(mut fetch: F) -> impl Stream<Item = Result<T, NimbusError>>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: Future<Output = Result<Page<T>, NimbusError>>,
+{
+    // (next page number, items seen so far, finished)
+    stream::try_unfold((1u32, 0u64, false), move |(page, seen, done)| {
+        let next = (!done).then(|| fetch(page));
+        async move {
+            let Some(next) = next else {
+                return Ok(None);
+            };
+            let current = next.await?;
+
+            let seen = seen + current.items.len() as u64;
+            let last = !current.has_next
+                || current.items.is_empty()
+                || current.total.is_some_and(|total| seen >= total);
+            if !last && page >= MAX_PAGES {
+                return Err(NimbusError::Parse(format!(
+                    "Server still reports more results after {} pages",
+                    MAX_PAGES
+                )));
+            }
+
+            let items = stream::iter(current.items.into_iter().map(Ok));
+            Ok(Some((items, (page + 1, seen, last))))
+        }
+    })
+    .try_flatten()
+}
+
+/// Checks `location_ids` is non-empty and drops duplicates, keeping first-seen order
+fn validate_location_ids(location_ids: &[i64]) -> Result<Vec<i64>, NimbusError> {
+    if location_ids.is_empty() {
+        let error = FieldError::new(
+            "location_ids",
+            "empty_location_ids",
+            "location_ids must not be empty",
+        );
+        return Err(vec![error].into());
+    }
+
+    let mut seen = HashSet::new();
+    Ok(location_ids
+        .iter()
+        .copied()
+        .filter(|id| seen.insert(*id))
+        .collect())
+}
+
+/// The problem with a description, if any, as it will be sent (normalized)
+///
+/// Nimbus answers an over-long description with a bare 400, so the length is
+/// checked here against `max_length` characters; 0 means no limit.
+fn description_error(description: &str, max_length: usize) -> Option<FieldError> {
+    let description = normalize_description(description);
+    let length = description.chars().count();
+    if description.is_empty() {
+        Some(FieldError::new(
+            "description",
+            "empty_description",
+            "description must not be empty",
+        ))
+    } else if max_length > 0 && length > max_length {
+        Some(FieldError::new(
+            "description",
+            "description_too_long",
+            format!(
+                "description is {} characters long; the limit is {}",
+                length, max_length
+            ),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Checks a LocationGroupRequest without calling the API
+///
+/// Covers a non-blank description of at most `max_description_length`
+/// characters (0 for no limit) and a non-empty list of positive location IDs.
+/// Repeated IDs are not an error, since creates drop the duplicates.
+pub fn validate_location_group_request(
+    req: &LocationGroupRequest,
+    max_description_length: usize,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors: Vec<FieldError> = description_error(&req.description, max_description_length)
+        .into_iter()
+        .collect();
+    if req.location_ids.is_empty() {
+        errors.push(FieldError::new(
+            "location_ids",
+            "empty_location_ids",
+            "location_ids must not be empty",
+        ));
+    }
+    let invalid: Vec<String> = req
+        .location_ids
+        .iter()
+        .filter(|id| **id <= 0)
+        .map(|id| id.to_string())
+        .collect();
+    if !invalid.is_empty() {
+        errors.push(FieldError::new(
+            "location_ids",
+            "invalid_location_id",
+            format!("location_ids must be positive, got {}", invalid.join(", ")),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// Validates a whole batch of LocationGroupRequests for the UI, before it is submitted
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client, whose config holds the description limit
+/// * `requests` - LocationGroupRequests as they would be sent to a batch create or import
+///
+/// # Returns
+/// One list per request, in the same order; all empty when the batch is ready
+/// to send. Besides the single-request checks, a description already used by
+/// an earlier request is reported as `duplicate_description`.
+#[tauri::command]
+pub async fn validate_location_groups(
+    client: tauri::State<'_, NimbusClient>,
+    requests: Vec<LocationGroupRequest>,
+) -> Result<Vec<Vec<FieldError>>, NimbusError> {
+    Ok(location_group_batch_errors(
+        &requests,
+        client.max_description_length(),
+    ))
+}
+
+fn location_group_batch_errors(
+    requests: &[LocationGroupRequest],
+    max_description_length: usize,
+) -> Vec<Vec<FieldError>> {
+    let mut first_index = HashMap::new();
+    requests
+        .iter()
+        .enumerate()
+        .map(|(index, request)| {
+            let mut errors = validate_location_group_request(request, max_description_length)
+                .err()
+                .unwrap_or_default();
+            let description = normalize_description(&request.description).to_lowercase();
+            if description.is_empty() {
+                return errors;
+            }
+            match first_index.get(&description) {
+                Some(first) => errors.push(FieldError::new(
+                    "description",
+                    "duplicate_description",
+                    format!(
+                        "description '{}' is already used by request {}",
+                        normalize_description(&request.description),
+                        first
+                    ),
+                )),
+                None => {
+                    first_index.insert(description, index);
+                }
+            }
+            errors
+        })
+        .collect()
+}
+
+/// Builds the PUT payload for a LocationGroup update
+///
+/// Same shape as the create payload, with `LocationGroupID` added.
+fn build_location_group_update_payload(
+    location_group_id: i64,
+    request: &LocationGroupRequest,
+) -> Value {
+    let mut payload = build_location_group_payload(request);
+    payload["LocationGroupID"] = json!(location_group_id);
+    payload
+}
+
+/// Adhoc field name `learning_period` is sent under
+const LEARNING_PERIOD_FIELD: &str = "adhoc_LearningPeriod";
+
+/// All adhoc fields for a ScheduleGroup payload
+///
+/// `learning_period` always comes first as `adhoc_LearningPeriod`, so older
+/// callers that only set it keep working. An explicit entry with the same name
+/// in `adhoc_fields` is dropped in its favour.
+fn collect_adhoc_fields(request: &ScheduleGroupRequest) -> Vec<AdhocField> {
+    let learning_period = AdhocField {
+        field_name: LEARNING_PERIOD_FIELD.to_string(),
+        value: request.learning_period.to_string(),
+    };
+
+    std::iter::once(learning_period)
+        .chain(
+            request
+                .adhoc_fields
+                .iter()
+                .filter(|field| field.field_name != LEARNING_PERIOD_FIELD)
+                .cloned(),
+        )
+        .collect()
+}
+
+/// Date format Nimbus expects for GroupStartDate/GroupEndDate
+pub(crate) const NIMBUS_DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Parses a `YYYY-MM-DD` date, naming the offending field on failure
+fn parse_nimbus_date(field: &str, value: &str) -> Result<NaiveDate, NimbusError> {
+    NaiveDate::parse_from_str(value, NIMBUS_DATE_FORMAT).map_err(|_| {
+        NimbusError::Validation(format!(
+            "{} must be a valid YYYY-MM-DD date, got '{}'",
+            field, value
+        ))
+    })
+}
+
+/// Checks both dates are well-formed and that `end_date` is not before `start_date`
+fn validate_date_range(start_date: &str, end_date: &str) -> Result<(), NimbusError> {
+    let start = parse_nimbus_date("start_date", start_date)?;
+    let end = parse_nimbus_date("end_date", end_date)?;
+    if end < start {
+        return Err(NimbusError::Validation(format!(
+            "end_date {} is before start_date {}",
+            end_date, start_date
+        )));
+    }
+    Ok(())
+}
+
+/// Moves both dates of a range by `days`, checking the result is still a valid range
+///
+/// Returns the shifted `(start_date, end_date)`; their order is unchanged. A
+/// date pushed outside what `YYYY-MM-DD` can express is a validation error
+/// rather than a bad PATCH.
+pub(crate) fn shift_date_range(
+    start_date: &str,
+    end_date: &str,
+    days: i64,
+) -> Result<(String, String), NimbusError> {
+    let shift = |field: &str, value: &str| {
+        let date = parse_nimbus_date(field, value)?;
+        chrono::Duration::try_days(days)
+            .and_then(|offset| date.checked_add_signed(offset))
+            // chrono goes far beyond the four-digit years YYYY-MM-DD can hold
+            .filter(|shifted| (1..=9999).contains(&shifted.year()))
+            .map(|shifted| shifted.format(NIMBUS_DATE_FORMAT).to_string())
+            .ok_or_else(|| {
+                NimbusError::Validation(format!(
+                    "{} {} shifted by {} days is out of range",
+                    field, value, days
+                ))
+            })
+    };
+    Ok((
+        shift("start_date", start_date)?,
+        shift("end_date", end_date)?,
+    ))
+}
+
+/// Checks a ScheduleGroupRequest without calling the API
+///
+/// Covers the date format and order, a non-blank description of at most
+/// `max_description_length` characters (0 for no limit), a positive
+/// `location_group_id` and a known learning period (see `LearningPeriod::is_known`).
+/// Every problem is reported, not just the first.
+pub fn validate_schedule_group_request(
+    req: &ScheduleGroupRequest,
+    max_description_length: usize,
+) -> Result<(), Vec<FieldError>> {
+    let mut errors = schedule_field_errors(
+        &req.description,
+        max_description_length,
+        &req.start_date,
+        &req.end_date,
+        &req.learning_period,
+    );
+    if req.location_group_id <= 0 {
+        errors.push(FieldError::new(
+            "location_group_id",
+            "non_positive_location_group_id",
+            format!(
+                "location_group_id must be positive, got {}",
+                req.location_group_id
+            ),
+        ));
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+/// The checks shared by finished requests and drafts, which have no LocationGroup yet
+fn schedule_field_errors(
+    description: &str,
+    max_description_length: usize,
+    start_date: &str,
+    end_date: &str,
+    learning_period: &LearningPeriod,
+) -> Vec<FieldError> {
+    let mut errors: Vec<FieldError> = description_error(description, max_description_length)
+        .into_iter()
+        .collect();
+
+    let start = parse_nimbus_date("start_date", start_date);
+    let end = parse_nimbus_date("end_date", end_date);
+    match (&start, &end) {
+        (Ok(start_day), Ok(end_day)) if end_day < start_day => errors.push(FieldError::new(
+            "end_date",
+            "end_before_start",
+            format!("end_date {} is before start_date {}", end_date, start_date),
+        )),
+        _ => {}
+    }
+    for (field, result) in [("start_date", start), ("end_date", end)] {
+        if let Err(e) = result {
+            let message = match e {
+                NimbusError::Validation(message) => message,
+                other => other.to_string(),
+            };
+            errors.push(FieldError::new(field, "invalid_date", message));
+        }
+    }
+
+    if !learning_period.is_known() {
+        errors.push(FieldError::new(
+            "learning_period",
+            "unknown_learning_period",
+            "learning_period must not be empty",
+        ));
+    }
+    errors
+}
+
+/// Metadata endpoint listing the values `adhoc_LearningPeriod` accepts
+const LEARNING_PERIOD_VALUES_PATH: &str = "/RESTApi/AdhocField/adhoc_LearningPeriod/Values";
+
+/// Learning periods already fetched, per base URL, for the rest of the session
+#[derive(Debug, Default)]
+pub struct LearningPeriodCache {
+    values: Mutex<HashMap<String, Vec<String>>>,
+}
+
+impl LearningPeriodCache {
+    fn get(&self, base_url: &str) -> Option<Vec<String>> {
+        let values = self.values.lock().expect("learning period lock poisoned");
+        values.get(base_url).cloned()
+    }
+
+    fn insert(&self, base_url: &str, periods: Vec<String>) {
+        let mut values = self.values.lock().expect("learning period lock poisoned");
+        values.insert(base_url.to_string(), periods);
+    }
+}
+
+/// Lists the learning periods the active Nimbus server accepts, for the form dropdown
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `cache` - Learning periods already fetched this session
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// The allowed values as Nimbus expects them in `learning_period`, e.g.
+/// `["30", "60", "90", "Term", "120"]`. Servers without the metadata endpoint
+/// get the standard values. Fetched once per environment per session.
+#[tauri::command]
+pub async fn list_learning_periods(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    cache: tauri::State<'_, LearningPeriodCache>,
+    profile: Option<String>,
+) -> Result<Vec<String>, NimbusError> {
+    // Resolve the target environment, and credentials only on a cache miss
+    let profile = profiles.resolve_name(profile.as_deref())?;
+    let base_url = profiles.base_url(&profile)?;
+    if let Some(periods) = cache.get(&base_url) {
+        return Ok(periods);
+    }
+    let token = session.token_for(&profile)?;
+
+    let periods = fetch_learning_periods(&client, &base_url, &token).await?;
+    cache.insert(&base_url, periods.clone());
+    Ok(periods)
+}
+
+/// GETs the allowed learning periods, falling back to the standard ones
+pub(crate) async fn fetch_learning_periods(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+) -> Result<Vec<String>, NimbusError> {
+    let result = client
+        .request(Method::GET, base_url, token, LEARNING_PERIOD_VALUES_PATH)
+        .send_parsed()
+        .await;
+
+    // Check response status - a missing route means this server doesn't list them
+    let response_body: Value = match result {
+        Err(NimbusError::Http {
+            status: 404 | 405 | 501,
+            ..
+        }) => {
+            tracing::debug!("Learning period metadata unavailable, using the standard values");
+            return Ok(LearningPeriod::standard()
+                .iter()
+                .map(|period| period.as_str().to_string())
+                .collect());
+        }
+        result => result?,
+    };
+
+    parse_learning_periods(&response_body)
+}
+
+/// Parses the allowed values from a bare array or a `{"Values": [...]}` object
+///
+/// Entries may be strings, numbers, or objects with a `Value` or `Name`.
+/// Blank entries and repeats are dropped.
+fn parse_learning_periods(body: &Value) -> Result<Vec<String>, NimbusError> {
+    let entries = body
+        .as_array()
+        .or_else(|| body.get("Values").and_then(Value::as_array))
+        .ok_or_else(|| NimbusError::Parse("Values not found in response".to_string()))?;
+
+    let mut seen = HashSet::new();
+    let periods: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| {
+            let value = entry
+                .get("Value")
+                .or_else(|| entry.get("Name"))
+                .unwrap_or(entry);
+            match value {
+                Value::String(text) => Some(text.trim().to_string()),
+                Value::Number(number) => Some(number.to_string()),
+                _ => None,
+            }
+        })
+        .filter(|period| !period.is_empty() && seen.insert(period.clone()))
+        .collect();
+    if periods.is_empty() {
+        return Err(NimbusError::Parse(
+            "No learning periods in response".to_string(),
+        ));
+    }
+    Ok(periods)
+}
+
+/// Validates a ScheduleGroupRequest for the UI, before it is submitted
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client, whose config holds the description limit
+/// * `request` - ScheduleGroupRequest as entered in the form
+///
+/// # Returns
+/// Every invalid field; empty when the request is ready to send
+#[tauri::command]
+pub async fn validate_schedule_group(
+    client: tauri::State<'_, NimbusClient>,
+    request: ScheduleGroupRequest,
+) -> Result<Vec<FieldError>, NimbusError> {
+    Ok(
+        validate_schedule_group_request(&request, client.max_description_length())
+            .err()
+            .unwrap_or_default(),
+    )
+}
+
+/// A ScheduleGroupRequest's dates checked against its LocationGroup
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LocationWindowCheck {
+    pub location_group_id: i64,
+    pub location_group_active: bool,
+    /// First day the LocationGroup is valid; None when it carries no start
+    pub valid_from: Option<String>,
+    /// Last day the LocationGroup is valid; None when it carries no end
+    pub valid_until: Option<String>,
+    /// Every rule the schedule breaks; empty when it fits
+    pub errors: Vec<FieldError>,
+}
+
+/// LocationGroup fields Nimbus versions use for the start of the valid window
+const LOCATION_VALID_FROM_FIELDS: [&str; 3] = ["StartDate", "EffectiveDate", "ActiveFrom"];
+/// LocationGroup fields Nimbus versions use for the end of the valid window
+const LOCATION_VALID_UNTIL_FIELDS: [&str; 3] = ["EndDate", "ExpiryDate", "ActiveTo"];
+
+/// Checks a schedule's dates fall within its LocationGroup's valid window
+///
+/// Nimbus only rejects a schedule outside the window once it processes the
+/// group, so this lets the form catch it up front.
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `request` - ScheduleGroupRequest as entered in the form
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// The window found and every rule the schedule breaks, each naming its
+/// field and a code: `location_group_inactive`, `starts_before_location_group`
+/// or `ends_after_location_group`. A side of the window the LocationGroup
+/// doesn't carry is not checked. Malformed schedule dates are a
+/// `NimbusError::Validation`, and a missing LocationGroup `NimbusError::NotFound`.
+#[tauri::command]
+pub async fn validate_schedule_against_location(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    request: ScheduleGroupRequest,
+    profile: Option<String>,
+) -> Result<LocationWindowCheck, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    check_location_window(&client, &base_url, &token, &request).await
+}
+
+/// Fetches the schedule's LocationGroup and compares the dates to its window
+pub(crate) async fn check_location_window(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    request: &ScheduleGroupRequest,
+) -> Result<LocationWindowCheck, NimbusError> {
+    validate_date_range(&request.start_date, &request.end_date)?;
+    let body =
+        fetch_location_group_body(client, base_url, token, request.location_group_id).await?;
+    let detail = parse_location_group_detail(&body, request.location_group_id)?;
+    let valid_from = location_window_date(&body, &LOCATION_VALID_FROM_FIELDS)?;
+    let valid_until = location_window_date(&body, &LOCATION_VALID_UNTIL_FIELDS)?;
+
+    let mut errors = Vec::new();
+    if !detail.active {
+        errors.push(FieldError::new(
+            "location_group_id",
+            "location_group_inactive",
+            format!("LocationGroup {} is inactive", detail.location_group_id),
+        ));
+    }
+    // Both dates parsed above, and YYYY-MM-DD strings sort as dates
+    if let Some(from) = valid_from.as_deref() {
+        if request.start_date.as_str() < from {
+            errors.push(FieldError::new(
+                "start_date",
+                "starts_before_location_group",
+                format!(
+                    "start_date {} is before LocationGroup {} is valid from {}",
+                    request.start_date, detail.location_group_id, from
+                ),
+            ));
+        }
+    }
+    if let Some(until) = valid_until.as_deref() {
+        if request.end_date.as_str() > until {
+            errors.push(FieldError::new(
+                "end_date",
+                "ends_after_location_group",
+                format!(
+                    "end_date {} is after LocationGroup {} is valid until {}",
+                    request.end_date, detail.location_group_id, until
+                ),
+            ));
+        }
+    }
+
+    Ok(LocationWindowCheck {
+        location_group_id: detail.location_group_id,
+        location_group_active: detail.active,
+        valid_from,
+        valid_until,
+        errors,
+    })
+}
+
+/// Reads the first of `fields` present on a LocationGroup as a YYYY-MM-DD date
+///
+/// Nimbus sends either a bare date or a timestamp like `2025-01-01T00:00:00`;
+/// only the date part is kept. Null or absent fields are None, anything else
+/// that doesn't start with a date is a `Parse` error.
+fn location_window_date(body: &Value, fields: &[&str]) -> Result<Option<String>, NimbusError> {
+    let Some(object) = body.as_object() else {
+        return Ok(None);
+    };
+    let found = fields.iter().find_map(|name| {
+        object
+            .iter()
+            .find(|(key, value)| key.eq_ignore_ascii_case(name) && !value.is_null())
+    });
+    let Some((key, value)) = found else {
+        return Ok(None);
+    };
+
+    let day = value
+        .as_str()
+        .and_then(|text| text.get(..10))
+        .and_then(|text| NaiveDate::parse_from_str(text, NIMBUS_DATE_FORMAT).ok())
+        .ok_or_else(|| NimbusError::Parse(format!("{} is not a date: {}", key, value)))?;
+    Ok(Some(day.format(NIMBUS_DATE_FORMAT).to_string()))
+}
+
+/// Creates a ScheduleGroup via REST API
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `queue` - Offline queue network failures are saved to
+/// * `audit` - Audit log the create is recorded in
+/// * `request` - ScheduleGroupRequest with description, location_group_id, dates, and learning_period
+/// * `idempotency_key` - Optional caller-supplied key; a UUID is generated if omitted.
+///   A key that isn't a valid header value fails with `NimbusError::Validation`
+/// * `dry_run` - When true, validate and return the payload without sending it
+/// * `verify_references` - When true, GET the LocationGroup first and fail with
+///   `NimbusError::ReferencedEntityNotFound` if it doesn't exist
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// ScheduleGroupID of the created group and the idempotency key used, or
+/// `queued: true` if Nimbus was unreachable
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn create_schedule_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    queue: tauri::State<'_, OfflineQueue>,
+    audit: tauri::State<'_, AuditLog>,
+    request: ScheduleGroupRequest,
+    idempotency_key: Option<String>,
+    dry_run: Option<bool>,
+    verify_references: Option<bool>,
+    profile: Option<String>,
+) -> Result<ScheduleGroupResponse, NimbusError> {
+    let idempotency_key = resolve_idempotency_key(idempotency_key)?;
+    if dry_run.unwrap_or(false) {
+        return dry_run_schedule_group(&request, idempotency_key, client.max_description_length());
+    }
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let result = send_schedule_group(
+        &client,
+        &base_url,
+        &token,
+        &request,
+        &idempotency_key,
+        verify_references.unwrap_or(false),
+    )
+    .await;
+    let actor = Some(profile.clone());
+    let schedule_group_id = match result {
+        Ok(id) => {
+            audit.record(
+                AuditOperation::Create,
+                "ScheduleGroup",
+                Some(id),
+                actor,
+                None,
+            );
+            Some(id)
+        }
+        // Unreachable server: keep the create for replay under the same key
+        Err(NimbusError::Network(_) | NimbusError::CircuitOpen { .. }) => {
+            queue.push(
+                &profile,
+                &base_url,
+                &idempotency_key,
+                QueuedRequest::ScheduleGroup(request),
+            )?;
+            audit.record_queued("ScheduleGroup", actor);
+            None
+        }
+        Err(e) => {
+            audit.record(
+                AuditOperation::Create,
+                "ScheduleGroup",
+                None,
+                actor,
+                Some(&e),
+            );
+            return Err(e);
+        }
+    };
+
+    Ok(ScheduleGroupResponse {
+        schedule_group_id,
+        idempotency_key,
+        queued: schedule_group_id.is_none(),
+        payload: None,
+    })
+}
+
+/// Builds the dry-run response: the validated payload, with no HTTP call
+fn dry_run_schedule_group(
+    request: &ScheduleGroupRequest,
+    idempotency_key: String,
+    max_description_length: usize,
+) -> Result<ScheduleGroupResponse, NimbusError> {
+    Ok(ScheduleGroupResponse {
+        schedule_group_id: None,
+        idempotency_key,
+        queued: false,
+        payload: Some(prepare_schedule_group_payload(
+            request,
+            max_description_length,
+        )?),
+    })
+}
+
+/// Validates a ScheduleGroupRequest and builds its create payload
+fn prepare_schedule_group_payload(
+    request: &ScheduleGroupRequest,
+    max_description_length: usize,
+) -> Result<Value, NimbusError> {
+    // Reject bad input before it reaches the API
+    validate_schedule_group_request(request, max_description_length)?;
+
+    Ok(build_schedule_group_payload(request))
+}
+
+/// The create payload for `req`, exactly as given (no validation)
+///
+/// `AdhocFields` always starts with `adhoc_LearningPeriod`; see `collect_adhoc_fields`.
+pub fn build_schedule_group_payload(req: &ScheduleGroupRequest) -> Value {
+    // Build adhoc fields array
+    let adhoc_fields: Vec<Value> = collect_adhoc_fields(req)
+        .iter()
+        .map(|field| json!({"FieldName": field.field_name, "Value": field.value}))
+        .collect();
+
+    // Build request payload
+    json!({
+        "Description": normalize_description(&req.description),
+        "Active": req.active,
+        "LocationGroupID": req.location_group_id,
+        "GroupStartDate": req.start_date,
+        "GroupEndDate": req.end_date,
+        "AdhocFields": adhoc_fields
+    })
+}
+
+/// POSTs a new ScheduleGroup after validating its dates
+///
+/// Returns the new ScheduleGroupID.
+#[tracing::instrument(skip(client, token), fields(status, correlation_id))]
+pub(crate) async fn post_schedule_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    request: &ScheduleGroupRequest,
+    idempotency_key: &str,
+) -> Result<i64, NimbusError> {
+    let payload = prepare_schedule_group_payload(request, client.max_description_length())?;
+
+    tracing::info!(%base_url, "Creating schedule group");
+    // The idempotency key lets Nimbus dedupe replays, so the POST is safe to retry
+    let response = client
+        .request(Method::POST, base_url, token, "/RESTApi/ScheduleGroup")
+        .header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
+        .json_body(payload)
+        .payload_encoding(client.payload_encoding())
+        .idempotent(true)
+        .captured()
+        .send_raw()
+        .await?;
+
+    let (schedule_group_id, body) = created_id(&response, "ScheduleGroupID")?;
+    if !body.is_null() {
+        warn_on_drift(&CREATED_SCHEDULE_GROUP, &body);
+    }
+    Ok(schedule_group_id)
+}
+
+/// `post_schedule_group`, first checking its LocationGroup exists when `verify_references`
+///
+/// The check runs after local validation, so a request that would be
+/// rejected anyway costs no round trip.
+pub(crate) async fn send_schedule_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    request: &ScheduleGroupRequest,
+    idempotency_key: &str,
+    verify_references: bool,
+) -> Result<i64, NimbusError> {
+    if verify_references {
+        validate_schedule_group_request(request, client.max_description_length())?;
+        verify_location_group_reference(client, base_url, token, request.location_group_id).await?;
+    }
+    post_schedule_group(client, base_url, token, request, idempotency_key).await
+}
+
+/// Fails with `ReferencedEntityNotFound` unless the LocationGroup exists
+async fn verify_location_group_reference(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: i64,
+) -> Result<(), NimbusError> {
+    match fetch_location_group(client, base_url, token, location_group_id).await {
+        Ok(_) => Ok(()),
+        Err(NimbusError::NotFound { .. }) => Err(NimbusError::ReferencedEntityNotFound {
+            entity: "LocationGroup",
+            id: location_group_id,
+            referenced_by: "ScheduleGroup",
+        }),
+        Err(e) => Err(e),
+    }
+}
+
+/// Creates a LocationGroup and then a ScheduleGroup referencing it
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log both creates are recorded in
+/// * `location_request` - LocationGroupRequest for the new group
+/// * `schedule_request` - ScheduleGroupDraft, completed with the new LocationGroupID
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// Both created IDs. If the ScheduleGroup fails after the LocationGroup was
+/// created, the error is `NimbusError::PartialFailure` carrying that
+/// `location_group_id` so the caller can clean up or retry.
+#[tauri::command]
+pub async fn create_location_and_schedule(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    location_request: LocationGroupRequest,
+    schedule_request: ScheduleGroupDraft,
+    profile: Option<String>,
+) -> Result<LocationAndScheduleResponse, NimbusError> {
+    // Validate both halves up front so a bad schedule doesn't orphan a LocationGroup
+    let max_description_length = client.max_description_length();
+    prepare_location_group_payload(&location_request, max_description_length)?;
+    let errors = schedule_field_errors(
+        &schedule_request.description,
+        max_description_length,
+        &schedule_request.start_date,
+        &schedule_request.end_date,
+        &schedule_request.learning_period,
+    );
+    if !errors.is_empty() {
+        return Err(errors.into());
+    }
+    let mut schedule_request = schedule_request.with_location_group(0);
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let actor = Some(profile);
+    let result = post_location_group(
+        &client,
+        &base_url,
+        &token,
+        &location_request,
+        &new_idempotency_key(),
+    )
+    .await;
+    let target_id = result.as_ref().ok().copied();
+    audit.record(
+        AuditOperation::Create,
+        "LocationGroup",
+        target_id,
+        actor.clone(),
+        result.as_ref().err(),
+    );
+    let location_group_id = result?;
+
+    schedule_request.location_group_id = location_group_id;
+    let result = post_schedule_group(
+        &client,
+        &base_url,
+        &token,
+        &schedule_request,
+        &new_idempotency_key(),
+    )
+    .await;
+    let target_id = result.as_ref().ok().copied();
+    audit.record(
+        AuditOperation::Create,
+        "ScheduleGroup",
+        target_id,
+        actor,
+        result.as_ref().err(),
+    );
+    let schedule_group_id = result.map_err(|e| NimbusError::PartialFailure {
+        location_group_id,
+        source: Box::new(e),
+    })?;
+
+    Ok(LocationAndScheduleResponse {
+        location_group_id,
+        schedule_group_id,
+    })
+}
+
+/// Fetches an existing ScheduleGroup via REST API
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `schedule_group_id` - ID of the ScheduleGroup to fetch
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// ScheduleGroupDetail including its adhoc fields.
+/// A 404 is reported as `NimbusError::NotFound`.
+#[tauri::command]
+pub async fn get_schedule_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    schedule_group_id: i64,
+    profile: Option<String>,
+) -> Result<ScheduleGroupDetail, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    fetch_schedule_group(&client, &base_url, &token, schedule_group_id).await
+}
+
+/// GETs a ScheduleGroup by ID
+pub(crate) async fn fetch_schedule_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    schedule_group_id: i64,
+) -> Result<ScheduleGroupDetail, NimbusError> {
+    let path = format!("/RESTApi/ScheduleGroup/{}", schedule_group_id);
+    let response_body: Value = client
+        .request(Method::GET, base_url, token, &path)
+        .not_found("ScheduleGroup", schedule_group_id)
+        .send_parsed()
+        .await?;
+
+    parse_schedule_group_detail(&response_body, schedule_group_id)
+}
+
+/// Parses a Nimbus ScheduleGroup entity into a ScheduleGroupDetail
+///
+/// `fallback_id` is used when the body does not echo `ScheduleGroupID`.
+fn parse_schedule_group_detail(
+    body: &Value,
+    fallback_id: i64,
+) -> Result<ScheduleGroupDetail, NimbusError> {
+    let str_field = |name: &str| -> Result<String, NimbusError> {
+        body.get(name)
+            .and_then(|v| v.as_str())
+            .map(str::to_string)
+            .ok_or_else(|| NimbusError::Parse(format!("{} not found in response", name)))
+    };
+
+    let schedule_group_id = id_field(body, "ScheduleGroupID").unwrap_or(fallback_id);
+
+    let location_group_id = id_field(body, "LocationGroupID")
+        .ok_or_else(|| NimbusError::Parse("LocationGroupID not found in response".to_string()))?;
+
+    let active = body.get("Active").and_then(|v| v.as_bool()).unwrap_or(true);
+
+    // Each entry in AdhocFields is an object of the form {"FieldName": ..., "Value": ...}
+    let adhoc_fields = body
+        .get("AdhocFields")
+        .and_then(|v| v.as_array())
+        .map(|fields| {
+            fields
+                .iter()
+                .filter_map(|f| {
+                    Some(AdhocField {
+                        field_name: f.get("FieldName")?.as_str()?.to_string(),
+                        value: match f.get("Value")? {
+                            Value::String(value) => value.clone(),
+                            other => other.to_string(),
+                        },
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    Ok(ScheduleGroupDetail {
+        schedule_group_id,
+        description: str_field("Description")?,
+        location_group_id,
+        start_date: date_part(&str_field("GroupStartDate")?),
+        end_date: date_part(&str_field("GroupEndDate")?),
+        active,
+        adhoc_fields,
+    })
+}
+
+/// Strips any time component Nimbus appends (`2025-01-01T00:00:00` -> `2025-01-01`)
+fn date_part(value: &str) -> String {
+    value.split('T').next().unwrap_or(value).to_string()
+}
+
+/// Creates a copy of a ScheduleGroup with new dates, e.g. for the next term
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the create is recorded in
+/// * `source_id` - ScheduleGroupID to copy
+/// * `new_start` - GroupStartDate of the copy (YYYY-MM-DD)
+/// * `new_end` - GroupEndDate of the copy (YYYY-MM-DD)
+/// * `append_copy` - When true, " (copy)" is appended to the description
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// ScheduleGroupID of the new group. Description, LocationGroup, active flag,
+/// and adhoc fields (including the learning period) are taken from the source.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn clone_schedule_group(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    source_id: i64,
+    new_start: String,
+    new_end: String,
+    append_copy: Option<bool>,
+    profile: Option<String>,
+) -> Result<i64, NimbusError> {
+    // Check the new dates before fetching anything
+    validate_date_range(&new_start, &new_end)?;
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let result = copy_schedule_group(
+        &client,
+        &base_url,
+        &token,
+        source_id,
+        new_start,
+        new_end,
+        append_copy.unwrap_or(false),
+    )
+    .await;
+    let target_id = result.as_ref().ok().copied();
+    audit.record(
+        AuditOperation::Create,
+        "ScheduleGroup",
+        target_id,
+        Some(profile),
+        result.as_ref().err(),
+    );
+    result
+}
+
+/// Fetches a ScheduleGroup and POSTs a copy of it with new dates
+pub(crate) async fn copy_schedule_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    source_id: i64,
+    new_start: String,
+    new_end: String,
+    append_copy: bool,
+) -> Result<i64, NimbusError> {
+    let source = fetch_schedule_group(client, base_url, token, source_id).await?;
+    let copy_description = append_copy.then(|| format!("{} (copy)", source.description));
+    let mut builder = schedule_group_request_from_detail(source)?
+        .into_builder()
+        .dates(new_start, new_end)
+        .max_description_length(client.max_description_length());
+    if let Some(description) = copy_description {
+        builder = builder.description(description);
+    }
+    let request = builder.build()?;
+
+    post_schedule_group(client, base_url, token, &request, &new_idempotency_key()).await
+}
+
+/// Turns a fetched ScheduleGroup back into a create request
+///
+/// The learning period is lifted out of the adhoc fields, since the request
+/// carries it separately.
+fn schedule_group_request_from_detail(
+    detail: ScheduleGroupDetail,
+) -> Result<ScheduleGroupRequest, NimbusError> {
+    let (learning_period, adhoc_fields): (Vec<_>, Vec<_>) = detail
+        .adhoc_fields
+        .into_iter()
+        .partition(|field| field.field_name == LEARNING_PERIOD_FIELD);
+    let learning_period = learning_period
+        .first()
+        .ok_or_else(|| {
+            NimbusError::Parse(format!(
+                "ScheduleGroup {} has no {}",
+                detail.schedule_group_id, LEARNING_PERIOD_FIELD
+            ))
+        })?
+        .value
+        .parse()?;
+
+    Ok(ScheduleGroupRequest {
+        description: detail.description,
+        location_group_id: detail.location_group_id,
+        start_date: detail.start_date,
+        end_date: detail.end_date,
+        learning_period,
+        adhoc_fields,
+        active: detail.active,
+    })
+}
+
+/// Moves a ScheduleGroup's start and end dates, leaving every other field as is
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log the update is recorded in
+/// * `schedule_group_id` - ID of the ScheduleGroup to update
+/// * `start_date` - New GroupStartDate (YYYY-MM-DD)
+/// * `end_date` - New GroupEndDate (YYYY-MM-DD)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// The updated ScheduleGroupDetail. Servers that reject PATCH are updated
+/// with a GET followed by a full PUT instead.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn patch_schedule_group_dates(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    schedule_group_id: i64,
+    start_date: String,
+    end_date: String,
+    profile: Option<String>,
+) -> Result<ScheduleGroupDetail, NimbusError> {
+    // Same checks as the create path
+    validate_date_range(&start_date, &end_date)?;
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let result = patch_schedule_dates(
+        &client,
+        &base_url,
+        &token,
+        schedule_group_id,
+        &start_date,
+        &end_date,
+    )
+    .await;
+    audit.record(
+        AuditOperation::Update,
+        "ScheduleGroup",
+        Some(schedule_group_id),
+        Some(profile),
+        result.as_ref().err(),
+    );
+    result
+}
+
+/// PATCHes only GroupStartDate/GroupEndDate, falling back to GET-merge-PUT
+/// when the server answers 405 or 501
+pub(crate) async fn patch_schedule_dates(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    schedule_group_id: i64,
+    start_date: &str,
+    end_date: &str,
+) -> Result<ScheduleGroupDetail, NimbusError> {
+    let payload = json!({
+        "GroupStartDate": start_date,
+        "GroupEndDate": end_date
+    });
+
+    // Make PATCH request
+    let path = format!("/RESTApi/ScheduleGroup/{}", schedule_group_id);
+    let result = client
+        .request(Method::PATCH, base_url, token, &path)
+        .json_body(payload)
+        .not_found("ScheduleGroup", schedule_group_id)
+        .send_text()
+        .await;
+
+    // Check response status
+    match result {
+        Err(NimbusError::Http {
+            status: 405 | 501, ..
+        }) => {
+            tracing::info!(%path, "PATCH unsupported, falling back to PUT");
+            let mut detail =
+                fetch_schedule_group(client, base_url, token, schedule_group_id).await?;
+            detail.start_date = start_date.to_string();
+            detail.end_date = end_date.to_string();
+            put_schedule_group(client, base_url, token, detail).await
+        }
+        result => {
+            schedule_group_update_result(client, base_url, token, schedule_group_id, result?).await
+        }
+    }
+}
+
+/// PUTs a full ScheduleGroup built from `detail`
+async fn put_schedule_group(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    detail: ScheduleGroupDetail,
+) -> Result<ScheduleGroupDetail, NimbusError> {
+    let adhoc_fields: Vec<Value> = detail
+        .adhoc_fields
+        .iter()
+        .map(|field| json!({"FieldName": field.field_name, "Value": field.value}))
+        .collect();
+    let payload = json!({
+        "ScheduleGroupID": detail.schedule_group_id,
+        "Description": detail.description,
+        "Active": detail.active,
+        "LocationGroupID": detail.location_group_id,
+        "GroupStartDate": detail.start_date,
+        "GroupEndDate": detail.end_date,
+        "AdhocFields": adhoc_fields
+    });
+
+    // Make PUT request
+    let path = format!("/RESTApi/ScheduleGroup/{}", detail.schedule_group_id);
+    let body_text = client
+        .request(Method::PUT, base_url, token, &path)
+        .json_body(payload)
+        .not_found("ScheduleGroup", detail.schedule_group_id)
+        .send_text()
+        .await?;
+
+    schedule_group_update_result(client, base_url, token, detail.schedule_group_id, body_text).await
+}
+
+/// Turns a PATCH/PUT success body into the updated ScheduleGroupDetail
+///
+/// An empty body is followed by a GET, since there is nothing to parse.
+async fn schedule_group_update_result(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    schedule_group_id: i64,
+    body_text: String,
+) -> Result<ScheduleGroupDetail, NimbusError> {
+    if body_text.trim().is_empty() {
+        return fetch_schedule_group(client, base_url, token, schedule_group_id).await;
+    }
+
+    let response_body: Value =
+        serde_json::from_str(&body_text).map_err(|e| NimbusError::Parse(e.to_string()))?;
+
+    parse_schedule_group_detail(&response_body, schedule_group_id)
+}
+
+/// Lists ScheduleGroups via REST API, optionally only those of one LocationGroup
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `location_group_id` - Only list schedules of this LocationGroup (all when omitted)
+/// * `page` - 1-based page number
+/// * `page_size` - Maximum number of groups per page
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// A Page of ScheduleGroupDetail with the total count when the server reports it
+#[tauri::command]
+pub async fn list_schedule_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    location_group_id: Option<i64>,
+    page: u32,
+    page_size: u32,
+    profile: Option<String>,
+) -> Result<Page<ScheduleGroupDetail>, NimbusError> {
+    if page == 0 || page_size == 0 {
+        return Err(NimbusError::Validation(
+            "page and page_size must be at least 1".to_string(),
+        ));
+    }
+
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    fetch_schedule_group_page(
+        &client,
+        &base_url,
+        &token,
+        location_group_id,
+        page,
+        page_size,
+    )
+    .await
+}
+
+/// Lists every ScheduleGroup, optionally only those of one LocationGroup
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `location_group_id` - Only list schedules of this LocationGroup (all when omitted)
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// All matching ScheduleGroupDetail records, in server order
+#[tauri::command]
+pub async fn list_all_schedule_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    location_group_id: Option<i64>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<Vec<ScheduleGroupDetail>, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let schedules = all_pages(|page| {
+        fetch_schedule_group_page(
+            &client,
+            &base_url,
+            &token,
+            location_group_id,
+            page,
+            ALL_PAGES_PAGE_SIZE,
+        )
+    });
+    with_request_timeout(timeout_ms, schedules.try_collect()).await
+}
+
+/// GETs one page of ScheduleGroups, optionally only those of one LocationGroup
+pub(crate) async fn fetch_schedule_group_page(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    location_group_id: Option<i64>,
+    page: u32,
+    page_size: u32,
+) -> Result<Page<ScheduleGroupDetail>, NimbusError> {
+    // Make GET request - the LocationGroupID filter is applied server-side
+    let mut request = client.request(Method::GET, base_url, token, "/RESTApi/ScheduleGroup");
+    for (name, value) in schedule_group_list_query(location_group_id, page, page_size) {
+        request = request.query(name, value);
+    }
+    let response = request.send_raw().await?;
+    let response_body: Value = response.json()?;
+
+    let (records, total) = split_page_body(&response_body)?;
+    let items = records
+        .iter()
+        .map(|record| parse_schedule_group_detail(record, 0))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(PageHeaders::parse(&response.headers, base_url).page(items, page, page_size, total))
+}
+
+/// Query params for a ScheduleGroup list, omitting the filter when unset
+fn schedule_group_list_query(
+    location_group_id: Option<i64>,
+    page: u32,
+    page_size: u32,
+) -> Vec<(&'static str, String)> {
+    let mut query = vec![
+        ("PageNumber", page.to_string()),
+        ("PageSize", page_size.to_string()),
+    ];
+    if let Some(id) = location_group_id {
+        query.push(("LocationGroupID", id.to_string()));
+    }
+    query
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+    use crate::commands::request::PayloadEncoding;
+    use std::sync::Arc;
+
+    const BASE_URL: &str = "https://nimbus.test";
+
+    fn mock_client(mock: &Arc<MockExecutor>) -> NimbusClient {
+        NimbusClient::with_executor(NimbusConfig::default(), mock.clone())
+    }
+
+    #[test]
+    fn test_location_group_request_serialization() {
+        let request = LocationGroupRequest {
+            description: "Test Group".to_string(),
+            location_ids: vec![1, 2, 3],
+            active: true,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["description"], "Test Group");
+        assert_eq!(json["location_ids"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_location_group_update_payload_serialization() {
+        let request = LocationGroupRequest {
+            description: "Test Group".to_string(),
+            location_ids: vec![1, 2, 3],
+            active: true,
+        };
+        let json = build_location_group_update_payload(42, &request);
+        assert_eq!(json["LocationGroupID"], 42);
+        assert_eq!(json["Description"], "Test Group");
+        assert_eq!(json["Locations"].as_array().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn test_build_location_group_payload_shape() {
+        let request = LocationGroupRequest {
+            description: "West Coast".to_string(),
+            location_ids: vec![101, 102],
+            active: false,
+        };
+        assert_eq!(
+            build_location_group_payload(&request),
+            json!({
+                "Description": "West Coast",
+                "Active": false,
+                "Locations": [{"LocationID": 101}, {"LocationID": 102}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_build_schedule_group_payload_shape() {
+        let request = ScheduleGroupRequest {
+            description: "Q1 2025".to_string(),
+            location_group_id: 42,
+            start_date: "2025-01-01".to_string(),
+            end_date: "2025-03-31".to_string(),
+            learning_period: LearningPeriod::Term,
+            adhoc_fields: vec![AdhocField {
+                field_name: "adhoc_Campus".to_string(),
+                value: "North".to_string(),
+            }],
+            active: true,
+        };
+        assert_eq!(
+            build_schedule_group_payload(&request),
+            json!({
+                "Description": "Q1 2025",
+                "Active": true,
+                "LocationGroupID": 42,
+                "GroupStartDate": "2025-01-01",
+                "GroupEndDate": "2025-03-31",
+                "AdhocFields": [
+                    {"FieldName": "adhoc_LearningPeriod", "Value": "Term"},
+                    {"FieldName": "adhoc_Campus", "Value": "North"}
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_location_group_detail() {
+        let body = json!({
+            "LocationGroupID": 7,
+            "Description": "West Coast Locations",
+            "Active": false,
+            "Locations": [{"LocationID": 101}, {"LocationID": 102}]
+        });
+        let detail = parse_location_group_detail(&body, 0).unwrap();
+        assert_eq!(detail.location_group_id, 7);
+        assert_eq!(detail.description, "West Coast Locations");
+        assert!(!detail.active);
+        assert_eq!(detail.location_ids, vec![101, 102]);
+    }
+
+    #[test]
+    fn test_id_field_ignores_key_case() {
+        let body = json!({
+            "LocationGroupId": 7,
+            "Description": "West Coast",
+            "Locations": [{"LocationId": 101}, {"locationid": 102}]
+        });
+        assert_eq!(id_field(&body, "LocationGroupID"), Some(7));
+        assert_eq!(id_field(&body, "ScheduleGroupID"), None);
+
+        let detail = parse_location_group_detail(&body, 0).unwrap();
+        assert_eq!(detail.location_group_id, 7);
+        assert_eq!(detail.location_ids, vec![101, 102]);
+    }
+
+    #[test]
+    fn test_schedule_group_request_serialization() {
+        let request = ScheduleGroupRequest {
+            description: "Test Schedule".to_string(),
+            location_group_id: 42,
+            start_date: "2025-01-01".to_string(),
+            end_date: "2025-12-31".to_string(),
+            learning_period: LearningPeriod::Days30,
+            adhoc_fields: Vec::new(),
+            active: true,
+        };
+        let json = serde_json::to_value(&request).unwrap();
         assert_eq!(json["description"], "Test Schedule");
         assert_eq!(json["location_group_id"], 42);
+        assert_eq!(json["active"], true);
+    }
+
+    #[test]
+    fn test_inactive_request_serialization() {
+        let request = LocationGroupRequest {
+            description: "Staged Group".to_string(),
+            location_ids: vec![1],
+            active: false,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(json["active"], false);
+        assert_eq!(
+            build_location_group_update_payload(1, &request)["Active"],
+            false
+        );
+    }
+
+    #[test]
+    fn test_active_defaults_to_true() {
+        let request: LocationGroupRequest =
+            serde_json::from_value(json!({"description": "Group", "location_ids": [1]})).unwrap();
+        assert!(request.active);
+    }
+
+    #[test]
+    fn test_schedule_group_request_builder() {
+        let request = ScheduleGroupRequest::builder()
+            .description("Term 1")
+            .location_group_id(42)
+            .dates("2025-01-01", "2025-03-31")
+            .learning_period(LearningPeriod::Term)
+            .add_adhoc_field("adhoc_Campus", "North")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request,
+            ScheduleGroupRequest {
+                description: "Term 1".to_string(),
+                location_group_id: 42,
+                start_date: "2025-01-01".to_string(),
+                end_date: "2025-03-31".to_string(),
+                learning_period: LearningPeriod::Term,
+                adhoc_fields: vec![AdhocField {
+                    field_name: "adhoc_Campus".to_string(),
+                    value: "North".to_string(),
+                }],
+                active: true,
+            }
+        );
+
+        // Changing a built request re-runs validation
+        let err = request
+            .into_builder()
+            .dates("2025-03-31", "2025-01-01")
+            .max_description_length(3)
+            .build()
+            .unwrap_err();
+        let codes: Vec<_> = err.field_errors().unwrap().iter().map(|e| e.code).collect();
+        assert_eq!(codes, ["description_too_long", "end_before_start"]);
+        assert!(err.to_string().contains("the limit is 3"));
+    }
+
+    #[test]
+    fn test_request_defaults_match_omitted_fields() {
+        let request: LocationGroupRequest =
+            serde_json::from_value(json!({"description": "", "location_ids": []})).unwrap();
+        assert_eq!(request, LocationGroupRequest::default());
+
+        let request: ScheduleGroupRequest = serde_json::from_value(json!({
+            "description": "",
+            "location_group_id": 0,
+            "start_date": "",
+            "end_date": "",
+            "learning_period": "30"
+        }))
+        .unwrap();
+        assert_eq!(request, ScheduleGroupRequest::default());
+        assert_eq!(
+            request.clone(),
+            ScheduleGroupDraft::default().with_location_group(0)
+        );
+    }
+
+    #[test]
+    fn test_validate_date_range_accepts_valid_range() {
+        assert!(validate_date_range("2025-01-01", "2025-12-31").is_ok());
+        assert!(validate_date_range("2025-06-01", "2025-06-01").is_ok());
+    }
+
+    #[test]
+    fn test_validate_date_range_rejects_inverted_range() {
+        let err = validate_date_range("2025-12-31", "2025-01-01").unwrap_err();
+        assert!(matches!(err, NimbusError::Validation(_)));
+        assert!(err.to_string().contains("before start_date"));
+    }
+
+    #[test]
+    fn test_validate_date_range_rejects_malformed_date() {
+        let err = validate_date_range("2025-13-01", "2025-12-31").unwrap_err();
+        assert!(err.to_string().contains("start_date"));
+
+        let err = validate_date_range("2025-01-01", "01/01/2026").unwrap_err();
+        assert!(err.to_string().contains("end_date"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_learning_periods_parses_or_falls_back() {
+        let mock = MockExecutor::new();
+        mock.respond(
+            200,
+            r#"{"Values": [{"Value": "30"}, {"Name": "Term"}, 120, "30", " "]}"#,
+        )
+        .respond(404, "");
+        let client = mock_client(&mock);
+
+        let periods = fetch_learning_periods(&client, BASE_URL, "tok")
+            .await
+            .unwrap();
+        assert_eq!(periods, vec!["30", "Term", "120"]);
+
+        let periods = fetch_learning_periods(&client, BASE_URL, "tok")
+            .await
+            .unwrap();
+        assert_eq!(periods, vec!["30", "60", "90", "Term"]);
+        assert!(parse_learning_periods(&json!([])).is_err());
+
+        let cache = LearningPeriodCache::default();
+        cache.insert(BASE_URL, periods.clone());
+        assert_eq!(cache.get(BASE_URL), Some(periods));
+        assert_eq!(cache.get("https://other.test"), None);
+    }
+
+    #[test]
+    fn test_descriptions_are_normalized_before_sending() {
+        let location_group = LocationGroupRequest {
+            description: "  West \t Coast\n".to_string(),
+            location_ids: vec![101],
+            active: true,
+        };
+        assert_eq!(
+            build_location_group_payload(&location_group)["Description"],
+            "West Coast"
+        );
+        assert_eq!(
+            build_location_group_update_payload(5, &location_group)["Description"],
+            "West Coast"
+        );
+
+        let schedule_group = ScheduleGroupRequest {
+            description: "\nSpring   Term ".to_string(),
+            location_group_id: 5,
+            start_date: "2025-01-01".to_string(),
+            end_date: "2025-06-30".to_string(),
+            learning_period: LearningPeriod::Days30,
+            adhoc_fields: Vec::new(),
+            active: true,
+        };
+        assert_eq!(
+            build_schedule_group_payload(&schedule_group)["Description"],
+            "Spring Term"
+        );
+
+        // Whitespace only is empty once trimmed
+        let blank = LocationGroupRequest {
+            description: " \n\t ".to_string(),
+            ..location_group
+        };
+        let errors = validate_location_group_request(&blank, 255).unwrap_err();
+        assert_eq!(errors[0].code, "empty_description");
+        let blank = ScheduleGroupRequest {
+            description: "\r\n".to_string(),
+            ..schedule_group
+        };
+        let errors = validate_schedule_group_request(&blank, 255).unwrap_err();
+        assert_eq!(errors[0].code, "empty_description");
+    }
+
+    #[test]
+    fn test_over_length_description_is_rejected() {
+        let request = LocationGroupRequest {
+            description: "x".repeat(256),
+            location_ids: vec![101],
+            active: true,
+        };
+        let err = dry_run_location_group(&request, "key-1".to_string(), 255).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation failed: description is 256 characters long; the limit is 255"
+        );
+        let errors = validate_location_group_request(&request, 255).unwrap_err();
+        assert_eq!(errors[0].code, "description_too_long");
+
+        // Measured after normalizing, and 0 lifts the limit
+        let padded = LocationGroupRequest {
+            description: format!("  {}  ", "x".repeat(255)),
+            ..request.clone()
+        };
+        assert!(validate_location_group_request(&padded, 255).is_ok());
+        assert!(validate_location_group_request(&request, 0).is_ok());
+
+        let schedule = ScheduleGroupRequest {
+            description: "é".repeat(300),
+            location_group_id: 5,
+            start_date: "2025-01-01".to_string(),
+            end_date: "2025-06-30".to_string(),
+            learning_period: LearningPeriod::Days30,
+            adhoc_fields: Vec::new(),
+            active: true,
+        };
+        let errors = validate_schedule_group_request(&schedule, 255).unwrap_err();
+        assert_eq!(
+            errors[0].message,
+            "description is 300 characters long; the limit is 255"
+        );
+    }
+
+    #[test]
+    fn test_validate_schedule_group_request_reports_every_field() {
+        let request = ScheduleGroupRequest {
+            description: "  ".to_string(),
+            location_group_id: 0,
+            start_date: "2025-13-01".to_string(),
+            end_date: "01/01/2026".to_string(),
+            learning_period: LearningPeriod::Custom(" ".to_string()),
+            adhoc_fields: Vec::new(),
+            active: true,
+        };
+        let errors = validate_schedule_group_request(&request, 255).unwrap_err();
+        let mut fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+        fields.sort_unstable();
+        assert_eq!(
+            fields,
+            vec![
+                "description",
+                "end_date",
+                "learning_period",
+                "location_group_id",
+                "start_date"
+            ]
+        );
+
+        let request = ScheduleGroupRequest {
+            description: "Spring Term".to_string(),
+            location_group_id: 42,
+            start_date: "2025-12-31".to_string(),
+            end_date: "2025-01-01".to_string(),
+            learning_period: LearningPeriod::Custom("120".to_string()),
+            ..request
+        };
+        let errors = validate_schedule_group_request(&request, 255).unwrap_err();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].field, "end_date");
+        assert_eq!(errors[0].code, "end_before_start");
+        assert!(errors[0].message.contains("before start_date"));
+
+        let request = ScheduleGroupRequest {
+            end_date: "2026-01-01".to_string(),
+            ..request
+        };
+        assert!(validate_schedule_group_request(&request, 255).is_ok());
+    }
+
+    #[test]
+    fn test_tenant_defined_learning_periods_validate() {
+        let learning_period: LearningPeriod = "Semester".parse().unwrap();
+        assert_eq!(
+            learning_period,
+            LearningPeriod::Custom("Semester".to_string())
+        );
+        let request = ScheduleGroupRequest {
+            description: "Spring Semester".to_string(),
+            location_group_id: 42,
+            start_date: "2025-02-03".to_string(),
+            end_date: "2025-06-27".to_string(),
+            learning_period,
+            adhoc_fields: Vec::new(),
+            active: true,
+        };
+        assert!(validate_schedule_group_request(&request, 255).is_ok());
+    }
+
+    #[test]
+    fn test_build_page_with_total() {
+        let page = build_page(vec![1, 2], 2, 2, Some(5));
+        assert!(page.has_next);
+
+        let page = build_page(vec![5], 3, 2, Some(5));
+        assert!(!page.has_next);
+    }
+
+    #[test]
+    fn test_build_page_short_last_page_without_total() {
+        let body = json!([{"LocationGroupID": 1, "Description": "A"}]);
+        let (records, total) = split_page_body(&body).unwrap();
+        assert_eq!(total, None);
+
+        let page = build_page(records.clone(), 1, 10, total);
+        assert_eq!(page.items.len(), 1);
+        assert!(!page.has_next);
+    }
+
+    #[tokio::test]
+    async fn test_page_info_from_headers_or_body() {
+        let mock = MockExecutor::new();
+        let records = json!([{"LocationGroupID": 1, "Description": "A", "Locations": []}]);
+        mock.respond_with_headers(
+            200,
+            &[
+                ("X-Total-Count", "7"),
+                (
+                    "Link",
+                    r#"</RESTApi/LocationGroup?PageNumber=3&PageSize=1>; rel="next", </RESTApi/LocationGroup?PageNumber=7&PageSize=1>; rel="last""#,
+                ),
+            ],
+            &records.to_string(),
+        );
+        let envelope = json!({"Items": records, "TotalCount": 2});
+        mock.respond(200, &envelope.to_string());
+        let client = mock_client(&mock);
+
+        let page = fetch_location_group_page(&client, BASE_URL, "tok", 2, 1)
+            .await
+            .unwrap();
+        assert_eq!(page.total, Some(7));
+        assert!(page.has_next);
+        assert_eq!(
+            page.next_url.as_deref(),
+            Some("https://nimbus.test/RESTApi/LocationGroup?PageNumber=3&PageSize=1")
+        );
+
+        let page = fetch_location_group_page(&client, BASE_URL, "tok", 2, 1)
+            .await
+            .unwrap();
+        assert_eq!(page.total, Some(2));
+        assert!(!page.has_next);
+        assert!(page.next_url.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_search_filters_server_side_or_locally() {
+        let group = |id: i64, description: &str| json!({"LocationGroupID": id, "Description": description, "Locations": []});
+        let mock = MockExecutor::new();
+        // Filter honoured: only matches come back
+        mock.respond(200, &json!([group(1, "West Coast")]).to_string());
+        // Filter ignored: everything comes back
+        mock.respond(
+            200,
+            &json!([
+                group(1, "West Coast"),
+                group(2, "East"),
+                group(3, "Midwest")
+            ])
+            .to_string(),
+        );
+        // Filter rejected: listed again without it
+        mock.respond(400, r#"{"Message": "Unknown parameter Search"}"#)
+            .respond(
+                200,
+                &json!([group(2, "East"), group(3, "Midwest")]).to_string(),
+            );
+        let client = mock_client(&mock);
+
+        let found = find_location_groups(&client, BASE_URL, "tok", "west")
+            .await
+            .unwrap();
+        assert_eq!(found.mode, SearchMode::Server);
+        assert!(mock.requests()[0].url.contains("Search=west"));
+
+        let found = find_location_groups(&client, BASE_URL, "tok", "WEST")
+            .await
+            .unwrap();
+        assert_eq!(found.mode, SearchMode::Client);
+        let ids: Vec<_> = found.matches.iter().map(|g| g.location_group_id).collect();
+        assert_eq!(ids, vec![1, 3]);
+
+        let found = find_location_groups(&client, BASE_URL, "tok", "west")
+            .await
+            .unwrap();
+        assert_eq!(found.mode, SearchMode::Client);
+        assert_eq!(found.matches.len(), 1);
+        assert!(!mock.requests()[3].url.contains("Search="));
+    }
+
+    #[test]
+    fn test_parse_location_ids_accepts_objects_and_integers() {
+        let objects = json!([{"LocationID": 101}, {"locationId": 102}]);
+        assert_eq!(parse_location_ids(&objects).unwrap(), vec![101, 102]);
+
+        let mixed = json!([103, {"LocationID": 104}]);
+        assert_eq!(parse_location_ids(&mixed).unwrap(), vec![103, 104]);
+        assert!(parse_location_ids(&Value::Null).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_location_ids_rejects_malformed_entries() {
+        let err =
+            parse_location_ids(&json!([{"LocationID": 101}, {"LocationID": "x"}])).unwrap_err();
+        assert!(matches!(&err, NimbusError::Parse(message) if message.contains("Locations[1]")));
+
+        assert!(parse_location_ids(&json!([{"Name": "Depot"}])).is_err());
+        assert!(parse_location_ids(&json!({"LocationID": 101})).is_err());
+    }
+
+    #[test]
+    fn test_link_next() {
+        assert_eq!(
+            link_next(r#"<https://a.test/x?p=1>; rel="prev", <https://a.test/x?p=3>; rel="next""#)
+                .as_deref(),
+            Some("https://a.test/x?p=3")
+        );
+        assert_eq!(link_next("</x?p=2>; rel=next").as_deref(), Some("/x?p=2"));
+        assert_eq!(
+            link_next(r#"</x?p=9>; rel="next last""#).as_deref(),
+            Some("/x?p=9")
+        );
+        // A last page sends a Link without next, which ends paging
+        assert!(link_next(r#"</x?p=1>; rel="first""#).is_none());
+        let headers = PageHeaders {
+            total: None,
+            has_link: true,
+            next_url: None,
+        };
+        assert!(!headers.page(vec![1, 2], 1, 2, None).has_next);
+    }
+
+    #[tokio::test]
+    async fn test_all_pages_stops_on_empty_or_total() {
+        // has_next is always true; an empty page must still end the stream
+        let fetched = std::sync::Mutex::new(Vec::new());
+        let items: Vec<u32> = all_pages(|page| {
+            fetched.lock().unwrap().push(page);
+            async move {
+                let items = if page < 3 { vec![page] } else { Vec::new() };
+                Ok(Page {
+                    items,
+                    page,
+                    total: None,
+                    has_next: true,
+                    next_url: None,
+                })
+            }
+        })
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(items, vec![1, 2]);
+        assert_eq!(*fetched.lock().unwrap(), vec![1, 2, 3]);
+
+        // With a total, paging stops once that many items have arrived
+        let items: Vec<u32> = all_pages(|page| async move {
+            Ok(Page {
+                items: vec![page, page],
+                page,
+                total: Some(4),
+                has_next: true,
+                next_url: None,
+            })
+        })
+        .try_collect()
+        .await
+        .unwrap();
+        assert_eq!(items, vec![1, 1, 2, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_all_pages_gives_up_on_endless_server() {
+        let result: Result<Vec<u32>, _> = all_pages(|page| async move {
+            Ok(Page {
+                items: vec![page],
+                page,
+                total: None,
+                has_next: true,
+                next_url: None,
+            })
+        })
+        .try_collect()
+        .await;
+        assert!(matches!(result, Err(NimbusError::Parse(_))));
+    }
+
+    #[test]
+    fn test_schedule_group_adhoc_fields_serialization() {
+        let request = ScheduleGroupRequest {
+            description: "Test Schedule".to_string(),
+            location_group_id: 42,
+            start_date: "2025-01-01".to_string(),
+            end_date: "2025-12-31".to_string(),
+            learning_period: LearningPeriod::Days30,
+            adhoc_fields: vec![
+                AdhocField {
+                    field_name: "adhoc_Campus".to_string(),
+                    value: "North".to_string(),
+                },
+                AdhocField {
+                    field_name: "adhoc_CostCentre".to_string(),
+                    value: "CC-100".to_string(),
+                },
+            ],
+            active: true,
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        let adhoc = json["adhoc_fields"].as_array().unwrap();
+        assert_eq!(adhoc.len(), 2);
+        assert_eq!(adhoc[1]["field_name"], "adhoc_CostCentre");
+        assert_eq!(adhoc[1]["value"], "CC-100");
+
+        let fields = collect_adhoc_fields(&request);
+        let names: Vec<&str> = fields.iter().map(|f| f.field_name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["adhoc_LearningPeriod", "adhoc_Campus", "adhoc_CostCentre"]
+        );
+    }
+
+    #[test]
+    fn test_location_group_batch_errors_per_index() {
+        let request = |description: &str, location_ids: Vec<i64>| LocationGroupRequest {
+            description: description.to_string(),
+            location_ids,
+            active: true,
+        };
+        let errors = location_group_batch_errors(
+            &[
+                request("West Coast", vec![101, 101]),
+                request(" ", vec![]),
+                request("east", vec![0, 5, -2]),
+                request("west coast ", vec![102]),
+            ],
+            255,
+        );
+
+        let codes: Vec<Vec<_>> = errors
+            .iter()
+            .map(|item| item.iter().map(|e| e.code).collect())
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                vec![],
+                vec!["empty_description", "empty_location_ids"],
+                vec!["invalid_location_id"],
+                vec!["duplicate_description"],
+            ]
+        );
+        assert!(errors[2][0].message.ends_with("got 0, -2"));
+        assert!(errors[3][0].message.ends_with("request 0"));
+    }
+
+    #[test]
+    fn test_validate_location_ids_rejects_empty() {
+        let err = validate_location_ids(&[]).unwrap_err();
+        let fields = err.field_errors().unwrap();
+        assert_eq!(fields[0].field, "location_ids");
+        assert_eq!(fields[0].code, "empty_location_ids");
+        assert!(err.to_string().contains("location_ids must not be empty"));
+    }
+
+    #[test]
+    fn test_validate_location_ids_dedupes_preserving_order() {
+        let ids = validate_location_ids(&[3, 1, 3, 2, 1]).unwrap();
+        assert_eq!(ids, vec![3, 1, 2]);
+    }
+
+    #[test]
+    fn test_parse_schedule_group_detail() {
+        let body = json!({
+            "ScheduleGroupID": 99,
+            "Description": "Q1 2025 Schedule",
+            "LocationGroupID": 42,
+            "GroupStartDate": "2025-01-01T00:00:00",
+            "GroupEndDate": "2025-03-31",
+            "AdhocFields": [
+                {"FieldName": "adhoc_LearningPeriod", "Value": "30"},
+                {"FieldName": "adhoc_Seats", "Value": 12}
+            ]
+        });
+        let detail = parse_schedule_group_detail(&body, 0).unwrap();
+        assert_eq!(detail.schedule_group_id, 99);
+        assert_eq!(detail.location_group_id, 42);
+        assert_eq!(detail.start_date, "2025-01-01");
+        assert_eq!(detail.end_date, "2025-03-31");
+        assert_eq!(detail.adhoc_fields.len(), 2);
+        assert_eq!(detail.adhoc_fields[1].value, "12");
+    }
+
+    #[test]
+    fn test_dry_run_returns_payload_without_sending() {
+        let request = LocationGroupRequest {
+            description: "West Coast Locations".to_string(),
+            location_ids: vec![101, 102, 101],
+            active: true,
+        };
+        // No client is involved, so no HTTP call can be made
+        let response = dry_run_location_group(&request, "key-1".to_string(), 255).unwrap();
+        assert_eq!(response.location_group_id, None);
+        assert_eq!(response.idempotency_key, "key-1");
+        assert_eq!(
+            response.payload.unwrap(),
+            json!({
+                "Description": "West Coast Locations",
+                "Active": true,
+                "Locations": [{"LocationID": 101}, {"LocationID": 102}]
+            })
+        );
+    }
+
+    #[test]
+    fn test_dry_run_still_validates() {
+        let request = ScheduleGroupRequest {
+            description: "Test Schedule".to_string(),
+            location_group_id: 42,
+            start_date: "2025-12-31".to_string(),
+            end_date: "2025-01-01".to_string(),
+            learning_period: LearningPeriod::Days30,
+            adhoc_fields: Vec::new(),
+            active: true,
+        };
+        assert!(dry_run_schedule_group(&request, "key-1".to_string(), 255).is_err());
+    }
+
+    #[test]
+    fn test_schedule_group_list_query_filter() {
+        let all = schedule_group_list_query(None, 2, 50);
+        assert!(all.iter().all(|(key, _)| *key != "LocationGroupID"));
+
+        let filtered = schedule_group_list_query(Some(42), 1, 50);
+        assert!(filtered.contains(&("LocationGroupID", "42".to_string())));
+        assert!(filtered.contains(&("PageNumber", "1".to_string())));
+    }
+
+    #[test]
+    fn test_learning_period_round_trip() {
+        for (raw, period) in [
+            ("30", LearningPeriod::Days30),
+            ("90", LearningPeriod::Days90),
+            ("term", LearningPeriod::Term),
+            ("120", LearningPeriod::Custom("120".to_string())),
+        ] {
+            assert_eq!(raw.parse::<LearningPeriod>().unwrap(), period);
+        }
+        assert_eq!(serde_json::to_value(LearningPeriod::Term).unwrap(), "Term");
+        let parsed: LearningPeriod = serde_json::from_value(json!("60")).unwrap();
+        assert_eq!(parsed, LearningPeriod::Days60);
+        assert!("  ".parse::<LearningPeriod>().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_respects_form_payload_encoding() {
+        let mock = MockExecutor::new();
+        mock.respond(201, r#"{"LocationGroupID": 77}"#);
+        let config = NimbusConfig {
+            payload_encoding: PayloadEncoding::Form,
+            ..NimbusConfig::default()
+        };
+        let client = NimbusClient::with_executor(config, mock.clone());
+        let request = LocationGroupRequest {
+            description: "West Coast".to_string(),
+            location_ids: vec![101],
+            active: true,
+        };
+
+        let id = post_location_group(&client, BASE_URL, "tok", &request, "key-1")
+            .await
+            .unwrap();
+        assert_eq!(id, 77);
+        let sent = &mock.requests()[0];
+        assert_eq!(
+            sent.headers["Content-Type"],
+            "application/x-www-form-urlencoded"
+        );
+        // Not JSON, so the mock can't parse it
+        assert_eq!(sent.body, None);
+    }
+
+    #[tokio::test]
+    async fn test_post_location_group_sends_expected_request() {
+        let mock = MockExecutor::new();
+        mock.respond(201, r#"{"LocationGroupID": 77}"#);
+        let request = LocationGroupRequest {
+            description: "West Coast".to_string(),
+            location_ids: vec![101, 102],
+            active: true,
+        };
+
+        let id = post_location_group(&mock_client(&mock), BASE_URL, "tok", &request, "key-1")
+            .await
+            .unwrap();
+        assert_eq!(id, 77);
+
+        let sent = &mock.requests()[0];
+        assert_eq!(sent.method, reqwest::Method::POST);
+        assert_eq!(sent.url, "https://nimbus.test/RESTApi/LocationGroup");
+        assert_eq!(sent.headers["AuthenticationToken"], "tok");
+        assert_eq!(sent.headers[IDEMPOTENCY_KEY_HEADER], "key-1");
+        assert_eq!(
+            sent.body,
+            Some(json!({
+                "Description": "West Coast",
+                "Active": true,
+                "Locations": [{"LocationID": 101}, {"LocationID": 102}]
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_post_location_group_surfaces_api_error() {
+        let mock = MockExecutor::new();
+        mock.respond(
+            400,
+            r#"{"Code": "LG_DUPLICATE", "Message": "Already exists"}"#,
+        );
+        let request = LocationGroupRequest {
+            description: "West Coast".to_string(),
+            location_ids: vec![101],
+            active: true,
+        };
+
+        let err = post_location_group(&mock_client(&mock), BASE_URL, "tok", &request, "key-1")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(400));
+        assert_eq!(
+            err.api_error().unwrap().code.as_deref(),
+            Some("LG_DUPLICATE")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_location_group_reference_stops_create() {
+        let mock = MockExecutor::new();
+        mock.respond(404, "");
+        let request = ScheduleGroupRequest {
+            description: "Term 1".to_string(),
+            location_group_id: 42,
+            start_date: "2025-01-01".to_string(),
+            end_date: "2025-03-31".to_string(),
+            learning_period: LearningPeriod::Days30,
+            adhoc_fields: Vec::new(),
+            active: true,
+        };
+
+        let err = send_schedule_group(&mock_client(&mock), BASE_URL, "tok", &request, "k", true)
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), "referenced_entity_not_found");
+        assert_eq!(
+            err.to_string(),
+            "LocationGroup 42 does not exist, so the ScheduleGroup cannot reference it"
+        );
+        // Only the check was sent, never the POST
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, reqwest::Method::GET);
+        assert_eq!(
+            requests[0].url,
+            format!("{}/RESTApi/LocationGroup/42", BASE_URL)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_schedule_group_maps_404() {
+        let mock = MockExecutor::new();
+        mock.respond(404, "");
+
+        let err = fetch_schedule_group(&mock_client(&mock), BASE_URL, "tok", 9)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NimbusError::NotFound {
+                entity: "ScheduleGroup",
+                id: 9
+            }
+        ));
+        assert_eq!(
+            mock.requests()[0].url,
+            "https://nimbus.test/RESTApi/ScheduleGroup/9"
+        );
+    }
+
+    fn schedule_group_body(start_date: &str, end_date: &str) -> String {
+        json!({
+            "ScheduleGroupID": 9,
+            "Description": "Spring Term",
+            "LocationGroupID": 5,
+            "GroupStartDate": start_date,
+            "GroupEndDate": end_date,
+            "Active": true,
+            "AdhocFields": [{"FieldName": "adhoc_LearningPeriod", "Value": "30"}]
+        })
+        .to_string()
+    }
+
+    fn location_group_body(location_ids: &[i64]) -> String {
+        let locations: Vec<Value> = location_ids
+            .iter()
+            .map(|id| json!({"LocationID": id}))
+            .collect();
+        json!({
+            "LocationGroupID": 5,
+            "Description": "West Coast",
+            "Active": true,
+            "Locations": locations
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_diff_location_group_reports_changes_and_nothing_else() {
+        let mock = MockExecutor::new();
+        mock.respond(200, &location_group_body(&[101, 102, 103]))
+            .respond(200, &location_group_body(&[101, 102, 103]));
+        let client = mock_client(&mock);
+
+        let request = LocationGroupRequest {
+            description: "West Coast (all)".to_string(),
+            location_ids: vec![103, 104, 101, 104],
+            active: false,
+        };
+        let diff = preview_location_group_update(&client, BASE_URL, "tok", 5, &request)
+            .await
+            .unwrap();
+        assert_eq!(
+            diff.description,
+            Some(FieldChange {
+                from: "West Coast".to_string(),
+                to: "West Coast (all)".to_string(),
+            })
+        );
+        assert_eq!(
+            diff.active,
+            Some(FieldChange {
+                from: true,
+                to: false
+            })
+        );
+        assert_eq!(diff.added_location_ids, vec![104]);
+        assert_eq!(diff.removed_location_ids, vec![102]);
+
+        // Same group with the locations reordered
+        let unchanged = LocationGroupRequest {
+            description: "West Coast".to_string(),
+            location_ids: vec![103, 101, 102],
+            active: true,
+        };
+        let diff = preview_location_group_update(&client, BASE_URL, "tok", 5, &unchanged)
+            .await
+            .unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_add_locations_posts_only_the_delta() {
+        let mock = MockExecutor::new();
+        mock.respond(204, "")
+            .respond(200, &location_group_body(&[101, 102, 103]));
+
+        let ids = apply_location_delta(
+            &mock_client(&mock),
+            BASE_URL,
+            "tok",
+            5,
+            LocationDelta::Add,
+            &[103],
+        )
+        .await
+        .unwrap();
+        assert_eq!(ids, vec![101, 102, 103]);
+
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, reqwest::Method::POST);
+        assert!(requests[0]
+            .url
+            .ends_with("/RESTApi/LocationGroup/5/Locations"));
+        assert_eq!(
+            requests[0].body,
+            Some(json!({"Locations": [{"LocationID": 103}]}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_remove_locations_falls_back_to_put() {
+        let mock = MockExecutor::new();
+        mock.respond(405, "")
+            .respond(200, &location_group_body(&[101, 102, 103]))
+            .respond(200, "");
+
+        let ids = apply_location_delta(
+            &mock_client(&mock),
+            BASE_URL,
+            "tok",
+            5,
+            LocationDelta::Remove,
+            &[102],
+        )
+        .await
+        .unwrap();
+        assert_eq!(ids, vec![101, 103]);
+
+        let requests = mock.requests();
+        let methods: Vec<_> = requests.iter().map(|r| r.method.as_str()).collect();
+        assert_eq!(methods, ["DELETE", "GET", "PUT"]);
+        let put = requests[2].body.as_ref().unwrap();
+        assert_eq!(
+            put["Locations"],
+            json!([{"LocationID": 101}, {"LocationID": 103}])
+        );
+        assert_eq!(put["Description"], "West Coast");
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_identical_reads_share_one_request() {
+        let mock = MockExecutor::new();
+        mock.with_latency(std::time::Duration::from_millis(50))
+            .respond(200, &location_group_body(&[101]));
+        let client = mock_client(&mock);
+
+        let (a, b, c) = tokio::join!(
+            fetch_location_group(&client, BASE_URL, "tok", 5),
+            fetch_location_group(&client, BASE_URL, "tok", 5),
+            fetch_location_group(&client, BASE_URL, "tok", 5),
+        );
+        for detail in [a, b, c] {
+            assert_eq!(detail.unwrap().location_ids, vec![101]);
+        }
+        assert_eq!(mock.requests().len(), 1);
+        assert!(client.in_flight().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_set_active_patches_only_the_flag() {
+        let mock = MockExecutor::new();
+        mock.respond(200, r#"{"LocationGroupID": 5, "Active": false}"#)
+            .respond(405, "")
+            .respond(200, &location_group_body(&[101]))
+            .respond(200, "");
+        let client = mock_client(&mock);
+
+        let active = patch_location_group_active(&client, BASE_URL, "tok", 5, false).await;
+        assert!(!active.unwrap());
+        let requests = mock.requests();
+        assert_eq!(requests[0].method, reqwest::Method::PATCH);
+        assert_eq!(requests[0].body, Some(json!({"Active": false})));
+
+        // Without PATCH the whole group is resent with the new flag
+        let active = patch_location_group_active(&client, BASE_URL, "tok", 5, false).await;
+        assert!(!active.unwrap());
+        let requests = mock.requests();
+        let methods: Vec<_> = requests[1..].iter().map(|r| r.method.as_str()).collect();
+        assert_eq!(methods, ["PATCH", "GET", "PUT"]);
+        let put = requests[3].body.as_ref().unwrap();
+        assert_eq!(put["Active"], false);
+        assert_eq!(put["Locations"], json!([{"LocationID": 101}]));
+    }
+
+    #[test]
+    fn test_schedule_group_request_from_detail() {
+        let mut body: Value =
+            serde_json::from_str(&schedule_group_body("2025-01-01", "2025-06-30")).unwrap();
+        body["AdhocFields"]
+            .as_array_mut()
+            .unwrap()
+            .push(json!({"FieldName": "adhoc_Campus", "Value": "North"}));
+        let detail = parse_schedule_group_detail(&body, 9).unwrap();
+
+        let request = schedule_group_request_from_detail(detail).unwrap();
+        assert_eq!(request.learning_period, LearningPeriod::Days30);
+        assert_eq!(request.location_group_id, 5);
+        assert_eq!(request.adhoc_fields.len(), 1);
+        assert_eq!(request.adhoc_fields[0].field_name, "adhoc_Campus");
+    }
+
+    #[tokio::test]
+    async fn test_patch_schedule_dates_sends_only_dates() {
+        let mock = MockExecutor::new();
+        mock.respond(200, &schedule_group_body("2025-02-01", "2025-07-01"));
+
+        let detail = patch_schedule_dates(
+            &mock_client(&mock),
+            BASE_URL,
+            "tok",
+            9,
+            "2025-02-01",
+            "2025-07-01",
+        )
+        .await
+        .unwrap();
+        assert_eq!(detail.start_date, "2025-02-01");
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, reqwest::Method::PATCH);
+        assert_eq!(
+            requests[0].body,
+            Some(json!({"GroupStartDate": "2025-02-01", "GroupEndDate": "2025-07-01"}))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_location_window_reports_each_broken_rule() {
+        let mock = MockExecutor::new();
+        mock.respond(
+            200,
+            &json!({
+                "LocationGroupID": 5,
+                "Description": "West Coast",
+                "Active": false,
+                "effectiveDate": "2025-03-01T00:00:00",
+                "EndDate": null
+            })
+            .to_string(),
+        )
+        .respond(200, &location_group_body(&[1]));
+        let request = ScheduleGroupRequest {
+            description: "Spring Term".to_string(),
+            location_group_id: 5,
+            start_date: "2025-02-01".to_string(),
+            end_date: "2025-07-01".to_string(),
+            ..ScheduleGroupRequest::default()
+        };
+
+        let check = check_location_window(&mock_client(&mock), BASE_URL, "tok", &request)
+            .await
+            .unwrap();
+        assert_eq!(check.valid_from.as_deref(), Some("2025-03-01"));
+        assert_eq!(check.valid_until, None);
+        let codes: Vec<_> = check.errors.iter().map(|e| e.code).collect();
+        assert_eq!(
+            codes,
+            vec!["location_group_inactive", "starts_before_location_group"]
+        );
+
+        // Without a window only the active flag is checked
+        let check = check_location_window(&mock_client(&mock), BASE_URL, "tok", &request)
+            .await
+            .unwrap();
+        assert!(check.errors.is_empty());
+
+        // Malformed dates fail before anything is fetched
+        let bad = ScheduleGroupRequest {
+            end_date: "July".to_string(),
+            ..request
+        };
+        let result = check_location_window(&mock_client(&mock), BASE_URL, "tok", &bad).await;
+        assert!(matches!(result, Err(NimbusError::Validation(_))));
+        assert_eq!(mock.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_patch_schedule_dates_falls_back_to_put() {
+        let mock = MockExecutor::new();
+        mock.respond(405, "")
+            .respond(200, &schedule_group_body("2025-01-01", "2025-06-30"))
+            .respond(200, "")
+            .respond(200, &schedule_group_body("2025-02-01", "2025-07-01"));
+
+        let detail = patch_schedule_dates(
+            &mock_client(&mock),
+            BASE_URL,
+            "tok",
+            9,
+            "2025-02-01",
+            "2025-07-01",
+        )
+        .await
+        .unwrap();
+        assert_eq!(detail.end_date, "2025-07-01");
+
+        let requests = mock.requests();
+        let methods: Vec<_> = requests.iter().map(|r| r.method.as_str()).collect();
+        assert_eq!(methods, ["PATCH", "GET", "PUT", "GET"]);
+        let put = requests[2].body.as_ref().unwrap();
+        assert_eq!(put["GroupStartDate"], "2025-02-01");
+        assert_eq!(put["Description"], "Spring Term");
+        assert_eq!(put["AdhocFields"][0]["Value"], "30");
+    }
+
+    #[tokio::test]
+    async fn test_created_detail_uses_echo_or_fetches() {
+        let mock = MockExecutor::new();
+        let client = mock_client(&mock);
+
+        let echoed = json!({
+            "LocationGroupID": 5,
+            "Description": "West Coast",
+            "Active": true,
+            "Locations": [{"LocationID": 101}]
+        });
+        let detail = created_location_group_detail(&client, BASE_URL, "tok", 5, &echoed).await;
+        assert_eq!(detail.unwrap().location_ids, vec![101]);
+        assert!(mock.requests().is_empty());
+
+        mock.respond(200, &echoed.to_string());
+        let id_only = json!({"LocationGroupID": 5});
+        let detail = created_location_group_detail(&client, BASE_URL, "tok", 5, &id_only).await;
+        assert_eq!(detail.unwrap().description, "West Coast");
+        assert_eq!(
+            mock.requests()[0].url,
+            "https://nimbus.test/RESTApi/LocationGroup/5"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_handles_empty_and_plain_text_bodies() {
+        let mock = MockExecutor::new();
+        mock.respond(200, "")
+            .respond_with_headers(201, &[("Location", "/RESTApi/LocationGroup/42")], "")
+            .respond_with_headers(200, &[("Content-Type", "text/plain")], "43");
+        let client = mock_client(&mock);
+        let request = LocationGroupRequest {
+            description: "West Coast".to_string(),
+            location_ids: vec![101],
+            active: true,
+        };
+
+        let err = post_location_group(&client, BASE_URL, "tok", &request, "key")
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NimbusError::UnexpectedEmptyBody { status: 200 }
+        ));
+
+        let from_location = post_location_group(&client, BASE_URL, "tok", &request, "key").await;
+        assert_eq!(from_location.unwrap(), 42);
+        let from_text = post_location_group(&client, BASE_URL, "tok", &request, "key").await;
+        assert_eq!(from_text.unwrap(), 43);
     }
 }
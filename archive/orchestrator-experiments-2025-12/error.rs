@@ -0,0 +1,676 @@
+use std::sync::RwLock;
+use std::time::Duration;
+
+use serde::ser::{SerializeStruct, Serializer};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::client::RetryPolicy;
+
+/// Error object returned by Nimbus on a 4xx/5xx
+///
+/// When the body isn't JSON (or has no message), `message` holds the raw
+/// body text and `code` is None.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NimbusApiError {
+    pub code: Option<String>,
+    pub message: String,
+}
+
+impl NimbusApiError {
+    /// Parses an error body, falling back to the raw text
+    pub fn parse(body: &str) -> Self {
+        let fallback = || NimbusApiError {
+            code: None,
+            message: body.to_string(),
+        };
+
+        let Ok(json) = serde_json::from_str::<Value>(body) else {
+            return fallback();
+        };
+        // Nimbus deployments differ in the casing/prefix of these keys
+        let field = |names: &[&str]| {
+            names.iter().find_map(|name| match json.get(*name)? {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            })
+        };
+
+        match field(&["Message", "ErrorMessage", "message"]) {
+            Some(message) => NimbusApiError {
+                code: field(&["Code", "ErrorCode", "code"]),
+                message,
+            },
+            None => fallback(),
+        }
+    }
+}
+
+/// One invalid input field, so a form can highlight it
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldError {
+    /// Request field name, e.g. `start_date`
+    pub field: String,
+    /// Stable machine code, e.g. `end_before_start`, for the UI to translate
+    pub code: &'static str,
+    /// English description, for logs and as the UI's fallback
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: &str, code: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<Vec<FieldError>> for NimbusError {
+    fn from(errors: Vec<FieldError>) -> Self {
+        NimbusError::InvalidFields(errors)
+    }
+}
+
+/// Every field error's message, for the `InvalidFields` display text
+fn field_messages(errors: &[FieldError]) -> String {
+    let messages: Vec<_> = errors.iter().map(|e| e.message.as_str()).collect();
+    messages.join("; ")
+}
+
+/// Tokens scrubbed from every serialized error, kept in sync by `Session`
+static REDACTED_TOKENS: RwLock<Vec<String>> = RwLock::new(Vec::new());
+
+/// Replacement for anything `redact` scrubs
+pub const REDACTED: &str = "***";
+
+/// Key names whose value is scrubbed wherever they appear as `key=value` or
+/// `key: value` (including JSON `"key": "value"`); matched case-insensitively
+const SECRET_KEYS: &[&str] = &[
+    "authenticationtoken",
+    "access_token",
+    "refresh_token",
+    "refreshtoken",
+    "accesstoken",
+    "client_secret",
+    "password",
+    "api_key",
+    "apikey",
+    "token",
+];
+
+/// Sets the tokens `redact` scrubs, one or two per logged-in profile
+pub fn set_redacted_tokens(tokens: Vec<String>) {
+    *REDACTED_TOKENS.write().expect("redaction lock poisoned") =
+        tokens.into_iter().filter(|t| !t.is_empty()).collect();
+}
+
+/// Replaces the stored tokens and secret-looking values in `text` with `***`
+///
+/// Besides the tokens themselves this catches `Bearer <value>` and the values of
+/// `SECRET_KEYS`, so a token that was never stored (e.g. one echoed back
+/// during a failed login) is scrubbed too.
+pub fn redact(text: &str) -> String {
+    let tokens = REDACTED_TOKENS.read().expect("redaction lock poisoned");
+    redact_with(text, &tokens)
+}
+
+/// `redact` with an explicit token list instead of the stored one
+fn redact_with(text: &str, tokens: &[String]) -> String {
+    let mut text = text.to_string();
+    for token in tokens {
+        text = text.replace(token.as_str(), REDACTED);
+    }
+    redact_secret_values(&text)
+}
+
+/// Scrubs a parsed JSON body in place
+///
+/// Values under `SECRET_KEYS` (compared ignoring case) become `***`, and the
+/// stored tokens are removed from every other string. Unlike `redact` on the
+/// raw text, ordinary data that merely looks like `key=value` is left alone.
+pub fn redact_value(value: &mut Value) {
+    let tokens = REDACTED_TOKENS.read().expect("redaction lock poisoned");
+    redact_value_with(value, &tokens);
+}
+
+fn redact_value_with(value: &mut Value, tokens: &[String]) {
+    match value {
+        Value::Object(object) => {
+            for (key, value) in object.iter_mut() {
+                let secret = SECRET_KEYS.iter().any(|k| key.eq_ignore_ascii_case(k));
+                if secret && !value.is_null() && !value.is_object() && !value.is_array() {
+                    *value = Value::String(REDACTED.to_string());
+                } else {
+                    redact_value_with(value, tokens);
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| redact_value_with(item, tokens)),
+        Value::String(text) => {
+            for token in tokens {
+                if text.contains(token.as_str()) {
+                    *text = text.replace(token.as_str(), REDACTED);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+fn redact_secret_values(text: &str) -> String {
+    // ASCII lowercasing keeps byte offsets identical to `text`. Matching works
+    // on bytes, since `pos` can land inside a multi-byte character; every
+    // offset sliced out of `text` sits next to an ASCII byte.
+    let lower = text.to_ascii_lowercase();
+    let bytes = lower.as_bytes();
+    let is_value_end =
+        |b: u8| b.is_ascii_whitespace() || matches!(b, b'&' | b'"' | b'\'' | b',' | b';' | b')');
+
+    let mut out = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let at_word_start = pos == 0 || !bytes[pos - 1].is_ascii_alphanumeric();
+        let value_start = if !at_word_start {
+            None
+        } else if bytes[pos..].starts_with(b"bearer ") {
+            Some(pos + "bearer ".len())
+        } else {
+            SECRET_KEYS
+                .iter()
+                .find(|key| bytes[pos..].starts_with(key.as_bytes()))
+                .and_then(|key| {
+                    // Optional closing quote, then `:` or `=`, then spaces and an opening quote
+                    let mut i = pos + key.len();
+                    if bytes.get(i) == Some(&b'"') {
+                        i += 1;
+                    }
+                    if !matches!(bytes.get(i), Some(b':' | b'=')) {
+                        return None;
+                    }
+                    i += 1;
+                    while matches!(bytes.get(i), Some(b' ' | b'"')) {
+                        i += 1;
+                    }
+                    Some(i)
+                })
+        };
+
+        let Some(start) = value_start else {
+            pos += 1;
+            continue;
+        };
+        let end = (start..bytes.len())
+            .find(|&i| is_value_end(bytes[i]))
+            .unwrap_or(bytes.len());
+        if end > start {
+            out.push_str(&text[copied..start]);
+            out.push_str(REDACTED);
+            copied = end;
+        }
+        pos = end.max(pos + 1);
+    }
+    out.push_str(&text[copied..]);
+    out
+}
+
+/// Errors returned by the Nimbus entity commands
+///
+/// Serialized across the Tauri boundary as `{ kind, message, status, ... }` so the
+/// frontend can branch on `kind` instead of string-matching `message`. Every
+/// string in that object goes through `redact` first.
+#[derive(Debug, thiserror::Error)]
+pub enum NimbusError {
+    /// A header value (usually the token) could not be encoded
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
+
+    /// The request never produced a response (DNS, connect, TLS, ...)
+    #[error("Network error: {0}")]
+    Network(reqwest::Error),
+
+    /// The server did not answer within the configured timeout
+    #[error("Request timed out: {0}")]
+    Timeout(reqwest::Error),
+
+    /// The server answered with a non-success status
+    #[error("API error ({status}): {}", error.message)]
+    Http {
+        status: u16,
+        error: NimbusApiError,
+        /// `X-Correlation-Id` the failed request was sent with, if it had one
+        correlation_id: Option<String>,
+    },
+
+    /// Nimbus kept answering 429 until the retry attempts ran out
+    #[error("Rate limited by Nimbus; retry after {retry_after:?}")]
+    RateLimited { retry_after: Duration },
+
+    /// Recent calls kept failing, so the client is refusing to send for a while
+    #[error("Nimbus keeps failing; requests are paused for {retry_after:?}")]
+    CircuitOpen { retry_after: Duration },
+
+    /// The server answered 404 for a specific entity
+    #[error("{entity} {id} not found")]
+    NotFound { entity: &'static str, id: i64 },
+
+    /// A request names an entity that a pre-check found missing, so it was not sent
+    #[error("{entity} {id} does not exist, so the {referenced_by} cannot reference it")]
+    ReferencedEntityNotFound {
+        entity: &'static str,
+        id: i64,
+        referenced_by: &'static str,
+    },
+
+    /// No token has been stored yet
+    #[error("Not logged in to Nimbus")]
+    NotAuthenticated,
+
+    /// The OS keychain could not be read or written
+    #[error("Keychain error: {0}")]
+    Keychain(String),
+
+    /// No Nimbus profile is selected (or none are configured)
+    #[error("No active Nimbus profile")]
+    NoActiveProfile,
+
+    /// A local config file is unreadable or malformed
+    #[error("Config error: {0}")]
+    Config(String),
+
+    /// The request was rejected locally before being sent
+    #[error("Validation failed: {0}")]
+    Validation(String),
+
+    /// The request was rejected locally, with the field each problem is in
+    #[error("Validation failed: {}", field_messages(.0))]
+    InvalidFields(Vec<FieldError>),
+
+    /// The connected Nimbus server doesn't offer the endpoint a command needs
+    #[error("Not supported by this Nimbus server: {0}")]
+    Unsupported(String),
+
+    /// The command is turned off by the client's configuration
+    #[error("Disabled: {0}")]
+    Disabled(String),
+
+    /// The response body was larger than `NimbusConfig::max_response_bytes`
+    #[error("Response body exceeded the {limit}-byte limit")]
+    ResponseTooLarge { limit: u64 },
+
+    /// A success response had no body where the command needed one
+    #[error("Nimbus answered {status} with an empty body")]
+    UnexpectedEmptyBody { status: u16 },
+
+    /// The response body was not in the expected shape
+    #[error("Failed to parse response: {0}")]
+    Parse(String),
+
+    /// A LocationGroup was created but the ScheduleGroup that followed it failed
+    #[error(
+        "Created LocationGroup {location_group_id} but creating its ScheduleGroup failed: {source}"
+    )]
+    PartialFailure {
+        location_group_id: i64,
+        source: Box<NimbusError>,
+    },
+}
+
+impl NimbusError {
+    /// Builds an `Http` error from a non-success response, consuming its body
+    pub async fn from_response(response: reqwest::Response) -> Self {
+        let status = response.status().as_u16();
+        let body = response
+            .text()
+            .await
+            .unwrap_or_else(|_| "Unknown error".to_string());
+
+        Self::from_body(status, &body)
+    }
+
+    /// Builds an `Http` error from a status and an already-read body
+    pub fn from_body(status: u16, body: &str) -> Self {
+        tracing::debug!(status, body = %redact(body), "Nimbus returned an error");
+
+        NimbusError::Http {
+            status,
+            error: NimbusApiError::parse(body),
+            correlation_id: None,
+        }
+    }
+
+    /// Tags an `Http` error with the correlation ID of the request that failed
+    pub fn with_correlation_id(mut self, id: &str) -> Self {
+        if let NimbusError::Http { correlation_id, .. } = &mut self {
+            *correlation_id = Some(id.to_string());
+        }
+        self
+    }
+
+    /// The error message with secrets scrubbed by `redact`; use this for
+    /// anything shown to the user or written to disk
+    pub fn sanitized_message(&self) -> String {
+        redact(&self.to_string())
+    }
+
+    /// Stable machine-readable discriminator for the frontend
+    pub fn kind(&self) -> &'static str {
+        match self {
+            NimbusError::InvalidHeader(_) => "invalid_header",
+            NimbusError::Network(_) => "network",
+            NimbusError::Timeout(_) => "timeout",
+            NimbusError::Http { .. } => "http",
+            NimbusError::RateLimited { .. } => "rate_limited",
+            NimbusError::CircuitOpen { .. } => "circuit_open",
+            NimbusError::NotFound { .. } => "not_found",
+            NimbusError::ReferencedEntityNotFound { .. } => "referenced_entity_not_found",
+            NimbusError::NotAuthenticated => "not_authenticated",
+            NimbusError::Keychain(_) => "keychain",
+            NimbusError::NoActiveProfile => "no_active_profile",
+            NimbusError::Config(_) => "config",
+            NimbusError::Validation(_) | NimbusError::InvalidFields(_) => "validation",
+            NimbusError::Unsupported(_) => "unsupported",
+            NimbusError::Disabled(_) => "disabled",
+            NimbusError::ResponseTooLarge { .. } => "response_too_large",
+            NimbusError::UnexpectedEmptyBody { .. } => "unexpected_empty_body",
+            NimbusError::Parse(_) => "parse",
+            NimbusError::PartialFailure { .. } => "partial_failure",
+        }
+    }
+
+    /// Whether sending the same request again later could succeed
+    ///
+    /// True for network errors, timeouts, an open circuit, and the statuses in
+    /// `policy.retryable_statuses`. Every other error (other statuses,
+    /// validation, parse, ...) would fail the same way again. A
+    /// `PartialFailure` is not retryable either, since its LocationGroup
+    /// already exists.
+    pub fn is_retryable(&self, policy: &RetryPolicy) -> bool {
+        match self {
+            NimbusError::Network(_)
+            | NimbusError::Timeout(_)
+            | NimbusError::RateLimited { .. }
+            | NimbusError::CircuitOpen { .. } => true,
+            NimbusError::Http { status, .. } => policy.retryable_statuses.contains(status),
+            _ => false,
+        }
+    }
+
+    /// The server's structured error, for `Http` errors
+    pub fn api_error(&self) -> Option<&NimbusApiError> {
+        match self {
+            NimbusError::Http { error, .. } => Some(error),
+            NimbusError::PartialFailure { source, .. } => source.api_error(),
+            _ => None,
+        }
+    }
+
+    /// HTTP status associated with the error, if any
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            NimbusError::Http { status, .. } => Some(*status),
+            NimbusError::RateLimited { .. } => Some(429),
+            NimbusError::UnexpectedEmptyBody { status } => Some(*status),
+            NimbusError::NotFound { .. } => Some(404),
+            NimbusError::Network(e) => e.status().map(|s| s.as_u16()),
+            NimbusError::PartialFailure { source, .. } => source.status(),
+            _ => None,
+        }
+    }
+
+    /// Correlation ID of the request behind an `Http` error
+    pub fn correlation_id(&self) -> Option<&str> {
+        match self {
+            NimbusError::Http { correlation_id, .. } => correlation_id.as_deref(),
+            NimbusError::PartialFailure { source, .. } => source.correlation_id(),
+            _ => None,
+        }
+    }
+
+    /// The invalid fields behind an `InvalidFields` error
+    pub fn field_errors(&self) -> Option<&[FieldError]> {
+        match self {
+            NimbusError::InvalidFields(errors) => Some(errors),
+            _ => None,
+        }
+    }
+
+    /// How long to wait before trying again, for `RateLimited` and `CircuitOpen` errors
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            NimbusError::RateLimited { retry_after } => Some(*retry_after),
+            NimbusError::CircuitOpen { retry_after } => Some(*retry_after),
+            _ => None,
+        }
+    }
+
+    /// LocationGroup left behind by a `PartialFailure`, for cleanup or retry
+    pub fn created_location_group_id(&self) -> Option<i64> {
+        match self {
+            NimbusError::PartialFailure {
+                location_group_id, ..
+            } => Some(*location_group_id),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for NimbusError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_timeout() {
+            NimbusError::Timeout(error)
+        } else {
+            NimbusError::Network(error)
+        }
+    }
+}
+
+impl Serialize for NimbusError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("NimbusError", 8)?;
+        state.serialize_field("kind", self.kind())?;
+        state.serialize_field("message", &self.sanitized_message())?;
+        state.serialize_field("status", &self.status())?;
+        let api_error = self.api_error().map(|error| NimbusApiError {
+            code: error.code.clone(),
+            message: redact(&error.message),
+        });
+        state.serialize_field("api_error", &api_error)?;
+        let fields = self.field_errors().map(|errors| {
+            errors
+                .iter()
+                .map(|error| FieldError {
+                    message: redact(&error.message),
+                    ..error.clone()
+                })
+                .collect::<Vec<_>>()
+        });
+        state.serialize_field("fields", &fields)?;
+        state.serialize_field("location_group_id", &self.created_location_group_id())?;
+        state.serialize_field("correlation_id", &self.correlation_id())?;
+        state.serialize_field(
+            "retry_after_ms",
+            &self.retry_after().map(|d| d.as_millis() as u64),
+        )?;
+        state.end()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_http_error_serialization() {
+        let error = NimbusError::from_body(400, "Bad request").with_correlation_id("c-1");
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "http");
+        assert_eq!(json["status"], 400);
+        assert_eq!(json["correlation_id"], "c-1");
+        assert_eq!(json["message"], "API error (400): Bad request");
+        assert_eq!(json["api_error"]["code"], Value::Null);
+        assert_eq!(json["api_error"]["message"], "Bad request");
+    }
+
+    #[test]
+    fn test_field_errors_are_serialized_as_fields() {
+        let error = NimbusError::from(vec![
+            FieldError::new(
+                "end_date",
+                "end_before_start",
+                "end_date is before start_date",
+            ),
+            FieldError::new(
+                "learning_period",
+                "unknown_learning_period",
+                "no such period",
+            ),
+        ]);
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "validation");
+        assert_eq!(
+            json["message"],
+            "Validation failed: end_date is before start_date; no such period"
+        );
+        assert_eq!(json["fields"][0]["field"], "end_date");
+        assert_eq!(json["fields"][0]["code"], "end_before_start");
+        assert_eq!(json["fields"][1]["code"], "unknown_learning_period");
+
+        let json = serde_json::to_value(NimbusError::NotAuthenticated).unwrap();
+        assert_eq!(json["fields"], Value::Null);
+    }
+
+    #[test]
+    fn test_serialized_error_redacts_secrets() {
+        let error = NimbusError::from_body(
+            401,
+            r#"{"Message": "Token rejected (Authorization: Bearer abc.def)"}"#,
+        );
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(
+            json["message"],
+            "API error (401): Token rejected (Authorization: Bearer ***)"
+        );
+        assert_eq!(
+            json["api_error"]["message"],
+            "Token rejected (Authorization: Bearer ***)"
+        );
+
+        // The stored token list is global and shared with other tests
+        let tokens = vec!["tok-5ecret".to_string()];
+        assert_eq!(
+            redact_with("Token tok-5ecret rejected", &tokens),
+            "Token *** rejected"
+        );
+
+        assert_eq!(
+            redact("GET /x?token=abc&page=2 failed; password: hunter2"),
+            "GET /x?token=***&page=2 failed; password: ***"
+        );
+        assert_eq!(
+            redact(r#"{"AuthenticationToken": "xyz", "tokens": 3}"#),
+            r#"{"AuthenticationToken": "***", "tokens": 3}"#
+        );
+    }
+
+    #[test]
+    fn test_redact_value_only_touches_secret_keys() {
+        let mut body = serde_json::json!({
+            "Description": "password=not-a-secret",
+            "Password": "hunter2",
+            "Nested": [{"access_token": "abc", "Note": "sent tok-5ecret"}],
+            "Token": null
+        });
+        redact_value_with(&mut body, &["tok-5ecret".to_string()]);
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "Description": "password=not-a-secret",
+                "Password": "***",
+                "Nested": [{"access_token": "***", "Note": "sent ***"}],
+                "Token": null
+            })
+        );
+    }
+
+    #[test]
+    fn test_redaction_handles_non_ascii_text() {
+        assert_eq!(
+            redact("Café Zürich: password=geheimé; ünïcode Bearer ßtoken"),
+            "Café Zürich: password=***; ünïcode Bearer ***"
+        );
+        assert_eq!(redact("é"), "é");
+        assert_eq!(redact("tokén: x"), "tokén: x");
+    }
+
+    #[test]
+    fn test_api_error_parses_nimbus_json() {
+        let error =
+            NimbusApiError::parse(r#"{"Code": "LG_DUPLICATE", "Message": "Already exists"}"#);
+        assert_eq!(error.code.as_deref(), Some("LG_DUPLICATE"));
+        assert_eq!(error.message, "Already exists");
+    }
+
+    #[test]
+    fn test_api_error_falls_back_to_raw_text() {
+        let raw = r#"{"unexpected": true}"#;
+        assert_eq!(NimbusApiError::parse(raw).message, raw);
+        assert_eq!(
+            NimbusApiError::parse("<html>502</html>").message,
+            "<html>502</html>"
+        );
+    }
+
+    #[test]
+    fn test_partial_failure_keeps_location_group_id() {
+        let error = NimbusError::PartialFailure {
+            location_group_id: 42,
+            source: Box::new(NimbusError::from_body(
+                409,
+                r#"{"Message": "Duplicate schedule"}"#,
+            )),
+        };
+        let json = serde_json::to_value(&error).unwrap();
+        assert_eq!(json["kind"], "partial_failure");
+        assert_eq!(json["location_group_id"], 42);
+        assert_eq!(json["status"], 409);
+        assert_eq!(json["api_error"]["message"], "Duplicate schedule");
+    }
+
+    #[test]
+    fn test_retryable_errors_are_the_transient_ones() {
+        let retryable = [
+            NimbusError::from_body(503, "busy"),
+            NimbusError::from_body(429, "slow down"),
+            NimbusError::RateLimited {
+                retry_after: Duration::from_secs(1),
+            },
+        ];
+        let policy = RetryPolicy::default();
+        assert!(retryable.iter().all(|e| e.is_retryable(&policy)));
+
+        let permanent = [
+            NimbusError::from_body(400, "Bad request"),
+            NimbusError::NotFound {
+                entity: "LocationGroup",
+                id: 1,
+            },
+            NimbusError::Validation("Description is required".to_string()),
+        ];
+        assert!(!permanent.iter().any(|e| e.is_retryable(&policy)));
+
+        // Only the statuses the client retries count
+        let policy = RetryPolicy {
+            retryable_statuses: vec![408, 503],
+            ..RetryPolicy::default()
+        };
+        assert!(NimbusError::from_body(408, "timeout").is_retryable(&policy));
+        assert!(!NimbusError::from_body(429, "slow down").is_retryable(&policy));
+        assert!(!NimbusError::from_body(500, "oops").is_retryable(&policy));
+    }
+}
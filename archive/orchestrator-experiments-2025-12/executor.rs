@@ -0,0 +1,143 @@
+use futures::future::BoxFuture;
+use reqwest::{Request, Response};
+
+/// Sends a fully built request and returns the raw response
+///
+/// `NimbusClient` routes every call through one of these, so tests can swap
+/// the network for `MockExecutor` and inspect exactly what would be sent.
+pub trait HttpExecutor: Send + Sync {
+    fn execute(&self, request: Request) -> BoxFuture<'_, Result<Response, reqwest::Error>>;
+}
+
+/// The production executor, backed by the pooled `reqwest::Client`
+pub struct ReqwestExecutor {
+    http: reqwest::Client,
+}
+
+impl ReqwestExecutor {
+    pub fn new(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+}
+
+impl HttpExecutor for ReqwestExecutor {
+    fn execute(&self, request: Request) -> BoxFuture<'_, Result<Response, reqwest::Error>> {
+        Box::pin(self.http.execute(request))
+    }
+}
+
+#[cfg(test)]
+pub(crate) use mock::MockExecutor;
+
+#[cfg(test)]
+mod mock {
+    use std::collections::VecDeque;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use futures::future::BoxFuture;
+    use reqwest::header::HeaderMap;
+    use reqwest::{Method, Request, Response};
+    use serde_json::Value;
+
+    use super::HttpExecutor;
+
+    /// A request as seen by `MockExecutor`
+    #[derive(Debug, Clone)]
+    pub(crate) struct RecordedRequest {
+        pub method: Method,
+        pub url: String,
+        pub headers: HeaderMap,
+        /// The body parsed as JSON, if there was one
+        pub body: Option<Value>,
+    }
+
+    /// One queued answer of `MockExecutor`
+    struct StubResponse {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+    }
+
+    /// Records requests and answers them with queued stub responses, in order
+    #[derive(Default)]
+    pub(crate) struct MockExecutor {
+        responses: Mutex<VecDeque<StubResponse>>,
+        requests: Mutex<Vec<RecordedRequest>>,
+        latency: Mutex<Duration>,
+    }
+
+    impl MockExecutor {
+        pub fn new() -> Arc<Self> {
+            Arc::new(Self::default())
+        }
+
+        /// Queues the response for the next unanswered request
+        pub fn respond(&self, status: u16, body: &str) -> &Self {
+            self.respond_with_headers(status, &[], body)
+        }
+
+        /// Like `respond`, with extra response headers
+        pub fn respond_with_headers(
+            &self,
+            status: u16,
+            headers: &[(&str, &str)],
+            body: &str,
+        ) -> &Self {
+            self.responses.lock().unwrap().push_back(StubResponse {
+                status,
+                headers: headers
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+                body: body.to_string(),
+            });
+            self
+        }
+
+        /// Delays every response, so concurrent callers overlap
+        pub fn with_latency(&self, latency: Duration) -> &Self {
+            *self.latency.lock().unwrap() = latency;
+            self
+        }
+
+        /// Every request executed so far
+        pub fn requests(&self) -> Vec<RecordedRequest> {
+            self.requests.lock().unwrap().clone()
+        }
+    }
+
+    impl HttpExecutor for MockExecutor {
+        fn execute(&self, request: Request) -> BoxFuture<'_, Result<Response, reqwest::Error>> {
+            let body = request
+                .body()
+                .and_then(|b| b.as_bytes())
+                .and_then(|bytes| serde_json::from_slice(bytes).ok());
+            self.requests.lock().unwrap().push(RecordedRequest {
+                method: request.method().clone(),
+                url: request.url().to_string(),
+                headers: request.headers().clone(),
+                body,
+            });
+
+            let stub = self
+                .responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or_else(|| panic!("no stubbed response for {}", request.url()));
+            let mut builder = http::Response::builder().status(stub.status);
+            for (name, value) in &stub.headers {
+                builder = builder.header(name, value);
+            }
+            let response = builder.body(stub.body).expect("invalid stub response");
+            let latency = *self.latency.lock().unwrap();
+            Box::pin(async move {
+                if !latency.is_zero() {
+                    tokio::time::sleep(latency).await;
+                }
+                Ok(Response::from(response))
+            })
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tokio_util::sync::CancellationToken;
+
+use super::client::NimbusClient;
+use super::error::NimbusError;
+use super::profiles::ProfileStore;
+use super::session::{Session, Target};
+
+/// Event emitted by the heartbeat when the server goes online or offline
+pub const CONNECTION_STATUS_EVENT: &str = "connection-status";
+
+/// Time between heartbeat probes unless `start_heartbeat` is given one
+pub const DEFAULT_HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Result of a connectivity probe, for the status indicator
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConnectionStatus {
+    /// The server answered at all (false on DNS/connect/TLS/timeout failure)
+    pub reachable: bool,
+    /// The server didn't reject the token with 401 or 403; a 5xx or 404 still
+    /// counts, since it says nothing about the credentials
+    pub authenticated: bool,
+    pub latency_ms: u64,
+}
+
+/// Checks the active profile is reachable and the stored token is accepted
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+///
+/// # Returns
+/// ConnectionStatus. Connection failures and rejected tokens are reported in
+/// the status rather than as errors.
+#[tauri::command]
+pub async fn check_connection(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+) -> Result<ConnectionStatus, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, None)?;
+
+    probe_connection(&client, &base_url, &token).await
+}
+
+/// Issues one authenticated GET and classifies the outcome
+///
+/// Deliberately bypasses the retry policy: a probe should report what a single
+/// request sees right now.
+pub(crate) async fn probe_connection(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+) -> Result<ConnectionStatus, NimbusError> {
+    // Prepare headers
+    let headers = client.headers(token)?;
+
+    let url = format!("{}/RESTApi/LocationGroup", base_url);
+    let started = Instant::now();
+    let http_request = client
+        .http()
+        .get(&url)
+        .headers(headers)
+        .query(&[("PageNumber", 1), ("PageSize", 1)]);
+    let result = client.send_once(http_request).await;
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    Ok(match result {
+        Ok(response) => ConnectionStatus {
+            reachable: true,
+            authenticated: !matches!(response.status().as_u16(), 401 | 403),
+            latency_ms,
+        },
+        Err(_) => ConnectionStatus {
+            reachable: false,
+            authenticated: false,
+            latency_ms,
+        },
+    })
+}
+
+/// The running heartbeat task, if any, held in managed state
+#[derive(Debug, Default)]
+pub struct Heartbeat {
+    running: Mutex<Option<CancellationToken>>,
+}
+
+impl Heartbeat {
+    /// Stops the running task, if any, and registers a token for the next one
+    fn restart(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Some(previous) = self
+            .running
+            .lock()
+            .expect("heartbeat lock poisoned")
+            .replace(token.clone())
+        {
+            previous.cancel();
+        }
+        token
+    }
+
+    fn stop(&self) {
+        if let Some(token) = self.running.lock().expect("heartbeat lock poisoned").take() {
+            token.cancel();
+        }
+    }
+}
+
+/// Starts probing the active profile in the background
+///
+/// # Arguments
+/// * `app` - App handle the task reads state from and emits on
+/// * `heartbeat` - Heartbeat state; a task that is already running is replaced
+/// * `interval_ms` - Time between probes (defaults to `DEFAULT_HEARTBEAT_INTERVAL`)
+///
+/// # Returns
+/// Nothing. `connection-status` is emitted with a ConnectionStatus after the
+/// first probe and whenever the server goes from reachable to unreachable or
+/// back. Probes are skipped while no app window is visible, and while there
+/// is no active profile or stored token.
+#[tauri::command]
+pub async fn start_heartbeat(
+    app: tauri::AppHandle,
+    heartbeat: tauri::State<'_, Heartbeat>,
+    interval_ms: Option<u64>,
+) -> Result<(), NimbusError> {
+    let interval = interval_ms.map_or(DEFAULT_HEARTBEAT_INTERVAL, Duration::from_millis);
+    if interval.is_zero() {
+        return Err(NimbusError::Validation(
+            "interval_ms must be at least 1".to_string(),
+        ));
+    }
+
+    let cancel = heartbeat.restart();
+    tauri::async_runtime::spawn(run_heartbeat(app, interval, cancel));
+    Ok(())
+}
+
+/// Stops the heartbeat started by `start_heartbeat`
+///
+/// # Arguments
+/// * `heartbeat` - Heartbeat state
+#[tauri::command]
+pub async fn stop_heartbeat(heartbeat: tauri::State<'_, Heartbeat>) -> Result<(), NimbusError> {
+    heartbeat.stop();
+    Ok(())
+}
+
+async fn run_heartbeat(app: tauri::AppHandle, interval: Duration, cancel: CancellationToken) {
+    use tauri::Manager;
+
+    let mut tracker = StatusTracker::default();
+    let mut ticks = tokio::time::interval(interval);
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => return,
+            _ = ticks.tick() => {}
+        }
+
+        // Nothing to show the result on; save the request
+        let visible = app
+            .windows()
+            .values()
+            .any(|window| window.is_visible().unwrap_or(true));
+        if !visible {
+            continue;
+        }
+
+        let client = app.state::<NimbusClient>();
+        let profiles = app.state::<ProfileStore>();
+        let Ok(Target {
+            base_url, token, ..
+        }) = app.state::<Session>().target(&profiles, None)
+        else {
+            continue;
+        };
+        let Ok(status) = probe_connection(&client, &base_url, &token).await else {
+            continue;
+        };
+
+        if tracker.changed(&status) {
+            tracing::info!(
+                reachable = status.reachable,
+                latency_ms = status.latency_ms,
+                "Connection status changed"
+            );
+            if let Err(e) = app.emit_all(CONNECTION_STATUS_EVENT, status) {
+                tracing::warn!(error = ?e, "Failed to emit connection-status");
+            }
+        }
+    }
+}
+
+/// Remembers the last reachability so only transitions are reported
+#[derive(Debug, Default)]
+struct StatusTracker {
+    last_reachable: Option<bool>,
+}
+
+impl StatusTracker {
+    /// True for the first status and whenever reachability flips
+    fn changed(&mut self, status: &ConnectionStatus) -> bool {
+        let changed = self.last_reachable != Some(status.reachable);
+        self.last_reachable = Some(status.reachable);
+        changed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+
+    #[tokio::test]
+    async fn test_probe_only_treats_401_and_403_as_unauthenticated() {
+        let mock = MockExecutor::new();
+        mock.respond(200, r#"{"Items": []}"#)
+            .respond(401, "")
+            .respond(403, "")
+            .respond(503, "busy");
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        for authenticated in [true, false, false, true] {
+            let status = probe_connection(&client, "https://nimbus.test", "tok")
+                .await
+                .unwrap();
+            assert!(status.reachable);
+            assert_eq!(status.authenticated, authenticated);
+        }
+        // Probes are sent once, never retried
+        assert_eq!(mock.requests().len(), 4);
+    }
+
+    #[test]
+    fn test_status_tracker_reports_transitions_only() {
+        let status = |reachable| ConnectionStatus {
+            reachable,
+            authenticated: reachable,
+            latency_ms: 12,
+        };
+        let mut tracker = StatusTracker::default();
+
+        assert!(tracker.changed(&status(true)));
+        assert!(!tracker.changed(&status(true)));
+        assert!(tracker.changed(&status(false)));
+        assert!(!tracker.changed(&status(false)));
+        assert!(tracker.changed(&status(true)));
+    }
+}
@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use futures::channel::oneshot;
+use futures::future::{FutureExt, Shared};
+use tokio::sync::Notify;
+
+use super::request::SuccessBody;
+
+/// Outcome a caller waits on: the leader's success body, or `Canceled` if it failed
+type SharedBody = Shared<oneshot::Receiver<SuccessBody>>;
+
+/// GETs currently on the wire, so identical concurrent reads share one call
+///
+/// Keyed by method, URL and token. The first caller for a key becomes the
+/// leader and sends the request; later callers await its result until it
+/// finishes. Only successes are shared: when the leader fails (or is dropped),
+/// each waiting caller sends its own request, since `NimbusError` cannot be
+/// cloned and a retry may well succeed.
+#[derive(Debug, Default)]
+pub struct InFlight {
+    calls: Mutex<HashMap<String, SharedBody>>,
+}
+
+/// How a caller takes part in a coalesced read
+pub(crate) enum InFlightRole<'a> {
+    /// Send the request, then hand the result to `finish`
+    Leader(InFlightCall<'a>),
+    /// Await the leader's body
+    Follower(SharedBody),
+}
+
+impl InFlight {
+    pub(crate) fn join(&self, key: String) -> InFlightRole<'_> {
+        let mut calls = self.calls.lock().expect("in-flight lock poisoned");
+        if let Some(shared) = calls.get(&key) {
+            return InFlightRole::Follower(shared.clone());
+        }
+
+        let (sender, receiver) = oneshot::channel();
+        calls.insert(key.clone(), receiver.shared());
+        InFlightRole::Leader(InFlightCall {
+            in_flight: self,
+            key,
+            sender: Some(sender),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.calls.lock().expect("in-flight lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn remove(&self, key: &str) {
+        self.calls
+            .lock()
+            .expect("in-flight lock poisoned")
+            .remove(key);
+    }
+}
+
+/// The leader's slot; dropping it unfinished releases the followers
+pub(crate) struct InFlightCall<'a> {
+    in_flight: &'a InFlight,
+    key: String,
+    sender: Option<oneshot::Sender<SuccessBody>>,
+}
+
+impl InFlightCall<'_> {
+    /// Shares `body` with the followers; callers arriving later start afresh
+    pub(crate) fn finish(mut self, body: &SuccessBody) {
+        if let Some(sender) = self.sender.take() {
+            self.in_flight.remove(&self.key);
+            let _ = sender.send(body.clone());
+        }
+    }
+}
+
+impl Drop for InFlightCall<'_> {
+    fn drop(&mut self) {
+        // Still holding the sender means no body was shared: followers get `Canceled`
+        if self.sender.take().is_some() {
+            self.in_flight.remove(&self.key);
+        }
+    }
+}
+
+/// Count of `NimbusRequest`s between being sent and having their body read
+///
+/// Lets shutdown wait for requests that are still on the wire.
+#[derive(Debug, Default)]
+pub struct ActiveRequests {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl ActiveRequests {
+    /// Counts a request as active until the returned guard is dropped
+    pub(crate) fn enter(&self) -> ActiveRequest<'_> {
+        self.count.fetch_add(1, Ordering::SeqCst);
+        ActiveRequest { active: self }
+    }
+
+    pub fn len(&self) -> usize {
+        self.count.load(Ordering::SeqCst)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Waits up to `grace` for every active request to finish
+    ///
+    /// Returns false if some were still running when `grace` ran out.
+    pub async fn wait_idle(&self, grace: Duration) -> bool {
+        let deadline = tokio::time::Instant::now() + grace;
+        loop {
+            // Registered before the check, so a request finishing in between still wakes us
+            let idle = self.idle.notified();
+            if self.is_empty() {
+                return true;
+            }
+            if tokio::time::timeout_at(deadline, idle).await.is_err() {
+                return self.is_empty();
+            }
+        }
+    }
+}
+
+/// One active request; dropping it wakes `wait_idle` once none are left
+pub(crate) struct ActiveRequest<'a> {
+    active: &'a ActiveRequests,
+}
+
+impl Drop for ActiveRequest<'_> {
+    fn drop(&mut self) {
+        if self.active.count.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.active.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn body(text: &str) -> SuccessBody {
+        SuccessBody {
+            status: 200,
+            content_type: None,
+            location: None,
+            headers: Default::default(),
+            text: text.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_followers_share_success_and_resend_after_failure() {
+        let in_flight = InFlight::default();
+
+        let InFlightRole::Leader(leader) = in_flight.join("GET /a".to_string()) else {
+            panic!("first caller should lead");
+        };
+        let InFlightRole::Follower(follower) = in_flight.join("GET /a".to_string()) else {
+            panic!("second caller should follow");
+        };
+        leader.finish(&body("A"));
+        assert_eq!(follower.await.unwrap().text, "A");
+        assert!(in_flight.is_empty());
+
+        // A leader dropped without finishing leaves its followers to resend
+        let InFlightRole::Leader(leader) = in_flight.join("GET /a".to_string()) else {
+            panic!("a finished call should not be joined");
+        };
+        let InFlightRole::Follower(follower) = in_flight.join("GET /a".to_string()) else {
+            panic!("second caller should follow");
+        };
+        drop(leader);
+        assert!(follower.await.is_err());
+        assert!(in_flight.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_returns_once_requests_finish() {
+        let active = ActiveRequests::default();
+        assert!(active.wait_idle(Duration::ZERO).await);
+
+        let first = active.enter();
+        let second = active.enter();
+        assert!(!active.wait_idle(Duration::from_millis(10)).await);
+
+        let release = async {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(first);
+            drop(second);
+        };
+        let (idle, ()) = tokio::join!(active.wait_idle(Duration::from_secs(5)), release);
+        assert!(idle);
+        assert!(active.is_empty());
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+use std::sync::Mutex;
+
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+use super::error::NimbusError;
+
+/// File name prefix for the rotated log files
+const LOG_FILE_PREFIX: &str = "nimbus.log";
+
+/// Installs the global tracing subscriber
+///
+/// Logs go to stdout and to a daily-rotated file in `log_dir`, which users can
+/// attach to bug reports. Verbosity follows `RUST_LOG`, defaulting to `info`.
+/// The returned guard keeps the file writer running, so it must be kept alive
+/// for the lifetime of the app (e.g. in managed state) and flushed on exit.
+pub fn init_logging(log_dir: &Path) -> Result<LogGuard, NimbusError> {
+    std::fs::create_dir_all(log_dir).map_err(|e| {
+        NimbusError::Config(format!("Failed to create {}: {}", log_dir.display(), e))
+    })?;
+
+    let file_appender = tracing_appender::rolling::daily(log_dir, LOG_FILE_PREFIX);
+    let (file_writer, guard) = tracing_appender::non_blocking(file_appender);
+
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(fmt::layer().with_ansi(false).with_writer(file_writer))
+        .try_init()
+        .map_err(|e| NimbusError::Config(format!("Failed to install log subscriber: {}", e)))?;
+
+    Ok(LogGuard(Mutex::new(Some(guard))))
+}
+
+/// Owns the background log file writer started by `init_logging`
+///
+/// Managed state is never dropped when a Tauri app exits, so lines still
+/// buffered by the writer would be lost without an explicit `flush`.
+pub struct LogGuard(Mutex<Option<WorkerGuard>>);
+
+impl LogGuard {
+    /// Writes out buffered lines and stops the file writer
+    ///
+    /// Only stdout receives log lines after this, so call it last.
+    pub fn flush(&self) {
+        drop(self.0.lock().expect("log guard lock poisoned").take());
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use reqwest::Method;
+use serde::Serialize;
+
+use super::client::NimbusClient;
+use super::error::NimbusError;
+
+/// Running totals for one endpoint
+#[derive(Debug, Default, Clone)]
+struct EndpointStats {
+    calls: u64,
+    successes: u64,
+    failures: u64,
+    total: Duration,
+    min: Duration,
+    max: Duration,
+}
+
+/// Serializable view of one endpoint's stats
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EndpointMetrics {
+    pub calls: u64,
+    pub successes: u64,
+    /// Transport errors plus non-success statuses
+    pub failures: u64,
+    pub min_ms: u64,
+    pub avg_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Snapshot of every endpoint called this session, keyed like `GET /RESTApi/LocationGroup/{id}`
+#[derive(Debug, Serialize)]
+pub struct MetricsSnapshot {
+    pub endpoints: BTreeMap<String, EndpointMetrics>,
+}
+
+impl MetricsSnapshot {
+    /// Writes the snapshot to `path` as pretty JSON, replacing the last session's
+    pub fn save(&self, path: &Path) -> Result<(), NimbusError> {
+        let write_err =
+            |e: String| NimbusError::Config(format!("Failed to write {}: {}", path.display(), e));
+
+        let contents = serde_json::to_string_pretty(self).map_err(|e| write_err(e.to_string()))?;
+        std::fs::write(path, contents).map_err(|e| write_err(e.to_string()))
+    }
+}
+
+/// Per-endpoint call counts and latency, recorded by `NimbusClient::send`
+///
+/// Latency covers the whole send including retries, i.e. what the command waited.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    endpoints: Mutex<HashMap<String, EndpointStats>>,
+}
+
+impl Metrics {
+    /// Records one completed call
+    pub fn record(&self, endpoint: &str, success: bool, latency: Duration) {
+        let mut endpoints = self.endpoints.lock().expect("metrics lock poisoned");
+        let stats = endpoints.entry(endpoint.to_string()).or_default();
+
+        stats.min = if stats.calls == 0 {
+            latency
+        } else {
+            stats.min.min(latency)
+        };
+        stats.max = stats.max.max(latency);
+        stats.total += latency;
+        stats.calls += 1;
+        if success {
+            stats.successes += 1;
+        } else {
+            stats.failures += 1;
+        }
+    }
+
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        let endpoints = self.endpoints.lock().expect("metrics lock poisoned");
+        MetricsSnapshot {
+            endpoints: endpoints
+                .iter()
+                .map(|(endpoint, stats)| {
+                    let avg = stats.total / stats.calls.max(1) as u32;
+                    let metrics = EndpointMetrics {
+                        calls: stats.calls,
+                        successes: stats.successes,
+                        failures: stats.failures,
+                        min_ms: stats.min.as_millis() as u64,
+                        avg_ms: avg.as_millis() as u64,
+                        max_ms: stats.max.as_millis() as u64,
+                    };
+                    (endpoint.clone(), metrics)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Metrics key for a request, with numeric path segments collapsed to `{id}`
+///
+/// Keeps `GET /RESTApi/LocationGroup/1` and `/2` in the same bucket.
+pub(crate) fn endpoint_key(method: &Method, path: &str) -> String {
+    let path = path
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.bytes().all(|b| b.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    format!("{} {}", method, path)
+}
+
+/// Returns call counts and latency for every Nimbus endpoint used this session
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+#[tauri::command]
+pub async fn get_metrics(
+    client: tauri::State<'_, NimbusClient>,
+) -> Result<MetricsSnapshot, NimbusError> {
+    Ok(client.metrics().snapshot())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_endpoint_key_collapses_ids() {
+        assert_eq!(
+            endpoint_key(&Method::GET, "/RESTApi/LocationGroup/42"),
+            "GET /RESTApi/LocationGroup/{id}"
+        );
+        assert_eq!(
+            endpoint_key(&Method::POST, "/RESTApi/ScheduleGroup"),
+            "POST /RESTApi/ScheduleGroup"
+        );
+    }
+
+    #[test]
+    fn test_record_tracks_counts_and_latency() {
+        let metrics = Metrics::default();
+        metrics.record("GET /x", true, Duration::from_millis(30));
+        metrics.record("GET /x", false, Duration::from_millis(10));
+        metrics.record("GET /x", true, Duration::from_millis(50));
+
+        let snapshot = metrics.snapshot();
+        let path = std::env::temp_dir().join(format!("{}.json", uuid::Uuid::new_v4()));
+        snapshot.save(&path).unwrap();
+        let saved: serde_json::Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved["endpoints"]["GET /x"]["calls"], 3);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            snapshot.endpoints["GET /x"],
+            EndpointMetrics {
+                calls: 3,
+                successes: 2,
+                failures: 1,
+                min_ms: 10,
+                avg_ms: 30,
+                max_ms: 50,
+            }
+        );
+    }
+}
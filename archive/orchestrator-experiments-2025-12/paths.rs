@@ -0,0 +1,100 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::error::NimbusError;
+
+/// Profiles config file, in the app config dir
+pub const PROFILES_FILE_NAME: &str = "nimbus-profiles.json";
+
+/// Offline create queue, in the app data dir
+pub const QUEUE_FILE_NAME: &str = "nimbus-queue.json";
+
+/// Append-only mutation log, in the app data dir
+pub const AUDIT_FILE_NAME: &str = "nimbus-audit.jsonl";
+
+/// Metrics snapshot written on shutdown, in the app data dir
+pub const METRICS_FILE_NAME: &str = "nimbus-metrics.json";
+
+/// Where the app keeps its files on this machine
+#[derive(Debug, Clone, Serialize)]
+pub struct AppPaths {
+    pub config_file: PathBuf,
+    pub log_dir: PathBuf,
+    pub queue_file: PathBuf,
+    pub audit_file: PathBuf,
+    pub metrics_file: PathBuf,
+}
+
+impl AppPaths {
+    /// Resolves the paths from Tauri's per-app directories
+    pub fn resolve(resolver: &tauri::PathResolver) -> Result<Self, NimbusError> {
+        let missing = |dir: &str| NimbusError::Config(format!("No app {} directory", dir));
+
+        let data_dir = resolver.app_data_dir().ok_or_else(|| missing("data"))?;
+        Ok(Self {
+            config_file: resolver
+                .app_config_dir()
+                .ok_or_else(|| missing("config"))?
+                .join(PROFILES_FILE_NAME),
+            log_dir: resolver.app_log_dir().ok_or_else(|| missing("log"))?,
+            queue_file: data_dir.join(QUEUE_FILE_NAME),
+            audit_file: data_dir.join(AUDIT_FILE_NAME),
+            metrics_file: data_dir.join(METRICS_FILE_NAME),
+        })
+    }
+
+    /// Creates every directory the paths live in
+    pub fn create_dirs(&self) -> Result<(), NimbusError> {
+        let dirs = [
+            self.config_file.parent(),
+            Some(self.log_dir.as_path()),
+            self.queue_file.parent(),
+            self.audit_file.parent(),
+            self.metrics_file.parent(),
+        ];
+        for dir in dirs.into_iter().flatten() {
+            std::fs::create_dir_all(dir).map_err(|e| {
+                NimbusError::Config(format!("Failed to create {}: {}", dir.display(), e))
+            })?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns where the config file, logs, offline queue, audit log, and metrics live
+///
+/// # Arguments
+/// * `app` - Handle used to resolve the per-app directories
+///
+/// # Returns
+/// AppPaths, after creating any missing directories so "reveal in file
+/// manager" always has something to open
+#[tauri::command]
+pub async fn get_app_paths(app: tauri::AppHandle) -> Result<AppPaths, NimbusError> {
+    let paths = AppPaths::resolve(&app.path_resolver())?;
+    paths.create_dirs()?;
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_dirs() {
+        let root = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        let paths = AppPaths {
+            config_file: root.join("config").join(PROFILES_FILE_NAME),
+            log_dir: root.join("logs"),
+            queue_file: root.join("data").join(QUEUE_FILE_NAME),
+            audit_file: root.join("data").join(AUDIT_FILE_NAME),
+            metrics_file: root.join("data").join(METRICS_FILE_NAME),
+        };
+
+        paths.create_dirs().unwrap();
+        assert!(root.join("config").is_dir());
+        assert!(root.join("logs").is_dir());
+        assert!(root.join("data").is_dir());
+    }
+}
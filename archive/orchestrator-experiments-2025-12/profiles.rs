@@ -0,0 +1,275 @@
+use std::path::Path;
+use std::sync::RwLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::demo::{DEMO_BASE_URL, DEMO_PROFILE};
+use super::error::NimbusError;
+use super::session::Session;
+
+/// A named Nimbus environment (dev, staging, prod, ...)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NimbusProfile {
+    pub name: String,
+    pub base_url: String,
+}
+
+/// On-disk shape of the profiles config file
+///
+/// ```json
+/// {
+///     "profiles": [{ "name": "dev", "base_url": "https://dev.nimbus.example.com" }],
+///     "default": "dev"
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+struct ProfilesFile {
+    #[serde(default)]
+    profiles: Vec<NimbusProfile>,
+    default: Option<String>,
+}
+
+/// Profiles returned to the UI for the environment dropdown
+#[derive(Debug, Serialize)]
+pub struct ProfileList {
+    pub profiles: Vec<NimbusProfile>,
+    pub active: Option<String>,
+    /// Profiles with a stored token, which can be switched to without logging in
+    pub signed_in: Vec<String>,
+}
+
+/// Environment profiles held in Tauri managed state
+///
+/// Commands resolve their base URL from the active profile rather than
+/// taking it from the frontend on every call.
+pub struct ProfileStore {
+    profiles: Vec<NimbusProfile>,
+    active: RwLock<Option<String>>,
+}
+
+impl ProfileStore {
+    /// Loads profiles from the config file at `path`
+    ///
+    /// A missing file yields an empty store, so the app still starts and the
+    /// UI can prompt for setup. A file that exists but can't be parsed is an error.
+    pub fn load(path: &Path) -> Result<Self, NimbusError> {
+        match std::fs::read_to_string(path) {
+            Ok(contents) => Self::from_json(&contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Ok(Self::from_file(ProfilesFile::default()))
+            }
+            Err(e) => Err(NimbusError::Config(format!(
+                "Failed to read {}: {}",
+                path.display(),
+                e
+            ))),
+        }
+    }
+
+    pub(crate) fn from_json(contents: &str) -> Result<Self, NimbusError> {
+        let file: ProfilesFile = serde_json::from_str(contents)
+            .map_err(|e| NimbusError::Config(format!("Invalid profiles file: {}", e)))?;
+        Ok(Self::from_file(file))
+    }
+
+    fn from_file(file: ProfilesFile) -> Self {
+        // Fall back to the first profile when no valid default is named
+        let active = file
+            .default
+            .filter(|name| file.profiles.iter().any(|p| &p.name == name))
+            .or_else(|| file.profiles.first().map(|p| p.name.clone()));
+
+        Self {
+            profiles: file.profiles,
+            active: RwLock::new(active),
+        }
+    }
+
+    /// The single profile used with `NimbusConfig::demo_mode`, pointing at the demo store
+    pub fn demo() -> Self {
+        Self::from_file(ProfilesFile {
+            profiles: vec![NimbusProfile {
+                name: DEMO_PROFILE.to_string(),
+                base_url: DEMO_BASE_URL.to_string(),
+            }],
+            default: None,
+        })
+    }
+
+    /// Base URL of the active profile, normalized by `normalize_base_url`
+    pub fn active_base_url(&self) -> Result<String, NimbusError> {
+        self.base_url(&self.resolve_name(None)?)
+    }
+
+    /// Base URL of the profile called `name`, normalized by `normalize_base_url`
+    pub fn base_url(&self, name: &str) -> Result<String, NimbusError> {
+        let profile = self
+            .profiles
+            .iter()
+            .find(|p| p.name == name)
+            .ok_or_else(|| unknown_profile(name))?;
+        normalize_base_url(&profile.base_url)
+    }
+
+    /// `name` if it is a configured profile, or the active profile when None
+    pub fn resolve_name(&self, name: Option<&str>) -> Result<String, NimbusError> {
+        match name {
+            Some(name) if self.profiles.iter().any(|p| p.name == name) => Ok(name.to_string()),
+            Some(name) => Err(unknown_profile(name)),
+            None => self.active_name().ok_or(NimbusError::NoActiveProfile),
+        }
+    }
+
+    /// Names of all configured profiles
+    pub fn names(&self) -> Vec<String> {
+        self.profiles.iter().map(|p| p.name.clone()).collect()
+    }
+
+    /// Name of the active profile, recorded as the actor in the audit log
+    pub fn active_name(&self) -> Option<String> {
+        self.active.read().expect("profile lock poisoned").clone()
+    }
+
+    pub(crate) fn set_active(&self, name: &str) -> Result<(), NimbusError> {
+        let name = self.resolve_name(Some(name))?;
+        *self.active.write().expect("profile lock poisoned") = Some(name);
+        Ok(())
+    }
+
+    fn list(&self) -> ProfileList {
+        ProfileList {
+            profiles: self.profiles.clone(),
+            active: self.active.read().expect("profile lock poisoned").clone(),
+            signed_in: Vec::new(),
+        }
+    }
+}
+
+fn unknown_profile(name: &str) -> NimbusError {
+    NimbusError::Validation(format!("Unknown profile '{}'", name))
+}
+
+/// Checks a base URL is an absolute http(s) URL and strips trailing slashes
+///
+/// Endpoint paths are appended as `{base_url}/RESTApi/...`, so a trailing slash
+/// would yield `//RESTApi`, which some gateways 404 on.
+pub fn normalize_base_url(base_url: &str) -> Result<String, NimbusError> {
+    let trimmed = base_url.trim().trim_end_matches('/');
+    let invalid = |reason: &str| {
+        NimbusError::Validation(format!("Invalid base URL '{}': {}", base_url, reason))
+    };
+
+    let parsed = url::Url::parse(trimmed).map_err(|e| invalid(&e.to_string()))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(invalid("scheme must be http or https"));
+    }
+    if !parsed.has_host() {
+        return Err(invalid("missing host"));
+    }
+
+    Ok(trimmed.to_string())
+}
+
+/// Lists the configured Nimbus profiles, which one is active, and which are logged in
+///
+/// # Arguments
+/// * `profiles` - Profile state loaded at startup
+/// * `session` - Session state holding each profile's stored token
+#[tauri::command]
+pub async fn list_profiles(
+    profiles: tauri::State<'_, ProfileStore>,
+    session: tauri::State<'_, Session>,
+) -> Result<ProfileList, NimbusError> {
+    Ok(ProfileList {
+        signed_in: session.signed_in(),
+        ..profiles.list()
+    })
+}
+
+/// Switches the profile subsequent commands run against
+///
+/// Each profile keeps its own login, so switching back to one that is
+/// already logged in needs no `login`.
+///
+/// # Arguments
+/// * `profiles` - Profile state loaded at startup
+/// * `name` - Name of a configured profile
+#[tauri::command]
+pub async fn set_active_profile(
+    profiles: tauri::State<'_, ProfileStore>,
+    name: String,
+) -> Result<(), NimbusError> {
+    profiles.set_active(&name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILES_JSON: &str = r#"{
+        "profiles": [
+            {"name": "dev", "base_url": "https://dev.nimbus.example.com"},
+            {"name": "prod", "base_url": "https://nimbus.example.com"}
+        ],
+        "default": "prod"
+    }"#;
+
+    #[test]
+    fn test_default_profile_is_active() {
+        let store = ProfileStore::from_json(PROFILES_JSON).unwrap();
+        assert_eq!(
+            store.active_base_url().unwrap(),
+            "https://nimbus.example.com"
+        );
+    }
+
+    #[test]
+    fn test_set_active_profile() {
+        let store = ProfileStore::from_json(PROFILES_JSON).unwrap();
+        store.set_active("dev").unwrap();
+        assert_eq!(
+            store.active_base_url().unwrap(),
+            "https://dev.nimbus.example.com"
+        );
+        assert!(store.set_active("staging").is_err());
+    }
+
+    #[test]
+    fn test_resolve_name_prefers_explicit_profile() {
+        let store = ProfileStore::from_json(PROFILES_JSON).unwrap();
+        assert_eq!(store.resolve_name(None).unwrap(), "prod");
+        assert_eq!(store.resolve_name(Some("dev")).unwrap(), "dev");
+        assert!(matches!(
+            store.resolve_name(Some("staging")),
+            Err(NimbusError::Validation(_))
+        ));
+        assert_eq!(
+            store.base_url("dev").unwrap(),
+            "https://dev.nimbus.example.com"
+        );
+    }
+
+    #[test]
+    fn test_missing_file_yields_empty_store() {
+        let store = ProfileStore::load(Path::new("/nonexistent/nimbus-profiles.json")).unwrap();
+        assert!(store.list().profiles.is_empty());
+        assert!(matches!(
+            store.active_base_url(),
+            Err(NimbusError::NoActiveProfile)
+        ));
+    }
+
+    #[test]
+    fn test_normalize_base_url() {
+        assert_eq!(
+            normalize_base_url("https://nimbus.example.com").unwrap(),
+            "https://nimbus.example.com"
+        );
+        assert_eq!(
+            normalize_base_url("https://nimbus.example.com/tenant//").unwrap(),
+            "https://nimbus.example.com/tenant"
+        );
+        assert!(normalize_base_url("nimbus.example.com").is_err());
+        assert!(normalize_base_url("ftp://nimbus.example.com").is_err());
+    }
+}
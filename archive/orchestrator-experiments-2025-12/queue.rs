@@ -0,0 +1,385 @@
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::audit::{AuditLog, AuditOperation};
+use super::client::NimbusClient;
+use super::entities::{
+    post_location_group, post_schedule_group, LocationGroupRequest, ScheduleGroupRequest,
+};
+use super::error::NimbusError;
+use super::profiles::ProfileStore;
+use super::session::{Session, Target};
+
+/// A create that failed with a network error, waiting to be replayed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedCreate {
+    /// Sent again on replay so Nimbus drops the create if it did arrive after all
+    pub idempotency_key: String,
+    /// Profile the create was sent under; replays use its host and token even
+    /// if the active profile changed. Empty for entries queued by older versions.
+    #[serde(default)]
+    pub profile: String,
+    /// Environment the create was aimed at, which finds the profile of an older entry
+    pub base_url: String,
+    pub queued_at: DateTime<Utc>,
+    pub request: QueuedRequest,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "entity", content = "request")]
+pub enum QueuedRequest {
+    LocationGroup(LocationGroupRequest),
+    ScheduleGroup(ScheduleGroupRequest),
+}
+
+/// Creates that reached Nimbus on replay, and those that were rejected
+#[derive(Debug, Default, Serialize)]
+pub struct FlushSummary {
+    /// Idempotency keys of the replayed creates, now removed from the queue
+    pub replayed: Vec<String>,
+    /// Creates Nimbus rejected; they stay queued
+    pub failed: Vec<FlushFailure>,
+    /// Entries still queued after the flush
+    pub remaining: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FlushFailure {
+    pub idempotency_key: String,
+    pub error: NimbusError,
+}
+
+/// Pending creates, persisted as JSON so they survive a restart
+///
+/// Held in Tauri managed state. Every change is written straight back to disk.
+pub struct OfflineQueue {
+    path: PathBuf,
+    items: Mutex<Vec<QueuedCreate>>,
+}
+
+impl OfflineQueue {
+    /// Loads the queue file at `path`; a missing file is an empty queue
+    pub fn load(path: &Path) -> Result<Self, NimbusError> {
+        let items = match std::fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .map_err(|e| NimbusError::Config(format!("Invalid queue file: {}", e)))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(NimbusError::Config(format!(
+                    "Failed to read {}: {}",
+                    path.display(),
+                    e
+                )))
+            }
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            items: Mutex::new(items),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.lock().expect("queue lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends a create and persists the queue
+    pub fn push(
+        &self,
+        profile: &str,
+        base_url: &str,
+        idempotency_key: &str,
+        request: QueuedRequest,
+    ) -> Result<(), NimbusError> {
+        let mut items = self.items.lock().expect("queue lock poisoned");
+        items.push(QueuedCreate {
+            idempotency_key: idempotency_key.to_string(),
+            profile: profile.to_string(),
+            base_url: base_url.to_string(),
+            queued_at: Utc::now(),
+            request,
+        });
+        self.save(&items)
+    }
+
+    /// Writes the queue to disk again, as on shutdown
+    pub fn flush(&self) -> Result<(), NimbusError> {
+        let items = self.items.lock().expect("queue lock poisoned");
+        self.save(&items)
+    }
+
+    fn snapshot(&self) -> Vec<QueuedCreate> {
+        self.items.lock().expect("queue lock poisoned").clone()
+    }
+
+    /// Drops the entries with the given idempotency keys and persists the queue
+    fn remove(&self, idempotency_keys: &[String]) -> Result<usize, NimbusError> {
+        let mut items = self.items.lock().expect("queue lock poisoned");
+        items.retain(|item| !idempotency_keys.contains(&item.idempotency_key));
+        self.save(&items)?;
+        Ok(items.len())
+    }
+
+    fn save(&self, items: &[QueuedCreate]) -> Result<(), NimbusError> {
+        let write_err = |e: String| {
+            NimbusError::Config(format!("Failed to write {}: {}", self.path.display(), e))
+        };
+
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| write_err(e.to_string()))?;
+        }
+        let contents = serde_json::to_string_pretty(items).map_err(|e| write_err(e.to_string()))?;
+        std::fs::write(&self.path, contents).map_err(|e| write_err(e.to_string()))
+    }
+}
+
+/// Replays queued creates against Nimbus
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding each profile's stored token
+/// * `profiles` - Profile state each entry's host is resolved from
+/// * `queue` - Offline queue loaded at startup
+/// * `audit` - Audit log each replayed create is recorded in
+///
+/// # Returns
+/// FlushSummary of the replay. Each entry is sent to the profile it was queued
+/// under, with that profile's token; one whose profile is gone or logged out
+/// fails and stays queued. Stops early, keeping the rest queued, if the
+/// server is still unreachable.
+#[tauri::command]
+pub async fn flush_queue(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    queue: tauri::State<'_, OfflineQueue>,
+    audit: tauri::State<'_, AuditLog>,
+) -> Result<FlushSummary, NimbusError> {
+    replay_queue(
+        &client,
+        &session,
+        &profiles,
+        &queue,
+        |entity, profile, result| {
+            audit.record(
+                AuditOperation::Create,
+                entity,
+                result.as_ref().ok().copied(),
+                Some(profile.to_string()),
+                result.as_ref().err(),
+            )
+        },
+    )
+    .await
+}
+
+/// Number of creates waiting in the offline queue
+///
+/// # Arguments
+/// * `queue` - Offline queue loaded at startup
+#[tauri::command]
+pub async fn queue_len(queue: tauri::State<'_, OfflineQueue>) -> Result<usize, NimbusError> {
+    Ok(queue.len())
+}
+
+/// Replays every queued create, calling `on_sent` with each one that reached Nimbus
+/// and the profile it was sent under
+pub(crate) async fn replay_queue(
+    client: &NimbusClient,
+    session: &Session,
+    profiles: &ProfileStore,
+    queue: &OfflineQueue,
+    on_sent: impl Fn(&str, &str, &Result<i64, NimbusError>),
+) -> Result<FlushSummary, NimbusError> {
+    let mut summary = FlushSummary::default();
+
+    for item in queue.snapshot() {
+        let key = &item.idempotency_key;
+        let target = queued_profile(profiles, &item)
+            .and_then(|profile| session.target(profiles, Some(&profile)));
+        let Target {
+            profile,
+            base_url,
+            token,
+        } = match target {
+            Ok(target) => target,
+            Err(error) => {
+                summary.failed.push(FlushFailure {
+                    idempotency_key: key.clone(),
+                    error,
+                });
+                continue;
+            }
+        };
+
+        let (entity, result) = match &item.request {
+            QueuedRequest::LocationGroup(request) => (
+                "LocationGroup",
+                post_location_group(client, &base_url, &token, request, key).await,
+            ),
+            QueuedRequest::ScheduleGroup(request) => (
+                "ScheduleGroup",
+                post_schedule_group(client, &base_url, &token, request, key).await,
+            ),
+        };
+        let offline = matches!(
+            result,
+            Err(NimbusError::Network(_) | NimbusError::CircuitOpen { .. })
+        );
+        if !offline {
+            on_sent(entity, &profile, &result);
+        }
+
+        match result {
+            // Still offline - no point trying the rest
+            _ if offline => break,
+            Ok(_) => summary.replayed.push(key.clone()),
+            Err(error) => summary.failed.push(FlushFailure {
+                idempotency_key: key.clone(),
+                error,
+            }),
+        }
+    }
+
+    summary.remaining = queue.remove(&summary.replayed)?;
+    Ok(summary)
+}
+
+/// The profile `item` was queued under, found by host for entries without one
+fn queued_profile(profiles: &ProfileStore, item: &QueuedCreate) -> Result<String, NimbusError> {
+    if !item.profile.is_empty() {
+        return Ok(item.profile.clone());
+    }
+    profiles
+        .names()
+        .into_iter()
+        .find(|name| profiles.base_url(name).ok().as_deref() == Some(item.base_url.as_str()))
+        .ok_or_else(|| {
+            NimbusError::Validation(format!(
+                "No profile points at {}, where the queued create was sent",
+                item.base_url
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+
+    fn temp_queue_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(uuid::Uuid::new_v4().to_string())
+            .join("nimbus-queue.json")
+    }
+
+    const PROFILES_JSON: &str = r#"{
+        "profiles": [
+            {"name": "tenant-a", "base_url": "https://a.nimbus.test"},
+            {"name": "tenant-b", "base_url": "https://b.nimbus.test"}
+        ],
+        "default": "tenant-a"
+    }"#;
+
+    fn location_request() -> QueuedRequest {
+        QueuedRequest::LocationGroup(LocationGroupRequest {
+            description: "West Coast".to_string(),
+            location_ids: vec![101],
+            active: true,
+        })
+    }
+
+    #[test]
+    fn test_queue_persists_across_loads() {
+        let path = temp_queue_path();
+        let queue = OfflineQueue::load(&path).unwrap();
+        assert!(queue.is_empty());
+
+        queue
+            .push(
+                "tenant-a",
+                "https://a.nimbus.test",
+                "key-1",
+                location_request(),
+            )
+            .unwrap();
+        assert_eq!(OfflineQueue::load(&path).unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_replay_removes_successful_creates() {
+        let queue = OfflineQueue::load(&temp_queue_path()).unwrap();
+        queue
+            .push(
+                "tenant-a",
+                "https://a.nimbus.test",
+                "key-1",
+                location_request(),
+            )
+            .unwrap();
+        queue
+            .push(
+                "tenant-a",
+                "https://a.nimbus.test",
+                "key-2",
+                location_request(),
+            )
+            .unwrap();
+
+        let mock = MockExecutor::new();
+        mock.respond(201, r#"{"LocationGroupID": 1}"#)
+            .respond(400, r#"{"Message": "Bad location"}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+        let profiles = ProfileStore::from_json(PROFILES_JSON).unwrap();
+        let session = Session::in_memory(&[("tenant-a", "tok")]);
+
+        let summary = replay_queue(&client, &session, &profiles, &queue, |_, _, _| {})
+            .await
+            .unwrap();
+        assert_eq!(summary.replayed, vec!["key-1".to_string()]);
+        assert_eq!(summary.failed[0].idempotency_key, "key-2");
+        assert_eq!(summary.remaining, 1);
+        assert_eq!(mock.requests()[0].headers["Idempotency-Key"], "key-1");
+    }
+
+    #[tokio::test]
+    async fn test_replay_uses_the_profile_the_create_was_queued_under() {
+        let queue = OfflineQueue::load(&temp_queue_path()).unwrap();
+        queue
+            .push(
+                "tenant-a",
+                "https://a.nimbus.test",
+                "key-1",
+                location_request(),
+            )
+            .unwrap();
+        let profiles = ProfileStore::from_json(PROFILES_JSON).unwrap();
+        profiles.set_active("tenant-b").unwrap();
+        let session = Session::in_memory(&[("tenant-a", "tok-a"), ("tenant-b", "tok-b")]);
+
+        let mock = MockExecutor::new();
+        mock.respond(201, r#"{"LocationGroupID": 1}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+        let actors = Mutex::new(Vec::new());
+
+        let summary = replay_queue(&client, &session, &profiles, &queue, |_, profile, _| {
+            actors.lock().unwrap().push(profile.to_string())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(summary.replayed, vec!["key-1".to_string()]);
+        let request = &mock.requests()[0];
+        assert!(request.url.starts_with("https://a.nimbus.test/"));
+        assert_eq!(request.headers["AuthenticationToken"], "tok-a");
+        assert_eq!(*actors.lock().unwrap(), vec!["tenant-a"]);
+    }
+}
@@ -0,0 +1,138 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Client-side cap on how fast requests are sent to Nimbus
+///
+/// Up to `burst` requests go out at once after a quiet spell; beyond that
+/// they are spaced `1 / requests_per_second` apart.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RateLimitConfig {
+    pub requests_per_second: f64,
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 10.0,
+            burst: 10,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct State {
+    /// Negative while callers are queued behind reserved tokens
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Token bucket every request takes a token from before it is sent
+///
+/// A caller that finds the bucket empty still takes its token, driving the
+/// count negative, and waits until it would have refilled. Concurrent callers
+/// therefore queue up in order instead of all waking at once. Every method
+/// takes `now` so tests can check the pacing without sleeping.
+#[derive(Debug)]
+pub struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    state: Mutex<State>,
+}
+
+impl TokenBucket {
+    /// Starts full, so the first `burst` requests are not delayed
+    ///
+    /// A rate that is not positive is treated as a very slow one rather than
+    /// dividing by zero; `burst` is at least 1.
+    pub fn new(config: &RateLimitConfig, now: Instant) -> Self {
+        let burst = f64::from(config.burst.max(1));
+        Self {
+            rate: config.requests_per_second.max(f64::MIN_POSITIVE),
+            burst,
+            state: Mutex::new(State {
+                tokens: burst,
+                updated: now,
+            }),
+        }
+    }
+
+    /// Refills the bucket and measures from `now`, for when the time source changes
+    pub fn reset(&self, now: Instant) {
+        let mut state = self.state.lock().expect("rate limit lock poisoned");
+        state.tokens = self.burst;
+        state.updated = now;
+    }
+
+    /// Takes a token and returns how long to wait before sending; zero when one was free
+    pub fn reserve(&self, now: Instant) -> Duration {
+        let mut state = self.state.lock().expect("rate limit lock poisoned");
+        let refilled = now.saturating_duration_since(state.updated).as_secs_f64() * self.rate;
+        state.tokens = (state.tokens + refilled).min(self.burst) - 1.0;
+        state.updated = now.max(state.updated);
+        if state.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-state.tokens / self.rate)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests_beyond_the_burst_are_paced_at_the_rate() {
+        let start = Instant::now();
+        let bucket = TokenBucket::new(
+            &RateLimitConfig {
+                requests_per_second: 5.0,
+                burst: 2,
+            },
+            start,
+        );
+
+        // Each request sends once its wait is over, like the client's sleep
+        let mut now = start;
+        for _ in 0..12 {
+            now += bucket.reserve(now);
+        }
+        // Two go out immediately, the other ten at 5 per second
+        let elapsed = now - start;
+        assert!(
+            (elapsed.as_secs_f64() - 2.0).abs() < 0.01,
+            "took {:?}",
+            elapsed
+        );
+
+        // A quiet spell refills the burst, but no further
+        let later = now + Duration::from_secs(60);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert!(bucket.reserve(later) > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_concurrent_callers_queue_behind_each_other() {
+        let start = Instant::now();
+        let bucket = TokenBucket::new(
+            &RateLimitConfig {
+                requests_per_second: 2.0,
+                burst: 1,
+            },
+            start,
+        );
+
+        let waits: Vec<_> = (0..4).map(|_| bucket.reserve(start)).collect();
+        assert_eq!(
+            waits,
+            vec![
+                Duration::ZERO,
+                Duration::from_millis(500),
+                Duration::from_secs(1),
+                Duration::from_millis(1500),
+            ]
+        );
+    }
+}
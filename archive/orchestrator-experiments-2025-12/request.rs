@@ -0,0 +1,736 @@
+use reqwest::header::{
+    HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, ETAG, IF_NONE_MATCH, LOCATION,
+};
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::client::{NimbusClient, CORRELATION_ID_HEADER};
+use super::error::NimbusError;
+use super::inflight::InFlightRole;
+
+/// One Nimbus REST call: URL, auth headers, send, status check, and parsing
+///
+/// Built with `NimbusClient::request`. Endpoints only describe what differs:
+///
+/// ```ignore
+/// let detail: Value = client
+///     .request(Method::GET, base_url, token, &format!("/RESTApi/LocationGroup/{}", id))
+///     .not_found("LocationGroup", id)
+///     .send_parsed()
+///     .await?;
+/// ```
+///
+/// Each request gets a fresh UUID, sent as `X-Correlation-Id` (the same on
+/// every retry), logged with the request, recorded on the current span's
+/// `correlation_id` field, and attached to the `Http` error if it fails.
+pub(crate) struct NimbusRequest<'a> {
+    client: &'a NimbusClient,
+    method: Method,
+    base_url: String,
+    url: String,
+    token: &'a str,
+    body: Option<Value>,
+    encoding: PayloadEncoding,
+    query: Vec<(String, String)>,
+    headers: Vec<(&'static str, String)>,
+    idempotent: bool,
+    not_found: Option<(&'static str, i64)>,
+    capture: bool,
+    cached: bool,
+    token_refresh: bool,
+    correlation_id: String,
+}
+
+/// A success response, read in full
+#[derive(Debug, Clone)]
+pub(crate) struct SuccessBody {
+    pub status: u16,
+    pub content_type: Option<String>,
+    /// `Location` header, which some servers use instead of echoing a created entity
+    pub location: Option<String>,
+    /// Every response header, e.g. for paging info sent outside the body
+    pub headers: HeaderMap,
+    pub text: String,
+}
+
+impl SuccessBody {
+    /// True if the server said the body is JSON (or said nothing)
+    pub fn is_json(&self) -> bool {
+        self.content_type
+            .as_deref()
+            .is_none_or(|ct| ct.to_ascii_lowercase().contains("json"))
+    }
+
+    /// Parses the body as `T`
+    ///
+    /// An empty body is `UnexpectedEmptyBody`; a non-JSON one is a `Parse`
+    /// error naming its content type, not serde's "expected value" message.
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T, NimbusError> {
+        if self.text.trim().is_empty() {
+            return Err(NimbusError::UnexpectedEmptyBody {
+                status: self.status,
+            });
+        }
+        serde_json::from_str(&self.text).map_err(|e| {
+            if self.is_json() {
+                NimbusError::Parse(e.to_string())
+            } else {
+                NimbusError::Parse(format!(
+                    "expected JSON but got {}",
+                    self.content_type.as_deref().unwrap_or_default()
+                ))
+            }
+        })
+    }
+}
+
+impl NimbusClient {
+    /// Starts a request to `path` (e.g. `/RESTApi/LocationGroup`) under `base_url`
+    ///
+    /// Every method except POST is treated as idempotent for retries.
+    pub(crate) fn request<'a>(
+        &'a self,
+        method: Method,
+        base_url: &str,
+        token: &'a str,
+        path: &str,
+    ) -> NimbusRequest<'a> {
+        NimbusRequest {
+            client: self,
+            idempotent: method != Method::POST,
+            method,
+            base_url: base_url.to_string(),
+            url: format!("{}{}", base_url, path),
+            token,
+            body: None,
+            encoding: PayloadEncoding::Json,
+            query: Vec::new(),
+            headers: Vec::new(),
+            not_found: None,
+            capture: false,
+            cached: false,
+            token_refresh: true,
+            correlation_id: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+}
+
+impl<'a> NimbusRequest<'a> {
+    pub(crate) fn json_body(mut self, body: Value) -> Self {
+        self.body = Some(body);
+        self
+    }
+
+    /// Sends the body form-encoded instead of as JSON, for `PayloadEncoding::Form`
+    pub(crate) fn payload_encoding(mut self, encoding: PayloadEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    pub(crate) fn query(mut self, name: &str, value: impl ToString) -> Self {
+        self.query.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds a header on top of the standard Nimbus ones
+    pub(crate) fn header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    /// Overrides whether the request may be retried after an ambiguous failure
+    pub(crate) fn idempotent(mut self, idempotent: bool) -> Self {
+        self.idempotent = idempotent;
+        self
+    }
+
+    /// Reports a 404 as `NimbusError::NotFound` for this entity
+    pub(crate) fn not_found(mut self, entity: &'static str, id: i64) -> Self {
+        self.not_found = Some((entity, id));
+        self
+    }
+
+    /// Writes the request and response to the debug capture dir, when enabled
+    pub(crate) fn captured(mut self) -> Self {
+        self.capture = true;
+        self
+    }
+
+    /// Revalidates against the client's ETag cache instead of re-downloading
+    ///
+    /// Only for GETs: a stored ETag is sent as `If-None-Match`, and a 304 is
+    /// answered with the body it was stored with.
+    pub(crate) fn cached(mut self) -> Self {
+        self.cached = self.method == Method::GET;
+        self
+    }
+
+    /// Whether a 401 may be answered by refreshing the token and retrying once
+    ///
+    /// On by default when the client has a `TokenRefresher`; off for calls
+    /// whose token is not the session's (login, the refresh itself).
+    pub(crate) fn token_refresh(mut self, enabled: bool) -> Self {
+        self.token_refresh = enabled;
+        self
+    }
+
+    /// Cache key: the URL including its query
+    fn cache_key(&self) -> String {
+        let query: Vec<_> = self
+            .query
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        if query.is_empty() {
+            self.url.clone()
+        } else {
+            format!("{}?{}", self.url, query.join("&"))
+        }
+    }
+
+    /// Sends the request and returns the raw body of a success response
+    pub(crate) async fn send_text(self) -> Result<String, NimbusError> {
+        Ok(self.send_raw().await?.text)
+    }
+
+    /// Sends the request and parses a success body as `T`
+    pub(crate) async fn send_parsed<T: DeserializeOwned>(self) -> Result<T, NimbusError> {
+        self.send_raw().await?.json()
+    }
+
+    /// Sends the request and returns a success response with its headers of interest
+    ///
+    /// A GET identical to one already on the wire waits for that one's
+    /// response instead of sending its own (see `InFlight`).
+    pub(crate) async fn send_raw(self) -> Result<SuccessBody, NimbusError> {
+        if self.method != Method::GET {
+            return self.send_now().await;
+        }
+
+        let key = format!("{} {} {}", self.method, self.cache_key(), self.token);
+        match self.client.in_flight().join(key) {
+            InFlightRole::Leader(call) => {
+                let result = self.send_now().await;
+                if let Ok(body) = &result {
+                    call.finish(body);
+                }
+                result
+            }
+            InFlightRole::Follower(shared) => match shared.await {
+                Ok(body) => {
+                    tracing::debug!(url = %self.url, "Shared an in-flight Nimbus response");
+                    Ok(body)
+                }
+                // The leader failed; this caller gets its own attempt
+                Err(_) => self.send_now().await,
+            },
+        }
+    }
+
+    /// Sends the request on its own, never coalesced
+    ///
+    /// The status and correlation ID are recorded on the current tracing
+    /// span's `status` and `correlation_id` fields, if it has them.
+    async fn send_now(self) -> Result<SuccessBody, NimbusError> {
+        let client = self.client;
+        let _active = client.active_requests().enter();
+        let correlation_id = self.correlation_id.as_str();
+        tracing::Span::current().record("correlation_id", correlation_id);
+        let cache_key = self.cached.then(|| self.cache_key());
+
+        // A 401 is retried once with a refreshed token, if the client can get one,
+        // and a 304 that can't be answered from the cache once without `If-None-Match`
+        let mut token = self.token.to_string();
+        let mut may_refresh = self.token_refresh;
+        let mut revalidate = true;
+        let (capture, response, cached) = loop {
+            // The body a 304 stands for, read together with the ETag that is sent
+            let cached = cache_key
+                .as_deref()
+                .filter(|_| revalidate)
+                .and_then(|key| client.etag_cache().get(key))
+                .and_then(|(etag, body)| Some((etag.parse::<HeaderValue>().ok()?, body)));
+            let etag = cached.as_ref().map(|(etag, _)| etag.clone());
+            let http_request = self.build(&token, etag)?;
+            let capture = if self.capture { client.capture() } else { None };
+            if let Some(capture) = &capture {
+                capture.request(&http_request);
+            }
+            let response = client.send(http_request, self.idempotent).await?;
+
+            let refresher = client.token_refresher().filter(|_| may_refresh);
+            if let (StatusCode::UNAUTHORIZED, Some(refresher)) = (response.status(), refresher) {
+                may_refresh = false;
+                match refresher.refresh(client, &self.base_url, &token).await {
+                    Ok(refreshed) => {
+                        tracing::info!(url = %self.url, correlation_id, "Retrying with a refreshed token");
+                        token = refreshed;
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e.sanitized_message(), "Token refresh failed")
+                    }
+                }
+            }
+            if response.status() == StatusCode::NOT_MODIFIED
+                && cache_key.is_some()
+                && cached.is_none()
+                && revalidate
+            {
+                tracing::debug!(url = %self.url, correlation_id, "304 with nothing cached, fetching in full");
+                revalidate = false;
+                continue;
+            }
+            break (capture, response, cached.map(|(_, body)| body));
+        };
+        let status = response.status();
+        tracing::debug!(%status, correlation_id, "Nimbus responded");
+
+        // Check response status
+        tracing::Span::current().record("status", status.as_u16());
+        let headers = response.headers().clone();
+        let etag = header_value(&headers, ETAG);
+        if let Some(key) = &cache_key {
+            if status == StatusCode::NOT_MODIFIED {
+                let text = cached.ok_or_else(|| {
+                    NimbusError::Parse(format!("304 for {} but nothing cached", self.url))
+                })?;
+                return Ok(SuccessBody {
+                    status: status.as_u16(),
+                    content_type: None,
+                    location: None,
+                    headers,
+                    text,
+                });
+            }
+            if etag.is_none() || !status.is_success() {
+                client.etag_cache().remove(key);
+            }
+        }
+        if let (StatusCode::NOT_FOUND, Some((entity, id))) = (status, self.not_found) {
+            return Err(NimbusError::NotFound { entity, id });
+        }
+        let content_type = header_value(&headers, CONTENT_TYPE);
+        let location = header_value(&headers, LOCATION);
+        let text = read_body(response, client.max_response_bytes()).await?;
+        if let Some(capture) = &capture {
+            capture.response(status.as_u16(), &text);
+        }
+        if !status.is_success() {
+            return Err(
+                NimbusError::from_body(status.as_u16(), &text).with_correlation_id(correlation_id)
+            );
+        }
+        if let (Some(key), Some(etag)) = (&cache_key, &etag) {
+            client.etag_cache().store(key, etag, &text);
+        }
+
+        Ok(SuccessBody {
+            status: status.as_u16(),
+            content_type,
+            location,
+            headers,
+            text,
+        })
+    }
+
+    /// The reqwest request for one attempt, authenticated with `token`
+    fn build(
+        &self,
+        token: &str,
+        if_none_match: Option<HeaderValue>,
+    ) -> Result<RequestBuilder, NimbusError> {
+        let client = self.client;
+        let correlation_id = self.correlation_id.as_str();
+
+        // Prepare headers
+        let mut headers: HeaderMap = client.headers(token)?;
+        headers.insert(
+            CORRELATION_ID_HEADER,
+            correlation_id
+                .parse()
+                .map_err(|e| NimbusError::InvalidHeader(format!("correlation id: {}", e)))?,
+        );
+        for (name, value) in &self.headers {
+            let value = value
+                .parse()
+                .map_err(|e| NimbusError::InvalidHeader(format!("{}: {}", name, e)))?;
+            headers.insert(*name, value);
+        }
+        if let Some(etag) = if_none_match {
+            headers.insert(IF_NONE_MATCH, etag);
+        }
+
+        // Serialized once into a buffer, so every retry resends the same bytes.
+        // Replaces the standard JSON Content-Type when the body is form-encoded
+        let body = match &self.body {
+            Some(body) => {
+                let (content_type, bytes) = encode_body(body, self.encoding)?;
+                headers.insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+                Some(bytes)
+            }
+            None => None,
+        };
+
+        tracing::debug!(method = %self.method, url = %self.url, correlation_id, "Sending Nimbus request");
+        let mut http_request = client
+            .http()
+            .request(self.method.clone(), &self.url)
+            .headers(headers);
+        if !self.query.is_empty() {
+            http_request = http_request.query(&self.query);
+        }
+        if let Some(bytes) = body {
+            http_request = http_request.body(bytes);
+        }
+
+        Ok(http_request)
+    }
+}
+
+/// How a request body is serialized
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum PayloadEncoding {
+    #[default]
+    Json,
+    /// `application/x-www-form-urlencoded`, with nested fields flattened the
+    /// way ASP.NET model binding reads them: `Locations[0].LocationID=3`
+    Form,
+}
+
+/// Serializes `body` for sending; returns the Content-Type and the bytes
+///
+/// Form encoding needs a JSON object at the top level. Null fields are left
+/// out, since a form has no way to say null.
+pub(crate) fn encode_body(
+    body: &Value,
+    encoding: PayloadEncoding,
+) -> Result<(&'static str, Vec<u8>), NimbusError> {
+    match encoding {
+        PayloadEncoding::Json => {
+            let bytes = serde_json::to_vec(body).map_err(|e| NimbusError::Parse(e.to_string()))?;
+            Ok(("application/json", bytes))
+        }
+        PayloadEncoding::Form => {
+            if !body.is_object() {
+                return Err(NimbusError::Validation(
+                    "A form-encoded body must be a JSON object".to_string(),
+                ));
+            }
+            let mut pairs = Vec::new();
+            flatten_form_fields("", body, &mut pairs);
+            let encoded = serde_urlencoded::to_string(&pairs)
+                .map_err(|e| NimbusError::Parse(e.to_string()))?;
+            Ok(("application/x-www-form-urlencoded", encoded.into_bytes()))
+        }
+    }
+}
+
+fn flatten_form_fields(name: &str, value: &Value, pairs: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => {}
+        Value::Object(fields) => {
+            for (key, value) in fields {
+                let name = if name.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", name, key)
+                };
+                flatten_form_fields(&name, value, pairs);
+            }
+        }
+        Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                flatten_form_fields(&format!("{}[{}]", name, i), item, pairs);
+            }
+        }
+        Value::String(text) => pairs.push((name.to_string(), text.clone())),
+        Value::Bool(_) | Value::Number(_) => pairs.push((name.to_string(), value.to_string())),
+    }
+}
+
+/// Reads the body chunk by chunk, giving up as soon as it passes `limit` bytes
+///
+/// A `Content-Length` over the limit fails before anything is read. Invalid
+/// UTF-8 is replaced rather than rejected, as `Response::text` does.
+pub(crate) async fn read_body(
+    mut response: Response,
+    limit: Option<u64>,
+) -> Result<String, NimbusError> {
+    let too_large = |limit| NimbusError::ResponseTooLarge { limit };
+    if let (Some(limit), Some(length)) = (limit, response.content_length()) {
+        if length > limit {
+            return Err(too_large(limit));
+        }
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| NimbusError::Parse(e.to_string()))?
+    {
+        body.extend_from_slice(&chunk);
+        if let Some(limit) = limit.filter(|limit| body.len() as u64 > *limit) {
+            return Err(too_large(limit));
+        }
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+pub(crate) fn header_value(headers: &HeaderMap, name: HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::{NimbusConfig, RetryPolicy};
+    use crate::commands::executor::MockExecutor;
+    use crate::commands::session::TokenRefresher;
+    use futures::future::BoxFuture;
+    use serde_json::json;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn test_request_builds_and_parses() {
+        let mock = MockExecutor::new();
+        mock.respond(200, r#"{"ok": true}"#).respond(404, "");
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let body: Value = client
+            .request(Method::POST, "https://nimbus.test", "tok", "/RESTApi/Thing")
+            .query("PageSize", 5)
+            .header("X-Trace", "abc")
+            .json_body(json!({"Name": "x"}))
+            .send_parsed()
+            .await
+            .unwrap();
+        assert_eq!(body["ok"], true);
+
+        let sent = &mock.requests()[0];
+        assert_eq!(sent.url, "https://nimbus.test/RESTApi/Thing?PageSize=5");
+        assert!(
+            uuid::Uuid::parse_str(sent.headers[CORRELATION_ID_HEADER].to_str().unwrap()).is_ok()
+        );
+        assert_eq!(sent.headers["X-Trace"], "abc");
+        assert_eq!(sent.headers["AuthenticationToken"], "tok");
+        assert_eq!(sent.body, Some(json!({"Name": "x"})));
+
+        let err = client
+            .request(
+                Method::GET,
+                "https://nimbus.test",
+                "tok",
+                "/RESTApi/Thing/3",
+            )
+            .not_found("Thing", 3)
+            .send_text()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            NimbusError::NotFound {
+                entity: "Thing",
+                id: 3
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retried_post_resends_identical_body() {
+        let mock = MockExecutor::new();
+        mock.respond(503, "").respond(200, r#"{"ok": true}"#);
+        let config = NimbusConfig {
+            retry: RetryPolicy {
+                max_attempts: 3,
+                base_delay: std::time::Duration::from_millis(1),
+                ..RetryPolicy::default()
+            },
+            ..NimbusConfig::default()
+        };
+        let client = NimbusClient::with_executor(config, mock.clone());
+
+        let payload = json!({"Description": "West Coast", "Locations": [{"LocationID": 101}]});
+        client
+            .request(Method::POST, "https://nimbus.test", "tok", "/RESTApi/Thing")
+            .json_body(payload.clone())
+            .idempotent(true)
+            .send_text()
+            .await
+            .unwrap();
+
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].body, Some(payload.clone()));
+        assert_eq!(requests[1].body, Some(payload));
+        assert_eq!(requests[1].headers[CONTENT_TYPE], "application/json");
+    }
+
+    #[test]
+    fn test_form_encoding_flattens_nested_fields() {
+        let payload = json!({
+            "Description": "West & East",
+            "Active": true,
+            "Locations": [{"LocationID": 101}, {"LocationID": 102}],
+            "Notes": null
+        });
+
+        let (content_type, bytes) = encode_body(&payload, PayloadEncoding::Form).unwrap();
+        assert_eq!(content_type, "application/x-www-form-urlencoded");
+        assert_eq!(
+            String::from_utf8(bytes).unwrap(),
+            "Active=true&Description=West+%26+East\
+             &Locations%5B0%5D.LocationID=101&Locations%5B1%5D.LocationID=102"
+        );
+
+        let (content_type, _) = encode_body(&payload, PayloadEncoding::Json).unwrap();
+        assert_eq!(content_type, "application/json");
+        assert!(encode_body(&json!([1, 2]), PayloadEncoding::Form).is_err());
+    }
+
+    /// Hands out `new-tok` and counts how often it was asked
+    #[derive(Default)]
+    struct FakeRefresher {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl TokenRefresher for FakeRefresher {
+        fn refresh<'a>(
+            &'a self,
+            _client: &'a NimbusClient,
+            _base_url: &'a str,
+            _rejected: &'a str,
+        ) -> BoxFuture<'a, Result<String, NimbusError>> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Box::pin(async { Ok("new-tok".to_string()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_401_refreshes_token_once() {
+        let mock = MockExecutor::new();
+        mock.respond(401, "").respond(200, "{}");
+        mock.respond(401, "")
+            .respond(401, r#"{"Message": "Expired"}"#);
+        let refresher = Arc::new(FakeRefresher::default());
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone())
+            .with_token_refresher(refresher.clone());
+        let get = || {
+            client
+                .request(
+                    Method::GET,
+                    "https://nimbus.test",
+                    "old-tok",
+                    "/RESTApi/Thing",
+                )
+                .send_text()
+        };
+
+        get().await.unwrap();
+        let requests = mock.requests();
+        assert_eq!(requests[0].headers["AuthenticationToken"], "old-tok");
+        assert_eq!(requests[1].headers["AuthenticationToken"], "new-tok");
+
+        // A second 401 is not refreshed again
+        let err = get().await.unwrap_err();
+        assert_eq!(err.status(), Some(401));
+        assert_eq!(mock.requests().len(), 4);
+        assert_eq!(refresher.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected() {
+        let mock = MockExecutor::new();
+        mock.respond(200, r#"{"ok": true}"#)
+            .respond(200, &format!(r#"{{"pad": "{}"}}"#, "x".repeat(64)));
+        let config = NimbusConfig {
+            max_response_bytes: Some(32),
+            ..NimbusConfig::default()
+        };
+        let client = NimbusClient::with_executor(config, mock.clone());
+        let get = || {
+            client
+                .request(Method::GET, "https://nimbus.test", "tok", "/RESTApi/Thing")
+                .send_text()
+        };
+
+        assert_eq!(get().await.unwrap(), r#"{"ok": true}"#);
+        let err = get().await.unwrap_err();
+        assert!(matches!(err, NimbusError::ResponseTooLarge { limit: 32 }));
+        assert_eq!(err.kind(), "response_too_large");
+    }
+
+    #[tokio::test]
+    async fn test_http_error_carries_correlation_id() {
+        let mock = MockExecutor::new();
+        mock.respond(400, r#"{"Message": "Bad"}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let err = client
+            .request(Method::GET, "https://nimbus.test", "tok", "/RESTApi/Thing")
+            .send_text()
+            .await
+            .unwrap_err();
+        let sent = &mock.requests()[0].headers[CORRELATION_ID_HEADER];
+        assert_eq!(err.correlation_id(), Some(sent.to_str().unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_cached_get_revalidates_with_etag() {
+        let mock = MockExecutor::new();
+        mock.respond_with_headers(200, &[("ETag", "\"v1\"")], r#"{"n": 1}"#)
+            .respond(304, "");
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        for _ in 0..2 {
+            let body: Value = client
+                .request(
+                    Method::GET,
+                    "https://nimbus.test",
+                    "tok",
+                    "/RESTApi/Thing/1",
+                )
+                .cached()
+                .send_parsed()
+                .await
+                .unwrap();
+            assert_eq!(body["n"], 1);
+        }
+
+        let requests = mock.requests();
+        assert!(requests[0].headers.get(IF_NONE_MATCH).is_none());
+        assert_eq!(requests[1].headers[IF_NONE_MATCH], "\"v1\"");
+    }
+
+    #[tokio::test]
+    async fn test_unmatched_304_is_fetched_again_in_full() {
+        let mock = MockExecutor::new();
+        mock.respond(304, "")
+            .respond_with_headers(200, &[("ETag", "\"v2\"")], r#"{"n": 2}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let body: Value = client
+            .request(
+                Method::GET,
+                "https://nimbus.test",
+                "tok",
+                "/RESTApi/Thing/1",
+            )
+            .cached()
+            .send_parsed()
+            .await
+            .unwrap();
+
+        assert_eq!(body["n"], 2);
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 2);
+        assert!(requests
+            .iter()
+            .all(|request| request.headers.get(IF_NONE_MATCH).is_none()));
+    }
+}
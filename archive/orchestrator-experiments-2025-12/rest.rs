@@ -0,0 +1,204 @@
+use reqwest::Method;
+use serde::Serialize;
+use serde_json::Value;
+
+use super::audit::{AuditLog, AuditOperation};
+use super::client::{with_request_timeout, NimbusClient};
+use super::error::{redact, redact_value, NimbusError};
+use super::profiles::ProfileStore;
+use super::session::{Session, Target};
+
+/// Status and body of a `call_rest` response
+#[derive(Debug, Serialize)]
+pub struct RestResponse {
+    pub status: u16,
+    /// Parsed JSON; null for an empty body, a string when the body isn't JSON
+    pub body: Value,
+}
+
+/// Calls any Nimbus REST endpoint, for ones the app has no command for yet
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log calls other than GET are recorded in
+/// * `method` - HTTP method, e.g. `GET` or `PATCH`
+/// * `path` - Path under the base URL, starting with `/`, e.g. `/RESTApi/Location/12`
+/// * `body` - JSON body to send, if any
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+///
+/// # Returns
+/// RestResponse with the status and body. Headers, retries, token refresh and
+/// error mapping are those of every other command, so a non-success status is
+/// a `NimbusError::Http`, and secrets in the body are redacted. Fails with
+/// `NimbusError::Disabled` when `NimbusConfig::allow_call_rest` is off.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn call_rest(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    method: String,
+    path: String,
+    body: Option<Value>,
+    timeout_ms: Option<u64>,
+) -> Result<RestResponse, NimbusError> {
+    if !client.allow_call_rest() {
+        return Err(NimbusError::Disabled(
+            "call_rest is turned off in this deployment".to_string(),
+        ));
+    }
+    let method = parse_method(&method)?;
+    validate_rest_path(&path)?;
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, None)?;
+
+    let result = with_request_timeout(
+        timeout_ms,
+        send_rest(&client, &base_url, &token, method.clone(), &path, body),
+    )
+    .await;
+    if let Some(operation) = audit_operation(&method) {
+        audit.record(
+            operation,
+            &format!("{} {}", method, path),
+            None,
+            Some(profile),
+            result.as_ref().err(),
+        );
+    }
+    result
+}
+
+/// Sends one `call_rest` request through the shared request path
+pub(crate) async fn send_rest(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    method: Method,
+    path: &str,
+    body: Option<Value>,
+) -> Result<RestResponse, NimbusError> {
+    let mut request = client.request(method, base_url, token, path);
+    if let Some(body) = body {
+        request = request.json_body(body);
+    }
+    let response = request.send_raw().await?;
+
+    let text = &response.text;
+    let body = if text.trim().is_empty() {
+        Value::Null
+    } else {
+        match serde_json::from_str(text) {
+            Ok(mut body) => {
+                redact_value(&mut body);
+                body
+            }
+            Err(_) => Value::String(redact(text)),
+        }
+    };
+    Ok(RestResponse {
+        status: response.status,
+        body,
+    })
+}
+
+fn parse_method(method: &str) -> Result<Method, NimbusError> {
+    match method.trim().to_ascii_uppercase().as_str() {
+        "GET" => Ok(Method::GET),
+        "POST" => Ok(Method::POST),
+        "PUT" => Ok(Method::PUT),
+        "PATCH" => Ok(Method::PATCH),
+        "DELETE" => Ok(Method::DELETE),
+        _ => Err(NimbusError::Validation(format!(
+            "Unsupported method '{}'; use GET, POST, PUT, PATCH or DELETE",
+            method
+        ))),
+    }
+}
+
+/// Checks `path` stays on the profile's host, so the token can't be sent elsewhere
+fn validate_rest_path(path: &str) -> Result<(), NimbusError> {
+    let invalid =
+        |reason: &str| NimbusError::Validation(format!("Invalid path '{}': {}", path, reason));
+
+    if !path.starts_with('/') {
+        return Err(invalid("must start with /"));
+    }
+    if path.starts_with("//") || path.contains('\\') {
+        return Err(invalid("must be a path on the Nimbus server"));
+    }
+    Ok(())
+}
+
+/// How a call changes Nimbus, for the audit log; None for reads
+fn audit_operation(method: &Method) -> Option<AuditOperation> {
+    match *method {
+        Method::POST => Some(AuditOperation::Create),
+        Method::PUT | Method::PATCH => Some(AuditOperation::Update),
+        Method::DELETE => Some(AuditOperation::Delete),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_send_rest_returns_status_and_body() {
+        let mock = MockExecutor::new();
+        mock.respond(
+            201,
+            r#"{"LocationID": 12, "Password": "hunter2", "Name": "Café token=12"}"#,
+        )
+        .respond(200, "plain text")
+        .respond(404, r#"{"Message": "No such location"}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+        let send = |method, body| {
+            send_rest(
+                &client,
+                "https://nimbus.test",
+                "tok",
+                method,
+                "/RESTApi/Location",
+                body,
+            )
+        };
+
+        let response = send(Method::POST, Some(json!({"Name": "Depot"})))
+            .await
+            .unwrap();
+        assert_eq!(response.status, 201);
+        assert_eq!(response.body["LocationID"], 12);
+        assert_eq!(response.body["Password"], "***");
+        assert_eq!(response.body["Name"], "Café token=12");
+
+        let response = send(Method::GET, None).await.unwrap();
+        assert_eq!(response.body, json!("plain text"));
+
+        let err = send(Method::GET, None).await.unwrap_err();
+        assert_eq!(err.status(), Some(404));
+        assert_eq!(mock.requests()[0].body, Some(json!({"Name": "Depot"})));
+    }
+
+    #[test]
+    fn test_rest_method_and_path_are_checked() {
+        assert_eq!(parse_method("patch").unwrap(), Method::PATCH);
+        assert!(parse_method("TRACE").is_err());
+
+        assert!(validate_rest_path("/RESTApi/Location?PageSize=5").is_ok());
+        assert!(validate_rest_path("RESTApi/Location").is_err());
+        assert!(validate_rest_path("//evil.example.com/x").is_err());
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt;
+
+use serde_json::Value;
+
+/// JSON type a response field is expected to have
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum JsonType {
+    Integer,
+    String,
+    Bool,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    fn matches(self, value: &Value) -> bool {
+        match self {
+            JsonType::Integer => value.is_i64() || value.is_u64(),
+            JsonType::String => value.is_string(),
+            JsonType::Bool => value.is_boolean(),
+            JsonType::Array => value.is_array(),
+            JsonType::Object => value.is_object(),
+        }
+    }
+}
+
+/// One field of a `ResponseSchema`
+#[derive(Debug)]
+pub struct FieldSpec {
+    pub name: &'static str,
+    pub json_type: JsonType,
+    /// Missing or null counts as drift; optional fields may be absent or null
+    pub required: bool,
+}
+
+const fn field(name: &'static str, json_type: JsonType, required: bool) -> FieldSpec {
+    FieldSpec {
+        name,
+        json_type,
+        required,
+    }
+}
+
+/// The fields a Nimbus response body is expected to have
+///
+/// Deliberately much less than JSON Schema: top-level names and types only,
+/// matched case-insensitively like `id_field`, since Nimbus versions disagree
+/// on casing.
+#[derive(Debug)]
+pub struct ResponseSchema {
+    /// Names the response in warnings, e.g. `LocationGroup create`
+    pub name: &'static str,
+    pub fields: &'static [FieldSpec],
+}
+
+/// Body of a LocationGroup create response that echoes the entity
+pub const CREATED_LOCATION_GROUP: ResponseSchema = ResponseSchema {
+    name: "LocationGroup create",
+    fields: &[
+        field("LocationGroupID", JsonType::Integer, true),
+        field("Description", JsonType::String, false),
+        field("Active", JsonType::Bool, false),
+        field("Locations", JsonType::Array, false),
+    ],
+};
+
+/// Body of a ScheduleGroup create response that echoes the entity
+pub const CREATED_SCHEDULE_GROUP: ResponseSchema = ResponseSchema {
+    name: "ScheduleGroup create",
+    fields: &[
+        field("ScheduleGroupID", JsonType::Integer, true),
+        field("Description", JsonType::String, false),
+        field("Active", JsonType::Bool, false),
+        field("LocationGroupID", JsonType::Integer, false),
+        field("GroupStartDate", JsonType::String, false),
+        field("GroupEndDate", JsonType::String, false),
+        field("AdhocFields", JsonType::Array, false),
+    ],
+};
+
+/// One way a response differs from its schema
+#[derive(Debug, PartialEq)]
+pub enum SchemaDrift {
+    Missing(&'static str),
+    WrongType {
+        field: &'static str,
+        expected: JsonType,
+    },
+    Unexpected(String),
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SchemaDrift::Missing(field) => write!(f, "{} is missing", field),
+            SchemaDrift::WrongType { field, expected } => {
+                write!(f, "{} is not {:?}", field, expected)
+            }
+            SchemaDrift::Unexpected(field) => write!(f, "{} is not in the schema", field),
+        }
+    }
+}
+
+impl ResponseSchema {
+    /// Every difference between `body` and the schema; empty when it matches
+    ///
+    /// A body that isn't an object only reports the required fields as missing.
+    pub fn drift(&self, body: &Value) -> Vec<SchemaDrift> {
+        let empty = serde_json::Map::new();
+        let object = body.as_object().unwrap_or(&empty);
+        let get = |name: &str| {
+            object
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value)
+        };
+
+        let mut drift = Vec::new();
+        for spec in self.fields {
+            match get(spec.name) {
+                None | Some(Value::Null) if spec.required => {
+                    drift.push(SchemaDrift::Missing(spec.name))
+                }
+                None | Some(Value::Null) => {}
+                Some(value) if !spec.json_type.matches(value) => {
+                    drift.push(SchemaDrift::WrongType {
+                        field: spec.name,
+                        expected: spec.json_type,
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        for key in object.keys() {
+            if !self
+                .fields
+                .iter()
+                .any(|spec| spec.name.eq_ignore_ascii_case(key))
+            {
+                drift.push(SchemaDrift::Unexpected(key.clone()));
+            }
+        }
+        drift
+    }
+}
+
+/// Logs a warning for each way `body` differs from `schema`, in debug builds only
+///
+/// Catches Nimbus API changes during development before they turn into
+/// silently dropped fields. Release builds skip the check entirely.
+pub fn warn_on_drift(schema: &ResponseSchema, body: &Value) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    for drift in schema.drift(body) {
+        tracing::warn!(response = schema.name, %drift, "Nimbus response differs from its schema");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_drift_reports_missing_mistyped_and_unexpected_fields() {
+        let matching = json!({"LocationGroupId": 7, "Description": "Depot", "Active": null});
+        assert!(CREATED_LOCATION_GROUP.drift(&matching).is_empty());
+
+        let drifted = json!({"Description": 12, "Locations": [], "Region": "West"});
+        assert_eq!(
+            CREATED_LOCATION_GROUP.drift(&drifted),
+            vec![
+                SchemaDrift::Missing("LocationGroupID"),
+                SchemaDrift::WrongType {
+                    field: "Description",
+                    expected: JsonType::String
+                },
+                SchemaDrift::Unexpected("Region".to_string()),
+            ]
+        );
+        assert_eq!(
+            CREATED_SCHEDULE_GROUP.drift(&json!(5)),
+            vec![SchemaDrift::Missing("ScheduleGroupID")]
+        );
+    }
+}
@@ -0,0 +1,907 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use futures::future::BoxFuture;
+use reqwest::Method;
+use serde::Serialize;
+use serde_json::{json, Value};
+
+use super::client::NimbusClient;
+use super::demo::{DEMO_PROFILE, DEMO_TOKEN};
+use super::error::{set_redacted_tokens, NimbusError};
+use super::profiles::ProfileStore;
+
+/// Keychain service name the token is stored under
+const KEYRING_SERVICE: &str = "claude-family-nimbus";
+
+/// Keychain account the token was stored under before accounts were per profile
+const LEGACY_KEYRING_ACCOUNT: &str = "default";
+
+/// Keychain account the refresh token was stored under before accounts were per profile
+const LEGACY_KEYRING_REFRESH_ACCOUNT: &str = "default-refresh";
+
+/// Current-user endpoint; older Nimbus versions don't have it
+const CURRENT_USER_PATH: &str = "/RESTApi/CurrentUser";
+
+/// Exchanges a refresh token for a new access token
+const REFRESH_TOKEN_PATH: &str = "/RESTApi/RefreshToken";
+
+/// Event emitted once the stored token is within the warning threshold of expiry
+pub const TOKEN_EXPIRING_EVENT: &str = "token-expiring";
+
+/// Event emitted after the stored token has been replaced by a refresh
+pub const TOKEN_REFRESHED_EVENT: &str = "token-refreshed";
+
+/// Who a token belongs to, as reported by Nimbus
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenInfo {
+    pub user: String,
+    pub roles: Vec<String>,
+    /// None when the server doesn't report an expiry
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// The profile a command runs against, with that profile's host and token
+///
+/// All three come from one resolved profile name, so switching the active
+/// profile mid-command can't pair one tenant's host with another's token.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub profile: String,
+    pub base_url: String,
+    pub token: String,
+}
+
+/// Nimbus auth tokens held in Tauri managed state, one set per profile
+///
+/// Tokens live in the OS keychain so they survive restarts, with an
+/// in-memory copy so commands don't hit the keychain on every call. Each
+/// profile has its own keychain accounts, so several tenants can stay logged
+/// in at once. The frontend only ever hands a token over once, to `login`.
+pub struct Session {
+    credentials: RwLock<HashMap<String, Credentials>>,
+    /// False for the demo session, whose tokens are only kept in memory
+    keychain: bool,
+}
+
+/// What `login` stored for one profile
+#[derive(Debug, Clone, Default)]
+struct Credentials {
+    token: String,
+    /// Exchanged for a new `token` by `refresh_token`, when the server issued one
+    refresh_token: Option<String>,
+    /// Last TokenInfo fetched for `token`, read by the expiry watch
+    token_info: Option<TokenInfo>,
+    /// The token `token` replaced, so a request still using it isn't refreshed twice
+    replaced: Option<String>,
+}
+
+impl Session {
+    /// Creates the session, restoring the stored tokens of every profile
+    ///
+    /// A token stored before accounts were per profile is moved to the
+    /// active profile, unless that profile already has one of its own.
+    pub fn load(profiles: &ProfileStore) -> Self {
+        let stored = |account: &str| {
+            keyring_entry(account)
+                .and_then(|entry| entry.get_password().map_err(keyring_error))
+                .ok()
+        };
+
+        let mut credentials = HashMap::new();
+        for name in profiles.names() {
+            if let Some(token) = stored(&token_account(&name)) {
+                let refresh_token = stored(&refresh_account(&name));
+                credentials.insert(name, Credentials::new(token, refresh_token));
+            }
+        }
+
+        let session = Self {
+            credentials: RwLock::new(credentials),
+            keychain: true,
+        };
+        if let Some(active) = profiles.active_name() {
+            if let Some(token) = stored(LEGACY_KEYRING_ACCOUNT) {
+                // The legacy entries are the only copy until the profile's are written
+                let moved = if session.token_for(&active).is_err() {
+                    let refresh_token = stored(LEGACY_KEYRING_REFRESH_ACCOUNT);
+                    session.store(&active, token, refresh_token)
+                } else {
+                    Ok(())
+                };
+                match moved {
+                    Ok(()) => {
+                        let _ =
+                            keyring_entry(LEGACY_KEYRING_ACCOUNT).and_then(|e| delete_password(&e));
+                        let _ = keyring_entry(LEGACY_KEYRING_REFRESH_ACCOUNT)
+                            .and_then(|e| delete_password(&e));
+                    }
+                    Err(e) => {
+                        tracing::warn!(error = %e.sanitized_message(), "Failed to move legacy token to profile")
+                    }
+                }
+            }
+        }
+        session.sync_redaction();
+        session
+    }
+
+    /// A session signed in to the demo profile that never touches the keychain
+    ///
+    /// Used with `NimbusConfig::demo_mode`, alongside `ProfileStore::demo`.
+    pub fn demo() -> Self {
+        let credentials = HashMap::from([(
+            DEMO_PROFILE.to_string(),
+            Credentials::new(DEMO_TOKEN.to_string(), None),
+        )]);
+        let session = Self {
+            credentials: RwLock::new(credentials),
+            keychain: false,
+        };
+        session.sync_redaction();
+        session
+    }
+
+    /// A session holding `(profile, token)` pairs in memory only, for tests
+    #[cfg(test)]
+    pub(crate) fn in_memory(tokens: &[(&str, &str)]) -> Self {
+        let credentials = tokens
+            .iter()
+            .map(|(profile, token)| {
+                (
+                    profile.to_string(),
+                    Credentials::new(token.to_string(), None),
+                )
+            })
+            .collect();
+        Self {
+            credentials: RwLock::new(credentials),
+            keychain: false,
+        }
+    }
+
+    /// Where and as whom to send a command for `profile`, the active one when None
+    ///
+    /// Fails with `NotAuthenticated` before that profile's `login`.
+    pub fn target(
+        &self,
+        profiles: &ProfileStore,
+        profile: Option<&str>,
+    ) -> Result<Target, NimbusError> {
+        let profile = profiles.resolve_name(profile)?;
+        let base_url = profiles.base_url(&profile)?;
+        let token = self.token_for(&profile)?;
+        Ok(Target {
+            profile,
+            base_url,
+            token,
+        })
+    }
+
+    /// The token stored for `profile`, or `NotAuthenticated` before `login`
+    pub fn token_for(&self, profile: &str) -> Result<String, NimbusError> {
+        self.read(profile, |c| c.token.clone())
+            .ok_or(NimbusError::NotAuthenticated)
+    }
+
+    /// TokenInfo cached for `profile` by the last `login` or `whoami`, if any
+    pub fn token_info(&self, profile: &str) -> Option<TokenInfo> {
+        self.read(profile, |c| c.token_info.clone()).flatten()
+    }
+
+    /// Names of the profiles with a stored token, sorted
+    pub fn signed_in(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.lock().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Every cached TokenInfo with the profile it belongs to
+    fn token_infos(&self) -> Vec<(String, TokenInfo)> {
+        self.lock()
+            .iter()
+            .filter_map(|(name, c)| Some((name.clone(), c.token_info.clone()?)))
+            .collect()
+    }
+
+    /// The profile `token` belongs to, and whether it is still that profile's current token
+    fn holder_of(&self, token: &str) -> Option<(String, bool)> {
+        self.lock().iter().find_map(|(name, c)| {
+            if c.token == token {
+                Some((name.clone(), true))
+            } else if c.replaced.as_deref() == Some(token) {
+                Some((name.clone(), false))
+            } else {
+                None
+            }
+        })
+    }
+
+    fn refresh_token(&self, profile: &str) -> Option<String> {
+        self.read(profile, |c| c.refresh_token.clone()).flatten()
+    }
+
+    fn cache_token_info(&self, profile: &str, info: Option<TokenInfo>) {
+        let mut credentials = self.credentials.write().expect("session lock poisoned");
+        if let Some(c) = credentials.get_mut(profile) {
+            c.token_info = info;
+        }
+    }
+
+    /// Stores `profile`'s tokens, forgetting its refresh token when None
+    fn store(
+        &self,
+        profile: &str,
+        token: String,
+        refresh_token: Option<String>,
+    ) -> Result<(), NimbusError> {
+        if self.keychain {
+            keyring_entry(&token_account(profile))?
+                .set_password(&token)
+                .map_err(keyring_error)?;
+            let refresh_entry = keyring_entry(&refresh_account(profile))?;
+            match &refresh_token {
+                Some(refresh_token) => refresh_entry
+                    .set_password(refresh_token)
+                    .map_err(keyring_error)?,
+                None => delete_password(&refresh_entry)?,
+            }
+        }
+
+        {
+            let mut credentials = self.credentials.write().expect("session lock poisoned");
+            let replaced = credentials.remove(profile).map(|c| c.token);
+            credentials.insert(
+                profile.to_string(),
+                Credentials {
+                    replaced,
+                    ..Credentials::new(token, refresh_token)
+                },
+            );
+        }
+        self.sync_redaction();
+        Ok(())
+    }
+
+    fn clear(&self, profile: &str) -> Result<(), NimbusError> {
+        self.credentials
+            .write()
+            .expect("session lock poisoned")
+            .remove(profile);
+        self.sync_redaction();
+        if !self.keychain {
+            return Ok(());
+        }
+        delete_password(&keyring_entry(&token_account(profile))?)?;
+        delete_password(&keyring_entry(&refresh_account(profile))?)
+    }
+
+    /// Has `redact` scrub every stored token, whichever profile it belongs to
+    fn sync_redaction(&self) {
+        let tokens: Vec<String> = self
+            .lock()
+            .values()
+            .flat_map(|c| [Some(c.token.clone()), c.refresh_token.clone()])
+            .flatten()
+            .collect();
+        set_redacted_tokens(tokens);
+    }
+
+    fn read<T>(&self, profile: &str, f: impl FnOnce(&Credentials) -> T) -> Option<T> {
+        self.lock().get(profile).map(f)
+    }
+
+    fn lock(&self) -> std::sync::RwLockReadGuard<'_, HashMap<String, Credentials>> {
+        self.credentials.read().expect("session lock poisoned")
+    }
+}
+
+impl Credentials {
+    fn new(token: String, refresh_token: Option<String>) -> Self {
+        Self {
+            token,
+            refresh_token,
+            ..Self::default()
+        }
+    }
+}
+
+/// Keychain account `profile`'s token is stored under
+fn token_account(profile: &str) -> String {
+    format!("profile/{}", profile)
+}
+
+/// Keychain account `profile`'s refresh token is stored under
+fn refresh_account(profile: &str) -> String {
+    format!("profile/{}/refresh", profile)
+}
+
+fn keyring_entry(account: &str) -> Result<keyring::Entry, NimbusError> {
+    keyring::Entry::new(KEYRING_SERVICE, account).map_err(keyring_error)
+}
+
+fn delete_password(entry: &keyring::Entry) -> Result<(), NimbusError> {
+    match entry.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(keyring_error(e)),
+    }
+}
+
+fn keyring_error(error: keyring::Error) -> NimbusError {
+    NimbusError::Keychain(error.to_string())
+}
+
+/// Validates a token against Nimbus and stores it for later commands
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state the token is stored in
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `token` - Authentication token to validate and store
+/// * `refresh_token` - Refresh token issued with it, used once `token` expires
+/// * `profile` - Profile to log in to; the active one when omitted
+///
+/// # Returns
+/// Nothing on success. A rejected token is reported as `NimbusError::Http`
+/// with status 401/403 and is not stored. Tokens already stored for other
+/// profiles are kept. The token's TokenInfo is fetched and cached for the
+/// expiry watch where the server supports it.
+#[tauri::command]
+pub async fn login(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    token: String,
+    refresh_token: Option<String>,
+    profile: Option<String>,
+) -> Result<(), NimbusError> {
+    // Resolve the target environment
+    let profile = profiles.resolve_name(profile.as_deref())?;
+    let base_url = profiles.base_url(&profile)?;
+
+    // Cheapest authenticated call available: a single-item LocationGroup page.
+    // A rejection is about this token, so it must not refresh the old one.
+    client
+        .request(Method::GET, &base_url, &token, "/RESTApi/LocationGroup")
+        .query("PageNumber", 1)
+        .query("PageSize", 1)
+        .token_refresh(false)
+        .send_text()
+        .await?;
+
+    session.store(&profile, token.clone(), refresh_token)?;
+
+    // Best effort: servers without the current-user endpoint just don't get expiry warnings
+    match fetch_token_info(&client, &base_url, &token).await {
+        Ok(info) => session.cache_token_info(&profile, Some(info)),
+        Err(e) => {
+            tracing::debug!(error = %e.sanitized_message(), "Token info unavailable after login")
+        }
+    }
+    Ok(())
+}
+
+/// Exchanges a refresh token for a new access token and stores both
+///
+/// # Arguments
+/// * `app` - App handle `token-refreshed` is emitted on
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state the new token is stored in
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `refresh_token` - Refresh token to exchange; the stored one when omitted
+/// * `profile` - Profile whose token to refresh; the active one when omitted
+///
+/// # Returns
+/// The TokenRefreshed payload that was also emitted as `token-refreshed`.
+/// Without a refresh token this is `NimbusError::NotAuthenticated`.
+#[tauri::command]
+pub async fn refresh_token(
+    app: tauri::AppHandle,
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    refresh_token: Option<String>,
+    profile: Option<String>,
+) -> Result<TokenRefreshed, NimbusError> {
+    // Resolve the target environment
+    let profile = profiles.resolve_name(profile.as_deref())?;
+    let base_url = profiles.base_url(&profile)?;
+
+    let refreshed = refresh_session(&client, &session, &profile, &base_url, refresh_token).await?;
+    emit_token_refreshed(&app, &refreshed);
+    Ok(refreshed)
+}
+
+/// Payload of `token-refreshed`; the token itself never leaves Rust
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenRefreshed {
+    /// Profile whose token was replaced
+    pub profile: String,
+    /// None when the server doesn't report an expiry
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Tokens issued by the refresh endpoint
+#[derive(Debug, Clone)]
+pub(crate) struct RefreshedTokens {
+    pub access_token: String,
+    /// None when the server keeps the old refresh token valid
+    pub refresh_token: Option<String>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// Refreshes `profile`'s token with `refresh_token` (or the stored one)
+async fn refresh_session(
+    client: &NimbusClient,
+    session: &Session,
+    profile: &str,
+    base_url: &str,
+    refresh_token: Option<String>,
+) -> Result<TokenRefreshed, NimbusError> {
+    let refresh_token = refresh_token
+        .or_else(|| session.refresh_token(profile))
+        .ok_or(NimbusError::NotAuthenticated)?;
+    let tokens = exchange_refresh_token(client, base_url, &refresh_token).await?;
+
+    let refresh_token = tokens.refresh_token.unwrap_or(refresh_token);
+    session.store(profile, tokens.access_token.clone(), Some(refresh_token))?;
+    tracing::info!(profile, "Nimbus token refreshed");
+
+    // Best effort, as after login, so the expiry watch follows the new token
+    let mut expires_at = tokens.expires_at;
+    match fetch_token_info(client, base_url, &tokens.access_token).await {
+        Ok(info) => {
+            expires_at = expires_at.or(info.expires_at);
+            session.cache_token_info(profile, Some(info));
+        }
+        Err(e) => {
+            tracing::debug!(error = %e.sanitized_message(), "Token info unavailable after refresh")
+        }
+    }
+    Ok(TokenRefreshed {
+        profile: profile.to_string(),
+        expires_at,
+    })
+}
+
+/// POSTs the refresh token and parses the tokens issued in exchange
+///
+/// The refresh token doubles as the request's auth header, and the call is
+/// never itself refreshed, so a rejected refresh token fails with its 401.
+pub(crate) async fn exchange_refresh_token(
+    client: &NimbusClient,
+    base_url: &str,
+    refresh_token: &str,
+) -> Result<RefreshedTokens, NimbusError> {
+    let response_body: Value = client
+        .request(Method::POST, base_url, refresh_token, REFRESH_TOKEN_PATH)
+        .json_body(json!({ "RefreshToken": refresh_token }))
+        .token_refresh(false)
+        .send_parsed()
+        .await?;
+
+    parse_refreshed_tokens(&response_body)
+}
+
+/// Parses a refresh response; the access token may be named `AuthenticationToken`,
+/// `AccessToken` or `Token`
+fn parse_refreshed_tokens(body: &Value) -> Result<RefreshedTokens, NimbusError> {
+    let string_field = |name: &str| body.get(name).and_then(|v| v.as_str()).map(str::to_string);
+    let access_token = string_field("AuthenticationToken")
+        .or_else(|| string_field("AccessToken"))
+        .or_else(|| string_field("Token"))
+        .ok_or_else(|| NimbusError::Parse("AccessToken not found in response".to_string()))?;
+    let expires_at = match body.get("TokenExpiry").and_then(|v| v.as_str()) {
+        Some(value) => Some(parse_expiry(value)?),
+        None => None,
+    };
+
+    Ok(RefreshedTokens {
+        access_token,
+        refresh_token: string_field("RefreshToken"),
+        expires_at,
+    })
+}
+
+fn emit_token_refreshed(app: &tauri::AppHandle, refreshed: &TokenRefreshed) {
+    use tauri::Manager;
+
+    if let Err(e) = app.emit_all(TOKEN_REFRESHED_EVENT, refreshed.clone()) {
+        tracing::warn!(error = ?e, "Failed to emit token-refreshed");
+    }
+}
+
+/// Supplies a new access token after Nimbus answers 401
+///
+/// Installed with `NimbusClient::with_token_refresher`; each request asks at
+/// most once, then retries with the returned token.
+pub trait TokenRefresher: Send + Sync {
+    /// A token to retry with, given the one that was just `rejected`
+    fn refresh<'a>(
+        &'a self,
+        client: &'a NimbusClient,
+        base_url: &'a str,
+        rejected: &'a str,
+    ) -> BoxFuture<'a, Result<String, NimbusError>>;
+}
+
+/// Refreshes the managed `Session` with its stored refresh token
+///
+/// The profile refreshed is the one the rejected token belongs to. Refreshes
+/// run one at a time. A caller whose rejected token has already been replaced
+/// (by a refresh it queued behind, or a new login) gets the current token
+/// without another refresh, since refresh tokens are often single-use.
+pub struct SessionRefresher {
+    app: tauri::AppHandle,
+    lock: tokio::sync::Mutex<()>,
+}
+
+impl SessionRefresher {
+    pub fn new(app: tauri::AppHandle) -> Self {
+        Self {
+            app,
+            lock: tokio::sync::Mutex::new(()),
+        }
+    }
+}
+
+impl TokenRefresher for SessionRefresher {
+    fn refresh<'a>(
+        &'a self,
+        client: &'a NimbusClient,
+        base_url: &'a str,
+        rejected: &'a str,
+    ) -> BoxFuture<'a, Result<String, NimbusError>> {
+        use tauri::Manager;
+
+        Box::pin(async move {
+            let _guard = self.lock.lock().await;
+            let session = self.app.state::<Session>();
+            let (profile, current) = session
+                .holder_of(rejected)
+                .ok_or(NimbusError::NotAuthenticated)?;
+            if !current {
+                return session.token_for(&profile);
+            }
+
+            let refreshed = refresh_session(client, &session, &profile, base_url, None).await?;
+            emit_token_refreshed(&self.app, &refreshed);
+            session.token_for(&profile)
+        })
+    }
+}
+
+/// Forgets a profile's stored token, both in memory and in the OS keychain
+///
+/// # Arguments
+/// * `session` - Session state the token is stored in
+/// * `profiles` - Profile state the active profile is resolved from
+/// * `profile` - Profile to log out of; the active one when omitted
+///
+/// # Returns
+/// Nothing on success. Other profiles stay logged in.
+#[tauri::command]
+pub async fn logout(
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    profile: Option<String>,
+) -> Result<(), NimbusError> {
+    session.clear(&profiles.resolve_name(profile.as_deref())?)
+}
+
+/// Describes the account the stored token belongs to
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `profile` - Profile to ask about; the active one when omitted
+///
+/// # Returns
+/// TokenInfo with the user name, roles, and expiry. Servers without the
+/// current-user endpoint are reported as `NimbusError::Unsupported`.
+#[tauri::command]
+pub async fn whoami(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    profile: Option<String>,
+) -> Result<TokenInfo, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let info = fetch_token_info(&client, &base_url, &token).await?;
+    session.cache_token_info(&profile, Some(info.clone()));
+    Ok(info)
+}
+
+/// GETs the current user for `token`
+pub(crate) async fn fetch_token_info(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+) -> Result<TokenInfo, NimbusError> {
+    // Make GET request
+    let result = client
+        .request(Method::GET, base_url, token, CURRENT_USER_PATH)
+        .send_parsed()
+        .await;
+
+    // Check response status - a missing route means the endpoint doesn't exist here
+    let response_body: Value = match result {
+        Err(NimbusError::Http {
+            status: status @ (404 | 405 | 501),
+            ..
+        }) => {
+            return Err(NimbusError::Unsupported(format!(
+                "{} answered {}",
+                CURRENT_USER_PATH, status
+            )))
+        }
+        result => result?,
+    };
+
+    parse_token_info(&response_body)
+}
+
+/// Parses a Nimbus current-user body into a TokenInfo
+///
+/// Roles may be plain strings or `{"RoleName": ...}` objects. The expiry is
+/// RFC 3339, or a naive timestamp taken to be UTC.
+fn parse_token_info(body: &Value) -> Result<TokenInfo, NimbusError> {
+    let user = body
+        .get("UserName")
+        .or_else(|| body.get("User"))
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| NimbusError::Parse("UserName not found in response".to_string()))?
+        .to_string();
+
+    let roles = body
+        .get("Roles")
+        .and_then(|v| v.as_array())
+        .map(|roles| {
+            roles
+                .iter()
+                .filter_map(|role| role.as_str().or_else(|| role.get("RoleName")?.as_str()))
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let expires_at = match body.get("TokenExpiry").and_then(|v| v.as_str()) {
+        Some(value) => Some(parse_expiry(value)?),
+        None => None,
+    };
+
+    Ok(TokenInfo {
+        user,
+        roles,
+        expires_at,
+    })
+}
+
+fn parse_expiry(value: &str) -> Result<DateTime<Utc>, NimbusError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .or_else(|_| {
+            NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f").map(|dt| dt.and_utc())
+        })
+        .map_err(|_| NimbusError::Parse(format!("Invalid TokenExpiry '{}'", value)))
+}
+
+/// Settings for the background token expiry check
+#[derive(Debug, Clone)]
+pub struct ExpiryWatchConfig {
+    /// How often the cached expiry is checked
+    pub interval: Duration,
+    /// How long before expiry `token-expiring` is emitted
+    pub threshold: Duration,
+}
+
+impl Default for ExpiryWatchConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(5 * 60),
+            threshold: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// Payload of `token-expiring`
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenExpiring {
+    /// Profile the expiring token is stored for
+    pub profile: String,
+    pub user: String,
+    pub expires_at: DateTime<Utc>,
+    /// Negative once the token has already expired
+    pub remaining_secs: i64,
+}
+
+/// Spawns the task that emits `token-expiring` for each profile's cached TokenInfo
+///
+/// Call from `setup` after `Session` is managed. The event fires once per
+/// token, the first time a check finds it inside `config.threshold`.
+pub fn spawn_expiry_watch(app: tauri::AppHandle, config: ExpiryWatchConfig) {
+    use tauri::Manager;
+
+    tauri::async_runtime::spawn(async move {
+        let mut watches: HashMap<String, ExpiryWatch> = HashMap::new();
+        let mut ticks = tokio::time::interval(config.interval);
+        loop {
+            ticks.tick().await;
+            for (profile, info) in app.state::<Session>().token_infos() {
+                let watch = watches
+                    .entry(profile.clone())
+                    .or_insert_with(|| ExpiryWatch::new(config.threshold));
+                if let Some(event) = watch.check(&profile, Some(&info), Utc::now()) {
+                    tracing::warn!(
+                        profile,
+                        expires_at = %event.expires_at,
+                        "Nimbus token expiring soon"
+                    );
+                    if let Err(e) = app.emit_all(TOKEN_EXPIRING_EVENT, event) {
+                        tracing::warn!(error = ?e, "Failed to emit token-expiring");
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Decides when to warn, remembering which expiry it already warned about
+struct ExpiryWatch {
+    threshold: chrono::Duration,
+    warned_for: Option<DateTime<Utc>>,
+}
+
+impl ExpiryWatch {
+    fn new(threshold: Duration) -> Self {
+        Self {
+            threshold: chrono::Duration::from_std(threshold).unwrap_or(chrono::Duration::MAX),
+            warned_for: None,
+        }
+    }
+
+    fn check(
+        &mut self,
+        profile: &str,
+        info: Option<&TokenInfo>,
+        now: DateTime<Utc>,
+    ) -> Option<TokenExpiring> {
+        let info = info?;
+        let expires_at = info.expires_at?;
+        let remaining = expires_at - now;
+        if remaining > self.threshold || self.warned_for == Some(expires_at) {
+            return None;
+        }
+
+        self.warned_for = Some(expires_at);
+        Some(TokenExpiring {
+            profile: profile.to_string(),
+            user: info.user.clone(),
+            expires_at,
+            remaining_secs: remaining.num_seconds(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_token_info() {
+        let body = json!({
+            "UserName": "jsmith",
+            "Roles": ["Scheduler", {"RoleName": "Admin"}],
+            "TokenExpiry": "2025-06-01T09:30:00"
+        });
+        let info = parse_token_info(&body).unwrap();
+        assert_eq!(info.user, "jsmith");
+        assert_eq!(info.roles, vec!["Scheduler", "Admin"]);
+        assert_eq!(
+            info.expires_at.unwrap().to_rfc3339(),
+            "2025-06-01T09:30:00+00:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_endpoint_is_unsupported() {
+        let mock = MockExecutor::new();
+        mock.respond(404, "");
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let err = fetch_token_info(&client, "https://nimbus.test", "tok")
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), "unsupported");
+    }
+
+    #[test]
+    fn test_parse_refreshed_tokens() {
+        let body = json!({
+            "AccessToken": "new-tok",
+            "RefreshToken": "new-refresh",
+            "TokenExpiry": "2025-06-01T09:30:00Z"
+        });
+        let tokens = parse_refreshed_tokens(&body).unwrap();
+        assert_eq!(tokens.access_token, "new-tok");
+        assert_eq!(tokens.refresh_token.as_deref(), Some("new-refresh"));
+        assert!(tokens.expires_at.is_some());
+
+        let tokens = parse_refreshed_tokens(&json!({"AuthenticationToken": "t"})).unwrap();
+        assert_eq!(tokens.access_token, "t");
+        assert!(tokens.refresh_token.is_none());
+        assert!(parse_refreshed_tokens(&json!({})).is_err());
+    }
+
+    #[test]
+    fn test_expiry_watch_fires_once_per_token() {
+        let now = Utc::now();
+        let token = |hours| TokenInfo {
+            user: "jsmith".to_string(),
+            roles: Vec::new(),
+            expires_at: Some(now + chrono::Duration::hours(hours)),
+        };
+        let mut watch = ExpiryWatch::new(Duration::from_secs(24 * 60 * 60));
+
+        assert!(watch.check("prod", Some(&token(48)), now).is_none());
+        let event = watch.check("prod", Some(&token(12)), now).unwrap();
+        assert_eq!(event.remaining_secs, 12 * 60 * 60);
+        assert!(watch.check("prod", Some(&token(12)), now).is_none());
+        assert!(watch.check("prod", None, now).is_none());
+
+        // A renewed token that is itself close to expiry warns again
+        assert!(watch.check("prod", Some(&token(6)), now).is_some());
+    }
+
+    #[test]
+    fn test_holder_of_tracks_replaced_tokens() {
+        let mut credentials = HashMap::new();
+        credentials.insert(
+            "prod".to_string(),
+            Credentials {
+                replaced: Some("old-tok".to_string()),
+                ..Credentials::new("prod-tok".to_string(), None)
+            },
+        );
+        credentials.insert(
+            "dev".to_string(),
+            Credentials::new("dev-tok".to_string(), None),
+        );
+        let session = Session {
+            credentials: RwLock::new(credentials),
+            keychain: false,
+        };
+
+        assert_eq!(
+            session.holder_of("prod-tok"),
+            Some(("prod".to_string(), true))
+        );
+        assert_eq!(
+            session.holder_of("old-tok"),
+            Some(("prod".to_string(), false))
+        );
+        assert_eq!(
+            session.holder_of("dev-tok"),
+            Some(("dev".to_string(), true))
+        );
+        assert_eq!(session.holder_of("unknown"), None);
+        assert_eq!(session.signed_in(), vec!["dev", "prod"]);
+        assert_eq!(session.token_for("dev").unwrap(), "dev-tok");
+        assert!(matches!(
+            session.token_for("staging"),
+            Err(NimbusError::NotAuthenticated)
+        ));
+    }
+}
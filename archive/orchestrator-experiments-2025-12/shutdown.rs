@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use tauri::Manager;
+
+use super::client::NimbusClient;
+use super::logging::LogGuard;
+use super::paths::AppPaths;
+use super::queue::OfflineQueue;
+
+/// How long `shutdown` lets requests still on the wire finish
+pub const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+
+/// Saves what is only held in memory before the app exits
+///
+/// Call from the `RunEvent::Exit` handler. Waits up to `grace` for in-flight
+/// requests, writes the metrics snapshot and the offline queue, and flushes
+/// the log file last so the earlier steps are logged too. Every step is best
+/// effort: a failure is logged and the remaining steps still run.
+pub fn shutdown(app: &tauri::AppHandle, grace: Duration) {
+    if let Some(client) = app.try_state::<NimbusClient>() {
+        let active = client.active_requests();
+        if !tauri::async_runtime::block_on(active.wait_idle(grace)) {
+            tracing::warn!(
+                active = active.len(),
+                ?grace,
+                "Exiting with Nimbus requests still in flight"
+            );
+        }
+
+        let saved = AppPaths::resolve(&app.path_resolver())
+            .and_then(|paths| client.metrics().snapshot().save(&paths.metrics_file));
+        if let Err(e) = saved {
+            tracing::warn!(error = %e.sanitized_message(), "Failed to save metrics on shutdown");
+        }
+    }
+
+    if let Some(queue) = app.try_state::<OfflineQueue>() {
+        if let Err(e) = queue.flush() {
+            tracing::warn!(error = %e.sanitized_message(), "Failed to save the offline queue on shutdown");
+        }
+    }
+
+    tracing::info!("Shutdown complete");
+    if let Some(guard) = app.try_state::<LogGuard>() {
+        guard.flush();
+    }
+}
@@ -0,0 +1,1029 @@
+use std::collections::HashSet;
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use chrono::NaiveDate;
+use futures::{StreamExt, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::audit::{AuditLog, AuditOperation};
+use super::batch::{record_create, run_bounded, DEFAULT_BATCH_CONCURRENCY};
+use super::client::{new_idempotency_key, with_request_timeout, NimbusClient};
+use super::entities::{
+    all_pages, fetch_location_group_page, post_location_group, send_schedule_group, LearningPeriod,
+    LocationGroupDetail, LocationGroupRequest, ScheduleGroupRequest, NIMBUS_DATE_FORMAT,
+};
+use super::error::{FieldError, NimbusError};
+use super::profiles::ProfileStore;
+use super::session::{Session, Target};
+
+/// LocationGroups requested per page while exporting
+const EXPORT_PAGE_SIZE: u32 = 100;
+
+/// Separator for the location IDs packed into one CSV cell
+const CSV_LOCATION_ID_SEPARATOR: &str = ";";
+
+/// Date formats accepted in a schedule CSV, tried in order
+const CSV_DATE_FORMATS: [&str; 2] = [NIMBUS_DATE_FORMAT, "%m/%d/%Y"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// A pretty-printed array of LocationGroupDetail, re-importable as-is
+    Json,
+    /// One row per group, location IDs joined with `;`
+    Csv,
+    /// One compact LocationGroupDetail object per line, for very large exports
+    #[serde(alias = "jsonl")]
+    JsonLines,
+}
+
+/// What happened to one record of an import
+#[derive(Debug, Serialize)]
+pub struct ImportRecord {
+    /// 0-based position in the file
+    pub index: usize,
+    /// None when the record couldn't be read at all
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub outcome: ImportOutcome,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ImportOutcome {
+    Created {
+        location_group_id: i64,
+    },
+    /// A group with the same description already exists and `skip_existing` was set
+    Skipped,
+    Failed {
+        error: NimbusError,
+    },
+}
+
+/// What happened to one row of a schedule CSV import
+#[derive(Debug, Serialize)]
+pub struct ScheduleImportRecord {
+    /// Line the row starts on, counting the header as line 1
+    pub line: u64,
+    /// None when the row has no description cell
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub outcome: ScheduleImportOutcome,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum ScheduleImportOutcome {
+    Created { schedule_group_id: i64 },
+    Failed { error: NimbusError },
+}
+
+/// One LocationGroup flattened to a CSV row
+#[derive(Debug, Serialize)]
+struct LocationGroupCsvRow<'a> {
+    location_group_id: i64,
+    description: &'a str,
+    active: bool,
+    location_ids: String,
+}
+
+/// Reads location IDs from one column of a CSV file
+///
+/// # Arguments
+/// * `path` - CSV file with a header row
+/// * `column` - Header of the column holding the location IDs
+///
+/// # Returns
+/// The IDs in file order with duplicates dropped, ready for
+/// `LocationGroupRequest::location_ids`. Blank cells are ignored; any other
+/// value that isn't an integer fails the whole import with its row number.
+#[tauri::command]
+pub async fn location_ids_from_csv(path: String, column: String) -> Result<Vec<i64>, NimbusError> {
+    let file = std::fs::File::open(&path)
+        .map_err(|e| NimbusError::Config(format!("Failed to read {}: {}", path, e)))?;
+
+    read_location_ids_csv(file, &column)
+}
+
+/// Extracts and parses `column` from CSV data
+///
+/// Row numbers in errors count the header as row 1, matching what a
+/// spreadsheet shows.
+fn read_location_ids_csv(data: impl Read, column: &str) -> Result<Vec<i64>, NimbusError> {
+    let mut reader = csv::Reader::from_reader(data);
+    let invalid_csv = |e: csv::Error| NimbusError::Validation(format!("Invalid CSV: {}", e));
+
+    let index = reader
+        .headers()
+        .map_err(invalid_csv)?
+        .iter()
+        .position(|header| header.trim() == column.trim())
+        .ok_or_else(|| NimbusError::Validation(format!("Column '{}' not found", column)))?;
+
+    let mut seen = HashSet::new();
+    let mut ids = Vec::new();
+    let mut bad_rows = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(invalid_csv)?;
+        let value = record.get(index).unwrap_or("").trim();
+        if value.is_empty() {
+            continue;
+        }
+
+        match value.parse::<i64>() {
+            Ok(id) => {
+                if seen.insert(id) {
+                    ids.push(id);
+                }
+            }
+            Err(_) => {
+                let row = record.position().map_or(0, |p| p.line());
+                bad_rows.push(format!("row {} ('{}')", row, value));
+            }
+        }
+    }
+
+    if !bad_rows.is_empty() {
+        return Err(NimbusError::Validation(format!(
+            "Non-numeric location IDs in column '{}': {}",
+            column,
+            bad_rows.join(", ")
+        )));
+    }
+    Ok(ids)
+}
+
+/// Writes every LocationGroup to a file
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `format` - `json`, `csv` or `jsonlines`
+/// * `path` - File to create (overwritten if it exists)
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// Number of groups exported. Groups are fetched a page at a time and written
+/// as they arrive, so only one page is held in memory. A `jsonlines` file is
+/// flushed after every page and only ever holds complete lines, so one cut
+/// short by an error or a crash is still valid up to its last record. A
+/// `json` or `csv` export that fails leaves `path` as it was.
+#[tauri::command]
+pub async fn export_location_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    format: ExportFormat,
+    path: String,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<usize, NimbusError> {
+    // Resolve the target environment and credentials
+    let Target {
+        base_url, token, ..
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let export = export_to_path(&client, &base_url, &token, format, &path, EXPORT_PAGE_SIZE);
+    let count = with_request_timeout(timeout_ms, export).await?;
+
+    tracing::info!(%path, count, ?format, "Exported location groups");
+    Ok(count)
+}
+
+/// Runs an export into the file at `path`
+///
+/// JSON and CSV are only valid once complete, so they are written to a
+/// temporary file in the same directory and renamed over `path` at the end;
+/// on failure the temporary file is deleted. JSON Lines is written in place.
+async fn export_to_path(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    format: ExportFormat,
+    path: &str,
+    page_size: u32,
+) -> Result<usize, NimbusError> {
+    let target = Path::new(path);
+    let written = match format {
+        ExportFormat::JsonLines => target.to_path_buf(),
+        ExportFormat::Json | ExportFormat::Csv => partial_path(target),
+    };
+
+    let result = match std::fs::File::create(&written) {
+        Ok(file) => {
+            let out = BufWriter::new(file);
+            write_location_groups(client, base_url, token, format, out, page_size).await
+        }
+        Err(e) => Err(ExportError::Io(e)),
+    }
+    .and_then(|count| {
+        if written != target {
+            std::fs::rename(&written, target)?;
+        }
+        Ok(count)
+    });
+
+    if result.is_err() && written != target {
+        let _ = std::fs::remove_file(&written);
+    }
+    result.map_err(|e| match e {
+        ExportError::Io(e) => write_err(path, e),
+        ExportError::Nimbus(e) => e,
+    })
+}
+
+/// Hidden, uniquely named sibling of `target` an export is written to first
+fn partial_path(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map_or_else(|| "export".into(), |name| name.to_string_lossy());
+    target.with_file_name(format!(".{}.{}.partial", name, uuid::Uuid::new_v4()))
+}
+
+fn write_err(path: &str, e: std::io::Error) -> NimbusError {
+    NimbusError::Config(format!("Failed to write {}: {}", path, e))
+}
+
+/// Why an export stopped: the file couldn't be written, or Nimbus failed
+enum ExportError {
+    Io(std::io::Error),
+    Nimbus(NimbusError),
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e)
+    }
+}
+
+/// Streams every LocationGroup into `out`, one page in memory at a time
+async fn write_location_groups<W: Write>(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    format: ExportFormat,
+    out: W,
+    page_size: u32,
+) -> Result<usize, ExportError> {
+    let mut out = match format {
+        ExportFormat::Json => ExportWriter::Json(out),
+        ExportFormat::Csv => ExportWriter::Csv(Box::new(csv::Writer::from_writer(out))),
+        ExportFormat::JsonLines => ExportWriter::JsonLines(out),
+    };
+    let mut count = 0;
+
+    let groups =
+        all_pages(|page| fetch_location_group_page(client, base_url, token, page, page_size));
+    futures::pin_mut!(groups);
+    while let Some(group) = groups.next().await {
+        let group = group.map_err(ExportError::Nimbus)?;
+        out.write_group(&group, count == 0)?;
+        count += 1;
+        if count % page_size.max(1) as usize == 0 {
+            out.page_done()?;
+        }
+    }
+
+    out.finish(count == 0)?;
+    Ok(count)
+}
+
+enum ExportWriter<W: Write> {
+    Json(W),
+    // Boxed: the CSV writer carries its own buffer
+    Csv(Box<csv::Writer<W>>),
+    JsonLines(W),
+}
+
+impl<W: Write> ExportWriter<W> {
+    fn write_group(&mut self, group: &LocationGroupDetail, first: bool) -> std::io::Result<()> {
+        match self {
+            ExportWriter::Json(out) => {
+                // Indent each pretty-printed group one level inside the array
+                let pretty = serde_json::to_string_pretty(group)?;
+                out.write_all(if first { b"[\n" } else { b",\n" })?;
+                let indented: Vec<String> = pretty.lines().map(|l| format!("  {}", l)).collect();
+                out.write_all(indented.join("\n").as_bytes())
+            }
+            ExportWriter::Csv(out) => {
+                let location_ids = group
+                    .location_ids
+                    .iter()
+                    .map(i64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(CSV_LOCATION_ID_SEPARATOR);
+                out.serialize(LocationGroupCsvRow {
+                    location_group_id: group.location_group_id,
+                    description: &group.description,
+                    active: group.active,
+                    location_ids,
+                })
+                .map_err(std::io::Error::from)
+            }
+            ExportWriter::JsonLines(out) => {
+                // One write per complete line, so a BufWriter only ever
+                // flushes between lines
+                let mut line = serde_json::to_vec(group)?;
+                line.push(b'\n');
+                out.write_all(&line)
+            }
+        }
+    }
+
+    /// Called after each full page; pushes JSON lines to disk as they arrive
+    fn page_done(&mut self) -> std::io::Result<()> {
+        match self {
+            ExportWriter::JsonLines(out) => out.flush(),
+            ExportWriter::Json(_) | ExportWriter::Csv(_) => Ok(()),
+        }
+    }
+
+    /// Closes the JSON array and flushes everything to the underlying writer
+    fn finish(self, empty: bool) -> std::io::Result<()> {
+        match self {
+            ExportWriter::Json(mut out) => {
+                out.write_all(if empty { b"[]\n" } else { b"\n]\n" })?;
+                out.flush()
+            }
+            ExportWriter::Csv(mut out) => out.flush(),
+            ExportWriter::JsonLines(mut out) => out.flush(),
+        }
+    }
+}
+
+/// Recreates LocationGroups from a JSON file written by `export_location_groups`
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log each create is recorded in
+/// * `path` - JSON array of LocationGroupRequest-shaped records; extra fields
+///   such as `location_group_id` are ignored
+/// * `skip_existing` - When a group with the same description already exists,
+///   skip the record (true) or report it as failed (false)
+/// * `concurrency` - Maximum creates in flight (defaults to `DEFAULT_BATCH_CONCURRENCY`)
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// One ImportRecord per record, in file order. A bad record doesn't stop the
+/// rest, so a partial import can be finished by re-running with `skip_existing`.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn import_location_groups(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    path: String,
+    skip_existing: bool,
+    concurrency: Option<usize>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<Vec<ImportRecord>, NimbusError> {
+    let contents = std::fs::read_to_string(&path)
+        .map_err(|e| NimbusError::Config(format!("Failed to read {}: {}", path, e)))?;
+    let records: Vec<Value> = serde_json::from_str(&contents).map_err(|e| {
+        NimbusError::Validation(format!("{} is not a JSON array of groups: {}", path, e))
+    })?;
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let options = ImportOptions {
+        skip_existing,
+        limit: concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY),
+        actor: Some(profile),
+    };
+    let import = import_records(&client, &base_url, &token, &audit, records, &options);
+    with_request_timeout(timeout_ms, import).await
+}
+
+struct ImportOptions {
+    skip_existing: bool,
+    limit: usize,
+    actor: Option<String>,
+}
+
+/// Validates each record, then creates those not already in Nimbus
+async fn import_records(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    audit: &AuditLog,
+    records: Vec<Value>,
+    options: &ImportOptions,
+) -> Result<Vec<ImportRecord>, NimbusError> {
+    let existing = existing_descriptions(client, base_url, token).await?;
+
+    let results = run_bounded(records, options.limit, |index, record| {
+        let existing = &existing;
+        async move {
+            let (description, outcome) =
+                import_one(client, base_url, token, audit, record, existing, options).await;
+            ImportRecord {
+                index,
+                description,
+                outcome,
+            }
+        }
+    })
+    .await;
+
+    Ok(results)
+}
+
+/// Imports one record, returning its description (when readable) and outcome
+async fn import_one(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    audit: &AuditLog,
+    record: Value,
+    existing: &HashSet<String>,
+    options: &ImportOptions,
+) -> (Option<String>, ImportOutcome) {
+    let request = match serde_json::from_value::<LocationGroupRequest>(record) {
+        Ok(request) => request,
+        Err(e) => {
+            let error = NimbusError::Validation(format!("Invalid record: {}", e));
+            return (None, ImportOutcome::Failed { error });
+        }
+    };
+    let description = Some(request.description.clone());
+
+    if existing.contains(&request.description) {
+        if options.skip_existing {
+            return (description, ImportOutcome::Skipped);
+        }
+        let error = NimbusError::Validation(format!(
+            "A LocationGroup named '{}' already exists",
+            request.description
+        ));
+        return (description, ImportOutcome::Failed { error });
+    }
+
+    let result =
+        post_location_group(client, base_url, token, &request, &new_idempotency_key()).await;
+    record_create(audit, &options.actor, &result);
+    let outcome = match result {
+        Ok(location_group_id) => ImportOutcome::Created { location_group_id },
+        Err(error) => ImportOutcome::Failed { error },
+    };
+    (description, outcome)
+}
+
+/// Creates ScheduleGroups from the rows of a CSV file
+///
+/// # Arguments
+/// * `client` - Shared Nimbus HTTP client from managed state
+/// * `session` - Session state holding the stored auth token
+/// * `profiles` - Profile state the base URL is resolved from
+/// * `audit` - Audit log each create is recorded in
+/// * `path` - CSV file with a header row and the columns `description`,
+///   `location_group_id`, `start_date`, `end_date` and `learning_period`
+///   (matched ignoring case, spaces and underscores; `start`/`end` also work)
+/// * `concurrency` - Maximum creates in flight (defaults to `DEFAULT_BATCH_CONCURRENCY`)
+/// * `timeout_ms` - Per-request timeout for this call (defaults to the client's)
+/// * `profile` - Profile to send to; the active one when omitted
+///
+/// # Returns
+/// One ScheduleImportRecord per row, in file order. Dates may be `YYYY-MM-DD`
+/// or `MM/DD/YYYY` and are sent as `YYYY-MM-DD`. Each row is validated like
+/// `create_schedule_group` and its LocationGroup checked to exist before it is
+/// created; a bad row doesn't stop the rest. A missing column fails the whole
+/// import before anything is sent.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn import_schedule_groups_csv(
+    client: tauri::State<'_, NimbusClient>,
+    session: tauri::State<'_, Session>,
+    profiles: tauri::State<'_, ProfileStore>,
+    audit: tauri::State<'_, AuditLog>,
+    path: String,
+    concurrency: Option<usize>,
+    timeout_ms: Option<u64>,
+    profile: Option<String>,
+) -> Result<Vec<ScheduleImportRecord>, NimbusError> {
+    let file = std::fs::File::open(&path)
+        .map_err(|e| NimbusError::Config(format!("Failed to read {}: {}", path, e)))?;
+    let rows = read_schedule_csv(file)?;
+
+    // Resolve the target environment and credentials
+    let Target {
+        profile,
+        base_url,
+        token,
+    } = session.target(&profiles, profile.as_deref())?;
+
+    let limit = concurrency.unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+    let actor = Some(profile);
+    let import = import_schedule_rows(&client, &base_url, &token, &audit, rows, limit, actor);
+    Ok(with_request_timeout(timeout_ms, import).await)
+}
+
+/// One data row of a schedule CSV, parsed as far as it could be
+struct ScheduleCsvRow {
+    line: u64,
+    description: Option<String>,
+    request: Result<ScheduleGroupRequest, NimbusError>,
+}
+
+/// Positions of the schedule CSV columns
+struct ScheduleColumns {
+    description: usize,
+    location_group_id: usize,
+    start_date: usize,
+    end_date: usize,
+    learning_period: usize,
+}
+
+/// Parses every row of a schedule CSV; only a bad header fails the whole file
+fn read_schedule_csv(data: impl Read) -> Result<Vec<ScheduleCsvRow>, NimbusError> {
+    let mut reader = csv::Reader::from_reader(data);
+    let headers = reader
+        .headers()
+        .map_err(|e| NimbusError::Validation(format!("Invalid CSV: {}", e)))?
+        .clone();
+    let column = |names: &[&str]| {
+        headers
+            .iter()
+            .position(|header| names.contains(&header_key(header).as_str()))
+            .ok_or_else(|| NimbusError::Validation(format!("Column '{}' not found", names[0])))
+    };
+    let columns = ScheduleColumns {
+        description: column(&["description"])?,
+        location_group_id: column(&["locationgroupid"])?,
+        start_date: column(&["startdate", "start"])?,
+        end_date: column(&["enddate", "end"])?,
+        learning_period: column(&["learningperiod"])?,
+    };
+
+    Ok(reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => {
+                let cell = |index| record.get(index).unwrap_or("").trim();
+                ScheduleCsvRow {
+                    line: record.position().map_or(0, |p| p.line()),
+                    description: record
+                        .get(columns.description)
+                        .map(|value| value.trim().to_string()),
+                    request: parse_schedule_row(cell, &columns),
+                }
+            }
+            Err(e) => ScheduleCsvRow {
+                line: e.position().map_or(0, |p| p.line()),
+                description: None,
+                request: Err(NimbusError::Validation(format!("Invalid CSV row: {}", e))),
+            },
+        })
+        .collect())
+}
+
+/// A header reduced to lowercase letters and digits, so `Start Date` matches `start_date`
+fn header_key(header: &str) -> String {
+    header
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Builds a ScheduleGroupRequest from one row's cells, reporting every unreadable cell
+fn parse_schedule_row<'a>(
+    cell: impl Fn(usize) -> &'a str,
+    columns: &ScheduleColumns,
+) -> Result<ScheduleGroupRequest, NimbusError> {
+    let mut errors = Vec::new();
+
+    let location_group_id = cell(columns.location_group_id);
+    let location_group_id = location_group_id.parse::<i64>().unwrap_or_else(|_| {
+        errors.push(FieldError::new(
+            "location_group_id",
+            "invalid_location_group_id",
+            format!(
+                "location_group_id must be an integer, got '{}'",
+                location_group_id
+            ),
+        ));
+        0
+    });
+    let mut date = |field: &str, value: &str| {
+        normalize_csv_date(value).unwrap_or_else(|| {
+            errors.push(FieldError::new(
+                field,
+                "invalid_date",
+                format!(
+                    "{} must be a YYYY-MM-DD or MM/DD/YYYY date, got '{}'",
+                    field, value
+                ),
+            ));
+            String::new()
+        })
+    };
+    let start_date = date("start_date", cell(columns.start_date));
+    let end_date = date("end_date", cell(columns.end_date));
+    let learning_period = match cell(columns.learning_period).parse::<LearningPeriod>() {
+        Ok(period) => Some(period),
+        Err(e) => {
+            let message = match e {
+                NimbusError::Validation(message) => message,
+                other => other.to_string(),
+            };
+            errors.push(FieldError::new(
+                "learning_period",
+                "unknown_learning_period",
+                message,
+            ));
+            None
+        }
+    };
+
+    match learning_period {
+        Some(learning_period) if errors.is_empty() => Ok(ScheduleGroupRequest {
+            description: cell(columns.description).to_string(),
+            location_group_id,
+            start_date,
+            end_date,
+            learning_period,
+            ..ScheduleGroupRequest::default()
+        }),
+        _ => Err(errors.into()),
+    }
+}
+
+/// Reads a date in any of `CSV_DATE_FORMATS` and writes it as `YYYY-MM-DD`
+fn normalize_csv_date(value: &str) -> Option<String> {
+    CSV_DATE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(value, format).ok())
+        .map(|date| date.format(NIMBUS_DATE_FORMAT).to_string())
+}
+
+/// Creates the parsed rows, checking each one's LocationGroup first
+async fn import_schedule_rows(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+    audit: &AuditLog,
+    rows: Vec<ScheduleCsvRow>,
+    limit: usize,
+    actor: Option<String>,
+) -> Vec<ScheduleImportRecord> {
+    run_bounded(rows, limit, |_, row| {
+        let actor = &actor;
+        async move {
+            let result = match row.request {
+                Ok(request) => {
+                    let result = send_schedule_group(
+                        client,
+                        base_url,
+                        token,
+                        &request,
+                        &new_idempotency_key(),
+                        true,
+                    )
+                    .await;
+                    audit.record(
+                        AuditOperation::Create,
+                        "ScheduleGroup",
+                        result.as_ref().ok().copied(),
+                        actor.clone(),
+                        result.as_ref().err(),
+                    );
+                    result
+                }
+                Err(e) => Err(e),
+            };
+            let outcome = match result {
+                Ok(schedule_group_id) => ScheduleImportOutcome::Created { schedule_group_id },
+                Err(error) => ScheduleImportOutcome::Failed { error },
+            };
+            ScheduleImportRecord {
+                line: row.line,
+                description: row.description,
+                outcome,
+            }
+        }
+    })
+    .await
+}
+
+/// Descriptions of every LocationGroup currently in Nimbus
+async fn existing_descriptions(
+    client: &NimbusClient,
+    base_url: &str,
+    token: &str,
+) -> Result<HashSet<String>, NimbusError> {
+    all_pages(|page| fetch_location_group_page(client, base_url, token, page, EXPORT_PAGE_SIZE))
+        .map_ok(|group| group.description)
+        .try_collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::client::NimbusConfig;
+    use crate::commands::executor::MockExecutor;
+    use serde_json::json;
+
+    fn group_page(ids: &[i64], total: u64) -> String {
+        let items: Vec<_> = ids
+            .iter()
+            .map(|id| {
+                json!({
+                    "LocationGroupID": id,
+                    "Description": format!("Group, {}", id),
+                    "Active": true,
+                    "Locations": [{"LocationID": 100 + id}, {"LocationID": 200 + id}]
+                })
+            })
+            .collect();
+        json!({"Items": items, "TotalCount": total}).to_string()
+    }
+
+    #[test]
+    fn test_read_location_ids_dedupes_and_skips_blanks() {
+        let csv = "Name,Location ID\nNorth,101\nSouth, 102\nEmpty,\nNorth again,101\n";
+        let ids = read_location_ids_csv(csv.as_bytes(), "Location ID").unwrap();
+        assert_eq!(ids, vec![101, 102]);
+    }
+
+    #[test]
+    fn test_read_location_ids_reports_bad_rows() {
+        let csv = "LocationID\n101\nabc\n102\n1.5\n";
+        let err = read_location_ids_csv(csv.as_bytes(), "LocationID").unwrap_err();
+        let message = err.to_string();
+        assert!(message.contains("row 3 ('abc')"), "{}", message);
+        assert!(message.contains("row 5 ('1.5')"), "{}", message);
+
+        let err = read_location_ids_csv(csv.as_bytes(), "Missing").unwrap_err();
+        assert!(matches!(err, NimbusError::Validation(_)));
+    }
+
+    #[tokio::test]
+    async fn test_export_pages_through_all_groups() {
+        let mock = MockExecutor::new();
+        mock.respond(200, &group_page(&[1, 2], 3))
+            .respond(200, &group_page(&[3], 3));
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let mut out = Vec::new();
+        let count = write_location_groups(
+            &client,
+            "https://nimbus.test",
+            "tok",
+            ExportFormat::Csv,
+            &mut out,
+            2,
+        )
+        .await
+        .ok()
+        .unwrap();
+        assert_eq!(count, 3);
+        assert_eq!(mock.requests().len(), 2);
+
+        let csv = String::from_utf8(out).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("location_group_id,description,active,location_ids")
+        );
+        assert_eq!(lines.next(), Some(r#"1,"Group, 1",true,101;201"#));
+    }
+
+    #[tokio::test]
+    async fn test_export_json_is_a_valid_array() {
+        let mock = MockExecutor::new();
+        mock.respond(200, &group_page(&[1, 2], 2));
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let mut out = Vec::new();
+        write_location_groups(
+            &client,
+            "https://nimbus.test",
+            "tok",
+            ExportFormat::Json,
+            &mut out,
+            EXPORT_PAGE_SIZE,
+        )
+        .await
+        .ok()
+        .unwrap();
+
+        let exported: Vec<LocationGroupDetail> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(exported[1].location_ids, vec![102, 202]);
+    }
+
+    #[tokio::test]
+    async fn test_failed_json_export_leaves_the_old_file() {
+        let dir = std::env::temp_dir().join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("groups.json");
+        std::fs::write(&path, "old export").unwrap();
+        let path = path.to_str().unwrap();
+
+        let mock = MockExecutor::new();
+        mock.respond(200, &group_page(&[1], 3))
+            .respond(400, r#"{"Message": "Paging broke"}"#)
+            .respond(200, &group_page(&[1, 2], 2));
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+        let export = |page_size| {
+            export_to_path(
+                &client,
+                "https://nimbus.test",
+                "tok",
+                ExportFormat::Json,
+                path,
+                page_size,
+            )
+        };
+
+        assert!(export(1).await.is_err());
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "old export");
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+
+        assert_eq!(export(EXPORT_PAGE_SIZE).await.unwrap(), 2);
+        let exported: Vec<LocationGroupDetail> =
+            serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(exported.len(), 2);
+        assert_eq!(std::fs::read_dir(&dir).unwrap().count(), 1);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_export_json_lines_keeps_complete_records_on_failure() {
+        let mock = MockExecutor::new();
+        mock.respond(200, &group_page(&[1, 2], 5))
+            .respond(200, &group_page(&[3, 4], 5))
+            .respond(400, r#"{"Message": "Paging broke"}"#);
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        let mut out = Vec::new();
+        let result = write_location_groups(
+            &client,
+            "https://nimbus.test",
+            "tok",
+            ExportFormat::JsonLines,
+            &mut out,
+            2,
+        )
+        .await;
+        assert!(matches!(result, Err(ExportError::Nimbus(_))));
+
+        let jsonl = String::from_utf8(out).unwrap();
+        assert!(jsonl.ends_with('\n'));
+        let exported: Vec<LocationGroupDetail> = jsonl
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let ids: Vec<_> = exported.iter().map(|g| g.location_group_id).collect();
+        assert_eq!(ids, vec![1, 2, 3, 4]);
+        assert_eq!(
+            serde_json::from_str::<ExportFormat>(r#""jsonl""#).unwrap(),
+            ExportFormat::JsonLines
+        );
+    }
+
+    #[test]
+    fn test_schedule_csv_rows_report_their_line_and_normalized_dates() {
+        let csv = "Description,Location Group ID,Start,End,Learning Period\n\
+                   Spring,5,03/01/2025,2025-06-30,30\n\
+                   \"Summer\nIntake\",5,7/1/2025,08/31/2025,Term\n\
+                   Broken,five,31/12/2025,2025-13-01,\n";
+        let rows = read_schedule_csv(csv.as_bytes()).unwrap();
+        assert_eq!(rows.iter().map(|r| r.line).collect::<Vec<_>>(), [2, 3, 5]);
+
+        let request = rows[0].request.as_ref().unwrap();
+        assert_eq!(request.location_group_id, 5);
+        assert_eq!(request.start_date, "2025-03-01");
+        assert_eq!(request.end_date, "2025-06-30");
+        assert_eq!(rows[1].request.as_ref().unwrap().start_date, "2025-07-01");
+        assert_eq!(
+            rows[1].request.as_ref().unwrap().learning_period,
+            LearningPeriod::Term
+        );
+
+        // Every bad cell is named
+        let err = rows[2].request.as_ref().unwrap_err().to_string();
+        for field in [
+            "location_group_id",
+            "start_date",
+            "end_date",
+            "learning_period",
+        ] {
+            assert!(err.contains(field), "{}", err);
+        }
+        assert_eq!(rows[2].description.as_deref(), Some("Broken"));
+
+        let missing = read_schedule_csv("description,start,end\n".as_bytes());
+        assert!(missing.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_schedule_csv_import_checks_references() {
+        let csv = "description,location_group_id,start_date,end_date,learning_period\n\
+                   Spring,5,03/01/2025,06/30/2025,30\n\
+                   Backwards,5,2025-06-30,2025-03-01,30\n\
+                   Orphan,6,2025-03-01,2025-06-30,60\n";
+        let rows = read_schedule_csv(csv.as_bytes()).unwrap();
+        let audit_path = std::env::temp_dir()
+            .join(uuid::Uuid::new_v4().to_string())
+            .join("audit.jsonl");
+        let audit = AuditLog::new(&audit_path);
+
+        let mock = MockExecutor::new();
+        mock.respond(200, r#"{"LocationGroupID": 5, "Description": "West"}"#)
+            .respond(201, r#"{"ScheduleGroupID": 90}"#)
+            .respond(404, "");
+        let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+
+        // Limit 1 so the stub responses are consumed in file order
+        let results =
+            import_schedule_rows(&client, "https://nimbus.test", "tok", &audit, rows, 1, None)
+                .await;
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json[0]["line"], 2);
+        assert_eq!(json[0]["status"], "created");
+        assert_eq!(json[0]["schedule_group_id"], 90);
+        assert_eq!(json[1]["line"], 3);
+        assert_eq!(json[1]["error"]["kind"], "validation");
+        assert_eq!(json[2]["line"], 4);
+        assert_eq!(json[2]["status"], "failed");
+        assert!(matches!(
+            results[2].outcome,
+            ScheduleImportOutcome::Failed {
+                error: NimbusError::ReferencedEntityNotFound { id: 6, .. }
+            }
+        ));
+
+        // The backwards row never reached the server
+        let requests = mock.requests();
+        assert_eq!(requests.len(), 3);
+        let body = requests[1].body.as_ref().unwrap();
+        assert_eq!(body["GroupStartDate"], "2025-03-01");
+        assert_eq!(body["GroupEndDate"], "2025-06-30");
+    }
+
+    #[tokio::test]
+    async fn test_import_skips_or_fails_existing_groups() {
+        let records = vec![
+            json!({"description": "Group, 1", "location_ids": [101]}),
+            json!({"description": "New", "location_ids": [301], "location_group_id": 9}),
+            json!({"description": "No locations"}),
+        ];
+        let audit_path = std::env::temp_dir()
+            .join(uuid::Uuid::new_v4().to_string())
+            .join("audit.jsonl");
+        let audit = AuditLog::new(&audit_path);
+
+        for skip_existing in [true, false] {
+            let mock = MockExecutor::new();
+            mock.respond(200, &group_page(&[1], 1))
+                .respond(201, r#"{"LocationGroupID": 42}"#);
+            let client = NimbusClient::with_executor(NimbusConfig::default(), mock.clone());
+            let options = ImportOptions {
+                skip_existing,
+                limit: 2,
+                actor: None,
+            };
+
+            let results = import_records(
+                &client,
+                "https://nimbus.test",
+                "tok",
+                &audit,
+                records.clone(),
+                &options,
+            )
+            .await
+            .unwrap();
+            assert_eq!(
+                skip_existing,
+                matches!(results[0].outcome, ImportOutcome::Skipped)
+            );
+            assert!(matches!(
+                results[1].outcome,
+                ImportOutcome::Created {
+                    location_group_id: 42
+                }
+            ));
+            assert!(matches!(results[2].outcome, ImportOutcome::Failed { .. }));
+            assert_eq!(results[2].description, None);
+            assert_eq!(mock.requests().len(), 2);
+        }
+    }
+}